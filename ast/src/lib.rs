@@ -28,13 +28,16 @@ impl For {
 pub struct If {
     pub expr: Box<Expr>,
     pub body: Box<Expr>,
+    // either another If for `else if`, or the final `else` body
+    pub else_body: Option<Box<Expr>>,
 }
 
 impl If {
-    pub fn new(if_expr: Box<Expr>, if_body: Box<Expr>) -> Self {
+    pub fn new(if_expr: Box<Expr>, if_body: Box<Expr>, else_body: Option<Box<Expr>>) -> Self {
         If {
             expr: if_expr,
             body: if_body,
+            else_body,
         }
     }
 }
@@ -99,12 +102,13 @@ impl FileAll {
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct UndefBubble {
+    pub span: Lexeme,
     pub prev: Box<Expr>,
 }
 
 impl UndefBubble {
-    pub fn new(prev: Box<Expr>) -> Self {
-        UndefBubble { prev }
+    pub fn new(span: Lexeme, prev: Box<Expr>) -> Self {
+        UndefBubble { span, prev }
     }
 }
 
@@ -158,10 +162,11 @@ impl PropAccess {
 #[derive(Debug, Clone, PartialEq)]
 pub struct ArgDef {
     pub ident: Box<Expr>,
-    pub typ: Box<Expr>,
+    // anonymous functions can leave the type off and have it inferred
+    pub typ: Option<Box<Expr>>,
 }
 impl ArgDef {
-    pub fn new(ident: Box<Expr>, typ: Box<Expr>) -> Self {
+    pub fn new(ident: Box<Expr>, typ: Option<Box<Expr>>) -> Self {
         ArgDef { ident, typ }
     }
 }
@@ -437,14 +442,24 @@ impl TraitDecl {
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct CatchDecl {
+    pub span: Lexeme,
+    pub prev: Box<Expr>,
     pub args: Option<Vec<Box<Expr>>>,
-    pub ret_typ: Box<Expr>,
+    pub ret_typ: Option<Box<Expr>>,
     pub block: Box<Expr>,
 }
 
 impl CatchDecl {
-    pub fn new(args: Option<Vec<Box<Expr>>>, ret_typ: Box<Expr>, block: Box<Expr>) -> Self {
+    pub fn new(
+        span: Lexeme,
+        prev: Box<Expr>,
+        args: Option<Vec<Box<Expr>>>,
+        ret_typ: Option<Box<Expr>>,
+        block: Box<Expr>,
+    ) -> Self {
         CatchDecl {
+            span,
+            prev,
             args,
             ret_typ,
             block,
@@ -484,12 +499,14 @@ impl FuncType {
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct FuncDecl {
+    // one of pub, export, or extern
     pub visibility: Option<Lexeme>,
     pub mutability: Lexeme,
     pub identifier: Box<Expr>,
     pub args: Option<Vec<Box<Expr>>>,
     pub ret_typ: Box<Expr>,
-    pub block: Box<Expr>,
+    // extern functions are defined elsewhere and have no block
+    pub block: Option<Box<Expr>>,
     pub sig: Option<Box<Expr>>,
 }
 
@@ -500,7 +517,7 @@ impl FuncDecl {
         identifier: Box<Expr>,
         args: Option<Vec<Box<Expr>>>,
         ret_typ: Box<Expr>,
-        block: Box<Expr>,
+        block: Option<Box<Expr>>,
         sig: Option<Box<Expr>>,
     ) -> Self {
         FuncDecl {
@@ -528,10 +545,11 @@ impl Block {
 #[derive(Debug, Clone, PartialEq)]
 pub struct RetOp {
     pub span: Lexeme,
-    pub expr: Box<Expr>,
+    // a bare `return` or `break` has no value
+    pub expr: Option<Box<Expr>>,
 }
 impl RetOp {
-    pub fn new(span: Lexeme, expr: Box<Expr>) -> Self {
+    pub fn new(span: Lexeme, expr: Option<Box<Expr>>) -> Self {
         RetOp { span, expr }
     }
 }
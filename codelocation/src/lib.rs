@@ -1,73 +1,134 @@
-use lexer::*;
-use logos::Lexer;
-use token::Token;
+use std::ops::Range;
 
+pub type FileId = usize;
+
+// where something is in a file, lines and cols start at 1. end is just past the last character.
+// cols count characters, not bytes, so they match what an editor shows
 #[derive(Debug, Clone, PartialEq)]
-pub struct CodeLocation {
-    pub val: String,
+pub struct Span {
+    pub file: FileId,
     pub line: usize,
-    pub code: String,
     pub col: usize,
+    pub end_line: usize,
+    pub end_col: usize,
 }
 
-impl<'s> CodeLocation {
-    pub fn new_lexer_stop_point(lexer: &Lexer<Token>) -> Self {
-        let chars = lexer
-            .source()
-            .get(0..lexer.span().end)
-            .unwrap_or_else(|| "");
-        let val = lexer
-            .source()
-            .get(lexer.span().start..lexer.span().end)
-            .unwrap_or_else(|| "(empty)");
-        let mut lines = chars.split('\n').collect::<Vec<&str>>();
-        let mut code = "".to_string();
-        let mut col = 0;
-        if lines.len() > 0 {
-            code.push_str(lines.pop().unwrap());
-            for x in lines.iter() {
-                for _ in x.bytes() {
-                    col += 1;
-                }
-            }
-        } else {
-            code.push_str(lexer.source());
-        }
+// a file and where each of its lines start, so finding the line of an offset is a binary search
+// instead of splitting the whole file again
+#[derive(Debug, Clone)]
+pub struct SourceFile {
+    pub id: FileId,
+    pub name: String,
+    src: String,
+    line_starts: Vec<usize>,
+}
 
-        return CodeLocation {
-            val: val.to_string(),
-            line: lines.len() + 1,
-            code,
-            col: lexer.span().start - col - lines.len() + 1,
+impl SourceFile {
+    pub fn new(id: FileId, name: &str, src: &str) -> Self {
+        let mut line_starts = vec![0];
+        line_starts.extend(src.match_indices('\n').map(|(i, _)| i + 1));
+        SourceFile {
+            id,
+            name: name.to_string(),
+            src: src.to_string(),
+            line_starts,
+        }
+    }
+    pub fn position(&self, offset: usize) -> (usize, usize) {
+        let offset = offset.min(self.src.len());
+        let line = self.line_starts.partition_point(|x| *x <= offset);
+        let start = self.line_starts[line - 1];
+        let col = match self.src.get(start..offset) {
+            Some(x) => x.chars().count(),
+            None => offset - start,
         };
+        (line, col + 1)
+    }
+    pub fn span(&self, range: &Range<usize>) -> Span {
+        let (line, col) = self.position(range.start);
+        let (end_line, end_col) = self.position(range.end);
+        Span {
+            file: self.id,
+            line,
+            col,
+            end_line,
+            end_col,
+        }
+    }
+    // from the start of the line range begins on, up to where it ends
+    pub fn code(&self, range: &Range<usize>) -> &str {
+        let (line, _) = self.position(range.start);
+        let end = range.end.min(self.src.len());
+        self.src.get(self.line_starts[line - 1]..end).unwrap_or("")
+    }
+    // the text of a line, without its newline
+    pub fn line(&self, line: usize) -> &str {
+        let start = self.line_starts[line - 1];
+        let end = match self.line_starts.get(line) {
+            Some(x) => x - 1,
+            None => self.src.len(),
+        };
+        self.src.get(start..end).unwrap_or("")
+    }
+    pub fn slice(&self, range: &Range<usize>) -> &str {
+        self.src.get(range.clone()).unwrap_or("(empty)")
     }
 }
 
-impl<'s> CodeLocation {
-    pub fn new(raw: &'s str, lexeme: Lexeme) -> Self {
-        let chars = raw.get(0..lexeme.span.end).unwrap_or_else(|| "");
-        let val = raw
-            .get(lexeme.span.start..lexeme.span.end)
-            .unwrap_or_else(|| "(empty)");
-        let mut lines = chars.split('\n').collect::<Vec<&str>>();
-        let mut code = "".to_string();
-        let mut col = 0;
-        if lines.len() > 0 {
-            code.push_str(lines.pop().unwrap());
-            for x in lines.iter() {
-                for _ in x.bytes() {
-                    col += 1;
-                }
-            }
-        } else {
-            code.push_str(raw);
-        }
+// every file read for a build, diagnostics name their file by its id
+#[derive(Debug, Default)]
+pub struct SourceMap {
+    files: Vec<SourceFile>,
+}
 
-        return CodeLocation {
-            val: val.to_string(),
-            line: lines.len() + 1,
-            code,
-            col: lexeme.span.start - col - lines.len() + 1,
-        };
+impl SourceMap {
+    pub fn new() -> Self {
+        SourceMap::default()
+    }
+    pub fn add(&mut self, name: &str, src: &str) -> FileId {
+        let id = self.files.len();
+        self.files.push(SourceFile::new(id, name, src));
+        id
+    }
+    pub fn get(&self, id: FileId) -> Option<&SourceFile> {
+        self.files.get(id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    fn it_should_find_lines_and_cols() {
+        let mut map = SourceMap::new();
+        let id = map.add("a.ty", "const x = 5\nconst yy = \"ab\ncd\"\n");
+        let file = map.get(id).unwrap();
+        assert_eq!(
+            file.span(&(18..20)),
+            Span {
+                file: id,
+                line: 2,
+                col: 7,
+                end_line: 2,
+                end_col: 9,
+            }
+        );
+        assert_eq!(file.code(&(18..20)), "const yy");
+        assert_eq!(file.line(2), "const yy = \"ab");
+        assert_eq!(file.line(4), "");
+        assert_eq!(file.span(&(23..30)).end_line, 3);
+        assert_eq!(file.position(0), (1, 1));
+        assert_eq!(file.position(100), (4, 1));
+    }
+    #[test]
+    fn it_should_count_cols_in_chars() {
+        let file = SourceFile::new(
+            0,
+            "a.ty",
+            "const é = 'ü' + x
+",
+        );
+        assert_eq!(file.position(8), (1, 8));
+        assert_eq!(file.span(&(18..19)).col, 17);
     }
 }
@@ -2,25 +2,51 @@ use lexer::TLexer;
 use linker::link;
 use linter::LintSource;
 use parser::Parser;
+use std::fs::create_dir_all;
+use std::fs::write;
 use std::fs::File;
 use std::io::Read;
 
 use std::path::Path;
 use std::process::Command;
 
-fn main() {
-    println!("[run] simple exe");
-    objmaker::from_buffer(
-        "const m = 7
-        pub const main = fn() usize { 
-            const x = 5 
-            return x + m
-        }",
-        Path::new("main.ty"),
-    );
-    let input = Path::new(".ty/main.o").to_path_buf();
+fn run_exe(title: &str, src: &str, code: i32) {
+    run_linked(title, &[("main", src)], code);
+}
+
+// each source is its own object, all of them are linked together
+fn run_linked(title: &str, srcs: &[(&str, &str)], code: i32) {
+    println!("[run] {}", title);
+    for (name, src) in srcs.iter() {
+        objmaker::from_buffer(src, &Path::new(name).with_extension("ty"));
+    }
+    run_objects(title, srcs, code);
+}
+
+// the sources are written out as files of one directory, so they can import each other
+fn run_modules(title: &str, srcs: &[(&str, &str)], code: i32) {
+    println!("[run] {}", title);
+    let root = Path::new(".ty/modules");
+    create_dir_all(root).unwrap();
+    let mut paths = vec![];
+    // a.b is written to a/b.ty
+    for (name, src) in srcs.iter() {
+        let path = root.join(name.replace('.', "/")).with_extension("ty");
+        create_dir_all(path.parent().unwrap()).unwrap();
+        write(&path, src).unwrap();
+        paths.push(path);
+    }
+    objmaker::from_files(paths.iter().collect());
+    run_objects(title, srcs, code);
+}
+
+fn run_objects(title: &str, srcs: &[(&str, &str)], code: i32) {
+    let inputs: Vec<_> = srcs
+        .iter()
+        .map(|(name, _)| Path::new(".ty").join(format!("{}.o", name)))
+        .collect();
     let output = Path::new(".ty/main").to_path_buf();
-    link(vec![&input], &output);
+    link(inputs.iter().collect(), &output);
     let output = Command::new(".ty/main")
         .args(&[""])
         .spawn()
@@ -29,8 +55,675 @@ fn main() {
         .expect("expected child to finish")
         .code();
 
-    assert!(output == Some(12));
-    println!("  [ok] simple exe success!");
+    assert!(output == Some(code));
+    println!("  [ok] {} success!", title);
+}
+
+fn main() {
+    run_exe(
+        "simple exe",
+        "const m = 7
+        pub const main = fn() usize { 
+            const x = 5 
+            return x + m
+        }",
+        12,
+    );
+    run_exe(
+        "if else exe",
+        "pub const main = fn() usize { 
+            const x = 3
+            let y = 1
+            if (x > 2) fn(v) void {
+                y = y + 1
+            }
+            const z = if (x > 3) {
+                break 10
+            } else if (x == 3) {
+                break 20
+            } else {
+                break 30
+            }
+            return z + y
+        }",
+        22,
+    );
+    run_exe(
+        "shadowing exe",
+        "pub const main = fn() usize { 
+            const x = 5
+            let y = 0
+            if (x > 1) {
+                const x = 30
+                y = x
+            }
+            return x + y
+        }",
+        35,
+    );
+    run_exe(
+        "match exe",
+        "const Day = enum
+          | monday
+          | tuesday
+          | wednesday
+        const score = fn(d: Day) usize {
+            return match (d) {
+                Day.monday => 1
+                Day.tuesday => 10
+                _ => 100
+            }
+        }
+        const next = fn(d: Day) Day {
+            return match (d) {
+                Day.monday => Day.tuesday
+                Day.tuesday => Day.wednesday
+                Day.wednesday => Day.monday
+            }
+        }
+        const digit = fn(x: usize) usize {
+            let total = 0
+            match (x) {
+                3 => {
+                    total = 30
+                }
+                _ => {
+                    total = 2
+                }
+            }
+            return total
+        }
+        pub const main = fn() usize {
+            return score(Day.tuesday) + score(next(Day.wednesday)) + digit(3) + digit(4)
+        }",
+        43,
+    );
+    run_exe(
+        "enum exe",
+        "const Status = enum(u16)
+          | ok = 200
+          | created
+          | missing = 404
+        const code = fn(s: Status) usize {
+            return match (s) {
+                Status.created => 1
+                Status.missing => 2
+                _ => 3
+            }
+        }
+        pub const main = fn() usize {
+            const s = Status.missing
+            return ((Status.created as usize) - 200) + ((s as usize) - 400) + code(s) + code(Status.ok)
+        }",
+        10,
+    );
+    run_exe(
+        "narrow enums exe",
+        "const Small = enum(u8)
+          | a = 1
+          | b = 7
+        const Code = enum(u16)
+          | ok = 200
+          | missing = 404
+        const Reply = struct {
+            small: Small,
+            code: Code,
+            size: u8,
+        }
+        let count: u8 = 2
+        pub const main = fn() usize {
+            let r = Reply { small: Small.b, code: Code.ok, size: 3 }
+            r.code = Code.missing
+            count += 1
+            const smalls = [Small.a, Small.b, Small.a]
+            let last = smalls[2]
+            for (smalls) fn(s) void {
+                last = s
+            }
+            return (r.small as usize) + ((r.code as usize) - 400) + (r.size as usize) + (count as usize) + (last as usize) + (smalls[1] as usize)
+        }",
+        25,
+    );
+    run_exe(
+        "tags exe",
+        "const Shape = tag
+          | circle: u64
+          | square: u8
+          | none
+        const area = fn(s: Shape) u64 {
+            return match (s) {
+                Shape.circle(r) => 3 * r * r
+                Shape.square(w) => (w as u64) * (w as u64)
+                Shape.none => 0
+            }
+        }
+        const sides = fn(s: Shape) u64 {
+            return match (s) {
+                Shape.square(_) => 4
+                _ => 0
+            }
+        }
+        pub const main = fn() u64 {
+            const c = Shape.circle(2)
+            return area(c) + area(Shape.square(3)) + area(Shape.none) + sides(Shape.square(1))
+        }",
+        25,
+    );
+    run_exe(
+        "errors exe",
+        "const Day = enum
+          | monday
+          | tuesday
+          | wednesday
+          | thursday
+          | friday
+          | saturday
+          | sunday
+        type InvalidWeekday = error
+        const assert_weekday = fn(d: Day) InvalidWeekday!Day {
+            return match (d) {
+                Day.saturday => InvalidWeekday
+                Day.sunday => InvalidWeekday
+                _ => d
+            }
+        }
+        const checked = fn(d: Day) InvalidWeekday!Day {
+            const day = try assert_weekday(d)
+            return day
+        }
+        const weekday_or = fn(d: Day, fallback: Day) Day {
+            return assert_weekday(d) catch (e) {
+                return fallback
+            }
+        }
+        const score = fn(d: Day) u64 {
+            return match (d) {
+                Day.monday => 1
+                Day.friday => 5
+                _ => 10
+            }
+        }
+        pub const main = fn() u64 {
+            const a = weekday_or(Day.sunday, Day.monday)
+            const b = weekday_or(Day.friday, Day.monday)
+            const c = checked(Day.saturday) catch () Day {
+                return Day.friday
+            }
+            const d = checked(Day.monday) catch () {
+                return Day.friday
+            }
+            return score(a) + score(b) + score(c) * 10 + score(d)
+        }",
+        57,
+    );
+    run_exe(
+        "optionals exe",
+        "const pred = fn(x: u64) ?u64 {
+            if (x == 0) {
+                return undefined
+            }
+            return x - 1
+        }
+        const pred2 = fn(x: u64) ?u64 {
+            const y = pred(x)?
+            return pred(y)
+        }
+        const or_else = fn(x: ?u64, fallback: u64) u64 {
+            if (x) {
+                return x
+            }
+            return fallback
+        }
+        type Point = struct {
+            x: u64,
+        }
+        const x_or_zero = fn(p: ?&Point) u64 {
+            if (p) {
+                return p.x
+            }
+            return 0
+        }
+        pub const main = fn() u64 {
+            const a = or_else(pred2(5), 9)
+            const b = or_else(pred2(1), 9)
+            const c = or_else(pred2(0), 20)
+            let d: ?u64 = undefined
+            const e = or_else(d, 30)
+            d = 7
+            const f = or_else(d, 30)
+            const p = Point { x: 100 }
+            const g = x_or_zero(&p) + x_or_zero(undefined)
+            return a + b + c + e + f + g
+        }",
+        169,
+    );
+    run_exe(
+        "loops exe",
+        "pub const main = fn() usize { 
+            let total = 0
+            for (0..5) fn(i) void {
+                total += i
+            }
+            const arr = [1, 2, 3]
+            for (arr) fn(x) void {
+                if (x == 2) {
+                    break
+                }
+                total += x
+            }
+            let n = 0
+            while (n != 3) {
+                n += 1
+            }
+            return total + n
+        }",
+        14,
+    );
+    run_exe(
+        "calls exe",
+        "const add = fn(x: usize, y: usize) usize {
+            return x + y
+        }
+        pub const main = fn() usize { 
+            const z = add(2, 3)
+            return double(z) + add(z, 1)
+        }
+        const double = fn(x: usize) usize {
+            return add(x, x)
+        }",
+        16,
+    );
+    run_exe(
+        "typed signatures exe",
+        "const scale = fn(x: f64, y: f64) f64 {
+            return x * y + 0.5
+        }
+        const small = fn(x: u8) u8 {
+            return x + 1
+        }
+        const wide = fn(x: i32) i64 {
+            return x as i64 - 10
+        }
+        const nothing = fn() void {
+            return
+        }
+        pub const main = fn() usize { 
+            nothing()
+            const big = if (scale(2.0, 3.0) > 6.0) {
+                break 1
+            } else {
+                break 0
+            }
+            return small(4) as usize + wide(20) as usize + big
+        }",
+        16,
+    );
+    run_exe(
+        "narrow and wide numbers exe",
+        "const half = fn(x: f32) f32 {
+            return x * 0.5
+        }
+        const widen = fn(x: i8, y: i16) i32 {
+            return x + y
+        }
+        pub const main = fn() usize { 
+            const a: f32 = 3.0
+            let b: f32 = half(a) + 1.25
+            b = b * 4
+            const small: i8 = -3
+            return b as usize + widen(small, 20) as usize
+        }",
+        28,
+    );
+    run_exe(
+        "float reassign exe",
+        "pub const main = fn() usize {
+            let f: f64 = 2.5
+            f += 1.5
+            f -= 0.5
+            return f * 2 as usize
+        }",
+        7,
+    );
+    run_exe(
+        "compound reassign exe",
+        "pub const main = fn() usize {
+            let b: u64 = 2
+            b *= 3
+            b <<= 2
+            b /= 5
+            b %= 3
+            b |= 6
+            b ^= 2
+            b >>= 1
+            let i: i64 = -9
+            i /= 2
+            i >>= 1
+            let r: i64 = -7
+            r %= 3
+            const signed = i + r + 20
+            return b as usize + signed as usize
+        }",
+        19,
+    );
+    run_exe(
+        "extern exe",
+        "extern const abs = fn(x: i32) i32
+        export const dist = fn(x: i32) i32 {
+            return abs(x - 10)
+        }
+        pub const main = fn() usize { 
+            return dist(3) as usize
+        }",
+        7,
+    );
+    run_exe(
+        "structs exe",
+        "const Point = struct {
+            x: u8,
+            y: u64,
+        }
+        const Line = struct {
+            start: Point,
+            len: u32,
+        }
+        const sum = fn(self: Point) u64 {
+            return self.x + self.y
+        }
+        pub const main = fn() usize { 
+            let p = Point { x: 2, y: 3 }
+            p.y = 10
+            p.y += 1
+            const l = Line { start: copy p, len: 5 }
+            p.x = 100
+            return sum(l.start) as usize + l.len as usize
+        }",
+        18,
+    );
+
+    run_exe(
+        "arrays exe",
+        "const greeting: [char] = \"Hello There\"
+        const Named = struct {
+            name: [char],
+            age: usize,
+        }
+        const count_spaces = fn(to_check: &[&char]) usize {
+            let count = 0
+            for (to_check) fn(x) void {
+                if (x == ' ') {
+                    count += 1
+                }
+            }
+            return count
+        }
+        pub const main = fn() usize { 
+            let nums = [5, 6, 7]
+            nums[0] = 1
+            nums[2] += 10
+            const n = Named { name: \"a b\", age: 3 }
+            const s = \"a b c\"
+            const spaces = count_spaces(&s) + count_spaces(greeting) + count_spaces(n.name)
+            return spaces + nums[0] + nums[2] + n.age + greeting[4] as usize - 100
+        }",
+        36,
+    );
+
+    run_exe(
+        "global data exe",
+        "const Point = struct {
+            x: u8,
+            y: usize,
+            name: [char],
+        }
+        const flag = true
+        const ratio = 2.5
+        const letter = 'a'
+        const base = 4 * 3 - 2
+        const derived = base + 1
+        let counter = 1
+        const origin = Point { x: 3, y: 4, name: \"pt\" }
+        const nums = [1, 2, 3]
+        const bump = fn() void {
+            counter += 5
+        }
+        pub const main = fn() usize { 
+            bump()
+            let total = counter + derived + origin.x + origin.y + nums[2]
+            if (flag) {
+                total += 1
+            }
+            total += letter as usize - 97 + origin.name[1] as usize - 116
+            return total + ratio * 2 as usize
+        }",
+        33,
+    );
+
+    run_exe(
+        "arrays of structs exe",
+        "const Point = struct {
+            x: usize,
+            y: u8,
+        }
+        const points = [Point { x: 1, y: 2 }, Point { x: 3, y: 4 }]
+        let moved = [Point { x: 5, y: 6 }, Point { x: 7, y: 8 }]
+        pub const main = fn() usize {
+            let local = [Point { x: 9, y: 10 }, Point { x: 11, y: 12 }]
+            local[0] = Point { x: 13, y: 1 }
+            moved[1].x = 20
+            return points[1].y + local[0].x + local[1].y + moved[1].x + moved[0].y
+        }",
+        55,
+    );
+
+    run_exe(
+        "writes within const globals exe",
+        "const Named = struct {
+            age: usize,
+            name: [char],
+        }
+        const greeting: [char] = \"Hello There\"
+        const fixed: [const char] = \"Hello\"
+        const named = Named { age: 1, name: \"ab\" }
+        pub const main = fn() usize {
+            greeting[0] = 'h'
+            named.age = 7
+            named.name[0] = 'c'
+            return named.age + greeting[0] as usize - 104 + named.name[0] as usize - 99 + fixed[4] as usize - 111
+        }",
+        7,
+    );
+
+    run_exe(
+        "loops over structs exe",
+        "const Point = struct {
+            x: u64,
+            y: u64,
+        }
+        pub const main = fn() u64 {
+            const ps = [Point { x: 1, y: 2 }, Point { x: 3, y: 4 }]
+            let total = 0
+            for (ps) fn(p) void {
+                total += p.x * p.y
+            }
+            return total
+        }",
+        14,
+    );
+
+    run_exe(
+        "strings as elements exe",
+        "const words = [\"ab\", \"cde\"]
+        pub const main = fn() usize {
+            const s = [\"ab\", \"cde\"]
+            let total = 0
+            for (s) fn(w) void {
+                total += w.len + w[0] as usize
+            }
+            for (words) fn(w) void {
+                total += w.len
+            }
+            return total + s[1].len + words[1][2] as usize - 101
+        }",
+        209,
+    );
+
+    run_linked(
+        "private symbols exe",
+        &[
+            (
+                "helper",
+                "const helper = fn() usize {
+                    return 1
+                }
+                const twice = 2
+                export const other = fn() usize {
+                    return helper() + twice
+                }",
+            ),
+            (
+                "main",
+                "extern const other = fn() usize
+                const twice = 20
+                const helper = fn() usize {
+                    return 39
+                }
+                pub const main = fn() usize { 
+                    return helper() + other()
+                }",
+            ),
+        ],
+        42,
+    );
+
+    run_modules(
+        "imports exe",
+        &[
+            (
+                "main",
+                "const Point = import \"shapes.Point\"
+                const total = import \"shapes.sum\"
+                const base = import \"shapes.base\"
+                const helper = fn() usize {
+                    return 1
+                }
+                pub const main = fn() usize { 
+                    const p = Point { x: 4, y: 6 }
+                    return total(p) + base + helper()
+                }",
+            ),
+            (
+                "shapes",
+                "pub const Point = struct {
+                    x: usize,
+                    y: usize,
+                }
+                pub let base = 30
+                const helper = fn() usize {
+                    return 1
+                }
+                pub const sum = fn(p: Point) usize {
+                    return p.x + p.y + helper()
+                }",
+            ),
+        ],
+        42,
+    );
+
+    run_modules(
+        "pub names exe",
+        &[
+            (
+                "main",
+                "const left = import \"north.value\"
+                const right = import \"south.value\"
+                const base = import \"south.base\"
+                pub const main = fn() usize {
+                    return left() + right() + base
+                }",
+            ),
+            (
+                "north",
+                "pub const base = 10
+                pub const value = fn() usize {
+                    return base
+                }",
+            ),
+            (
+                "south",
+                "pub const base = 30
+                pub const value = fn() usize {
+                    return 2
+                }",
+            ),
+        ],
+        42,
+    );
+
+    run_modules(
+        "imported structs exe",
+        &[
+            (
+                "main",
+                "const Shape = import \"shapes.Point\"
+                const Rect = import \"shapes.Rect\"
+                const width = import \"shapes.width\"
+                const Point = struct {
+                    z: usize,
+                }
+                pub const main = fn() usize {
+                    const local = Point { z: 2 }
+                    const r = Rect { a: Shape { x: 1, y: 2 }, b: Shape { x: 11, y: 30 } }
+                    const y = r.b.y
+                    return width(r) + local.z + y
+                }",
+            ),
+            (
+                "shapes",
+                "pub const Point = struct {
+                    x: usize,
+                    y: usize,
+                }
+                pub const Rect = struct {
+                    a: Point,
+                    b: Point,
+                }
+                pub const width = fn(r: Rect) usize {
+                    return r.b.x - r.a.x
+                }",
+            ),
+        ],
+        42,
+    );
+
+    run_modules(
+        "nested modules exe",
+        &[
+            (
+                "main",
+                "const nested = import \"geo.flat.area\"
+                const top = import \"flat.area\"
+                const base = import \"geo.flat.base\"
+                pub const main = fn() usize {
+                    return nested(2, 3) + top() + base
+                }",
+            ),
+            (
+                "geo.flat",
+                "pub const base = 30
+                pub const area = fn(w: usize, h: usize) usize {
+                    return w * h
+                }",
+            ),
+            (
+                "flat",
+                "pub const area = fn() usize {
+                    return 6
+                }",
+            ),
+        ],
+        42,
+    );
 
     println!("[run] full parse");
 
@@ -39,20 +732,17 @@ fn main() {
     ty.read_to_string(&mut contents).unwrap();
     let lex = TLexer::new(&contents);
     let mut parse = Parser::new(lex);
-    let res = parse.all();
-    match res {
-        Ok(_) => println!("  [ok] full parse success!"),
-        Err(x) => {
-            println!("  [fail]\n{}", x);
-            std::process::exit(1);
-        }
+    let (res, errors) = parse.all();
+    if errors.len() > 0 {
+        errors.iter().for_each(|x| println!("  [fail]\n{}", x));
+        std::process::exit(1);
     }
+    println!("  [ok] full parse success!");
     println!("[run] full linting without cache context");
     let mut ttbls = vec![];
     let mut scopes = vec![];
     let mut linter = LintSource::new(&contents, &mut scopes, &mut ttbls);
-    let borrow = res.unwrap();
-    let result = linter.lint_check(&mut borrow.to_owned());
+    let result = linter.lint_check(&mut res.to_owned());
 
     if linter.issues.len() > 0 {
         println!("  [fail]\n issues: {:?}\n", linter.issues);
@@ -1,11 +1,14 @@
 use cranelift_codegen::entity::EntityRef;
-use cranelift_codegen::ir::entities::FuncRef;
+use cranelift_codegen::ir::condcodes::{FloatCC, IntCC};
 use cranelift_codegen::ir::function::DisplayFunction;
-use cranelift_codegen::ir::immediates::Offset32;
 use cranelift_codegen::ir::types::*;
 use cranelift_codegen::ir::AbiParam;
+use cranelift_codegen::ir::Block as IrBlock;
 use cranelift_codegen::ir::MemFlags;
+use cranelift_codegen::ir::TrapCode;
+use cranelift_codegen::ir::Type;
 use cranelift_codegen::ir::{Function, InstBuilder, Signature, UserFuncName, Value};
+use cranelift_codegen::ir::{StackSlot, StackSlotData, StackSlotKind};
 use cranelift_codegen::isa::CallConv;
 use cranelift_codegen::settings;
 use cranelift_codegen::verifier::verify_function;
@@ -13,9 +16,14 @@ use cranelift_frontend::*;
 use cranelift_frontend::{FunctionBuilder, FunctionBuilderContext};
 use cranelift_module::Module;
 use datatable::DataTable;
+use layout::{
+    discriminant_of, elem_of, enum_info, error_info, has_niche, layout_of, payload_of, prop_of,
+    struct_info, tag_info, TAG_DISCRIMINANT,
+};
 use oir::Oir;
 use perror::*;
 use scopetable::ScopeTable;
+use std::collections::HashMap;
 use symtable::SymTable;
 use types::*;
 
@@ -23,154 +31,1566 @@ use types::*;
 pub struct Fir {
     variables: u32,
     sym: SymTable,
+    // where a `break` jumps to, the innermost value producing if, or loop. and whether the
+    // break must carry a value
+    exits: Vec<(IrBlock, bool)>,
+    // where a `return` jumps to when inside of an inlined anonymous function
+    rets: Vec<IrBlock>,
+    // variables holding the address of an array, with the length and element type. elements are
+    // held one after another, each as it is held within a struct
+    arrays: HashMap<u32, (Variable, Ty)>,
+    // variables holding the value of an error union, with the variable holding its error code.
+    // the code is 0 when there is no error
+    errors: HashMap<u32, Variable>,
+    // variables holding the value of an optional, with the variable holding whether it is
+    // defined. an optional with a niche has none, it is 0 when undefined
+    optionals: HashMap<u32, Variable>,
+    // the declared return type of the function being built
+    ret: Ty,
 }
 
 impl Fir {
     pub fn refresh(&mut self) -> () {
         self.variables = 0;
-        self.sym = SymTable::new()
+        self.sym = SymTable::new();
+        self.exits.clear();
+        self.rets.clear();
+        self.arrays.clear();
+        self.errors.clear();
+        self.optionals.clear();
+        self.ret = Ty::Void;
     }
     pub fn new(variables: u32, sym: SymTable) -> Self {
-        Fir { variables, sym }
+        Fir {
+            variables,
+            sym,
+            exits: vec![],
+            rets: vec![],
+            arrays: HashMap::new(),
+            errors: HashMap::new(),
+            optionals: HashMap::new(),
+            ret: Ty::Void,
+        }
+    }
+    pub fn run(
+        &mut self,
+        func_def: &FunctionInitialize,
+        ctx: &mut FunctionBuilderContext,
+        namespace: u32,
+        index: u32,
+        dtbl: &DataTable,
+        scopes: &Vec<ScopeTable>,
+        types: &Vec<TypeTree>,
+        oir: &mut Oir,
+    ) -> ResultFir<Function> {
+        let sig = self.signature(func_def, oir);
+        self.ret = func_def.ret_curried.clone();
+        let name = UserFuncName::user(namespace, index);
+        let mut func = Function::with_name_signature(name, sig);
+        let mut builder = FunctionBuilder::new(&mut func, ctx);
+        let root_block = builder.create_block();
+        let mut result_sets = vec![];
+        for x in func_def.args.iter() {
+            let z = self.recurse(*x, &mut builder, dtbl, scopes, types, oir)?;
+            result_sets.push(z);
+        }
+        builder.append_block_params_for_function_params(root_block);
+        builder.switch_to_block(root_block);
+
+        let mut params = builder.block_params(root_block).to_vec().into_iter();
+        for x in result_sets.iter() {
+            builder.def_var(*x, params.next().unwrap());
+            if let Some((len, _)) = self.arrays.get(&x.as_u32()) {
+                builder.def_var(*len, params.next().unwrap());
+            }
+            if let Some(flag) = self.optionals.get(&x.as_u32()) {
+                builder.def_var(*flag, params.next().unwrap());
+            }
+        }
+
+        self.recurse(func_def.block, &mut builder, dtbl, scopes, types, oir)?;
+        if !self.is_terminated(&builder) {
+            if self.ret.error_union() == Some(Ty::Void) || builder.func.signature.returns.len() == 0
+            {
+                let _ = self.handle_ret_void(&mut builder);
+            } else {
+                builder.ins().trap(TrapCode::unwrap_user(1));
+            }
+        }
+        builder.seal_all_blocks();
+        builder.finalize();
+        Ok(func)
+    }
+    // the signature is needed before the function is built, so that it can be declared and called
+    // from functions defined before it
+    // exported functions are called from c, so they use the platform calling convention.
+    pub fn signature(&self, func_def: &FunctionInitialize, oir: &Oir) -> Signature {
+        let call_conv = match func_def.export {
+            true => oir.obj_mod.isa().default_call_conv(),
+            false => CallConv::Fast,
+        };
+        make_signature(&func_def.args_curried, &func_def.ret_curried, call_conv)
+    }
+    pub fn extern_signature(&self, ext: &ExternInitialize, oir: &Oir) -> Signature {
+        let call_conv = oir.obj_mod.isa().default_call_conv();
+        make_signature(&ext.args_curried, &ext.ret_curried, call_conv)
+    }
+    // the calling convention matches how the function was declared in its own module
+    pub fn import_signature(&self, imp: &ImportInitialize, oir: &Oir) -> Signature {
+        let call_conv = match imp.export {
+            true => oir.obj_mod.isa().default_call_conv(),
+            false => CallConv::Fast,
+        };
+        let args = imp.args_curried.clone().unwrap_or_default();
+        make_signature(&args, &imp.curried, call_conv)
+    }
+    pub fn handle_arg_init(
+        &mut self,
+        op: &SymbolInit,
+        builder: &mut FunctionBuilder,
+        dtbl: &DataTable,
+        scopes: &Vec<ScopeTable>,
+        types: &Vec<TypeTree>,
+        oir: &mut Oir,
+    ) -> ResultFir<Variable> {
+        let result = self.add_var();
+        builder.declare_var(result, ty_to_type(&op.curried).unwrap_or(I64));
+        self.sym.table.insert(op.ident.clone(), result.as_u32());
+        if let Some(elem) = elem_of(&op.curried) {
+            let len = self.add_var();
+            builder.declare_var(len, I64);
+            self.arrays.insert(result.as_u32(), (len, elem));
+        }
+        if flagged(&op.curried).is_some() {
+            let flag = self.add_var();
+            builder.declare_var(flag, I8);
+            self.optionals.insert(result.as_u32(), flag);
+        }
+        Ok(result)
+    }
+    pub fn handle_const_init(
+        &mut self,
+        op: &Initialization,
+        builder: &mut FunctionBuilder,
+        dtbl: &DataTable,
+        scopes: &Vec<ScopeTable>,
+        types: &Vec<TypeTree>,
+        oir: &mut Oir,
+    ) -> ResultFir<Variable> {
+        let temp = self.recurse(op.right, builder, dtbl, scopes, types, oir)?;
+        // todo:: optimization: not all paths need declare var if value is only ever read. or something similar, this statement is in the same ballpark, but might not be totally correct
+        let mut x = builder.use_var(temp);
+        let right = types.get(op.right as usize).unwrap();
+        // structs are values, a struct that already has a home is copied into a new one
+        let ty = right.get_curried();
+        let fresh = matches!(
+            right,
+            TypeTree::StructInit(_) | TypeTree::VariantInit(_) | TypeTree::VariantAccess(_)
+        );
+        if is_aggregate(&ty, scopes, types) && !fresh {
+            let layout = layout_of(&ty, scopes, types);
+            let slot = self.stack_slot(layout.size, layout.align, builder);
+            let addr = builder.ins().stack_addr(I64, slot, 0);
+            self.copy(addr, x, layout.size, layout.align, builder, oir);
+            x = addr;
+        }
+        // a declared type is what the value is held in
+        let some = flagged(&op.curried);
+        if let Some(to) = scalar_type(some.as_ref().unwrap_or(&op.curried)) {
+            x = self.coerce(x, to, ty.is_signed(), builder);
+        }
+        let result = self.add_var();
+        builder.declare_var(result, builder.func.dfg.value_type(x));
+        if some.is_some() {
+            let defined = self.defined_flag(temp, builder);
+            let flag = self.add_var();
+            builder.declare_var(flag, I8);
+            builder.def_var(flag, defined);
+            self.optionals.insert(result.as_u32(), flag);
+        }
+        let tt = types.get(op.left as usize).unwrap();
+
+        self.sym
+            .table
+            .insert(tt.into_symbol_init().ident.clone(), result.as_u32());
+        builder.def_var(result, x);
+        if let Some(arr) = self.arrays.get(&temp.as_u32()).cloned() {
+            self.arrays.insert(result.as_u32(), arr);
+        }
+        Ok(result)
+    }
+    // compound assignments like `+=` pass in the operation combining the current value with the
+    // new one
+    pub fn handle_reassign(
+        &mut self,
+        op: &Reassignment,
+        combine: Option<&TypeTree>,
+        builder: &mut FunctionBuilder,
+        dtbl: &DataTable,
+        scopes: &Vec<ScopeTable>,
+        types: &Vec<TypeTree>,
+        oir: &mut Oir,
+    ) -> ResultFir<Variable> {
+        let temp = self.recurse(op.right, builder, dtbl, scopes, types, oir)?;
+        let mut x = builder.use_var(temp);
+        // the value takes on the type of what it is assigned to
+        let signed = types
+            .get(op.right as usize)
+            .unwrap()
+            .get_curried()
+            .is_signed();
+        let left_signed = op.curried.is_signed();
+        // properties, elements, and globals are all written at an offset from an address
+        let place = match types.get(op.left as usize).unwrap() {
+            TypeTree::SymbolAccess(sym) if !self.sym.table.contains_key(&sym.ident) => {
+                let id = dtbl.table.get(&sym.ident).unwrap();
+                let gv = oir.obj_mod.declare_data_in_func(*id, builder.func);
+                let addr = builder.ins().global_value(I64, gv);
+                Some((addr, 0, sym.curried.clone()))
+            }
+            TypeTree::PropAccess(prop) => {
+                Some(self.prop_addr(prop, builder, dtbl, scopes, types, oir)?)
+            }
+            TypeTree::ArrayAccess(access) => {
+                let (addr, elem) = self.elem_addr(access, builder, dtbl, scopes, types, oir)?;
+                Some((addr, 0, elem))
+            }
+            _ => None,
+        };
+        if let Some((base, offset, ty)) = place {
+            if let Some(combine) = combine {
+                let prev = self.load_prop(base, offset, &ty, builder, scopes, types);
+                x = self.coerce(x, builder.func.dfg.value_type(prev), signed, builder);
+                x = self.combine(combine, prev, x, left_signed, builder);
+            }
+            match self.arrays.get(&temp.as_u32()).cloned() {
+                Some((len, _)) => {
+                    let len = builder.use_var(len);
+                    self.store_slice(base, offset, x, len, builder);
+                }
+                None => self.store_prop(base, offset, &ty, x, builder, scopes, types, oir),
+            }
+            return Ok(temp);
+        }
+        let ident = &types
+            .get(op.left as usize)
+            .unwrap()
+            .into_symbol_access()
+            .ident;
+        if let Some(s) = self.sym.table.get(ident) {
+            let result = Variable::from_u32(*s);
+            let prev = builder.use_var(result);
+            x = self.coerce(x, builder.func.dfg.value_type(prev), signed, builder);
+            if let Some(combine) = combine {
+                x = self.combine(combine, prev, x, left_signed, builder);
+            }
+            builder.def_var(result, x);
+            if let Some(flag) = self.optionals.get(&result.as_u32()).copied() {
+                let defined = self.defined_flag(temp, builder);
+                builder.def_var(flag, defined);
+            }
+            return Ok(result);
+        }
+        Err(FirError::new(format!("unknown binding {}", ident)))
+    }
+    pub fn handle_if(
+        &mut self,
+        op: &IfOp,
+        as_value: bool,
+        builder: &mut FunctionBuilder,
+        dtbl: &DataTable,
+        scopes: &Vec<ScopeTable>,
+        types: &Vec<TypeTree>,
+        oir: &mut Oir,
+    ) -> ResultFir<Variable> {
+        if as_value && op.else_body.is_none() {
+            return Err(FirError::new(
+                "if used as a value must have an else branch".to_string(),
+            ));
+        }
+        let merge_block = builder.create_block();
+        if as_value {
+            self.exits.push((merge_block, true));
+        }
+        let chain =
+            self.handle_if_chain(op, merge_block, as_value, builder, dtbl, scopes, types, oir);
+        if as_value {
+            self.exits.pop();
+        }
+        chain?;
+        builder.seal_block(merge_block);
+        builder.switch_to_block(merge_block);
+        if !as_value {
+            return Ok(Variable::from_u32(0));
+        }
+        let param = builder.block_params(merge_block).first().copied();
+        match param {
+            Some(val) => {
+                let result = self.add_var();
+                builder.declare_var(result, builder.func.dfg.value_type(val));
+                builder.def_var(result, val);
+                Ok(result)
+            }
+            None => Err(FirError::new(
+                "if used as a value must break with a value".to_string(),
+            )),
+        }
+    }
+    // each `else if` is lowered into the else block of the previous if, all sharing one merge
+    pub fn handle_if_chain(
+        &mut self,
+        op: &IfOp,
+        merge_block: IrBlock,
+        as_value: bool,
+        builder: &mut FunctionBuilder,
+        dtbl: &DataTable,
+        scopes: &Vec<ScopeTable>,
+        types: &Vec<TypeTree>,
+        oir: &mut Oir,
+    ) -> ResultFir<()> {
+        let cond_var = self.recurse(op.in_expr, builder, dtbl, scopes, types, oir)?;
+        let cond = builder.use_var(cond_var);
+        // an optional is checked for being defined
+        let test = match self.optionals.get(&cond_var.as_u32()) {
+            Some(flag) => builder.use_var(*flag),
+            None => self.truthy(cond, builder),
+        };
+        let then_block = builder.create_block();
+        let else_block = match op.else_body {
+            Some(_) => builder.create_block(),
+            None => merge_block,
+        };
+        builder.ins().brif(test, then_block, &[], else_block, &[]);
+        builder.seal_block(then_block);
+        builder.switch_to_block(then_block);
+        self.handle_branch(
+            op.body,
+            Some(cond),
+            merge_block,
+            as_value,
+            builder,
+            dtbl,
+            scopes,
+            types,
+            oir,
+        )?;
+        if let Some(els) = op.else_body {
+            builder.seal_block(else_block);
+            builder.switch_to_block(else_block);
+            match types.get(els as usize).unwrap() {
+                TypeTree::If(inner) => {
+                    return self.handle_if_chain(
+                        inner,
+                        merge_block,
+                        as_value,
+                        builder,
+                        dtbl,
+                        scopes,
+                        types,
+                        oir,
+                    );
+                }
+                _ => {
+                    self.handle_branch(
+                        els,
+                        None,
+                        merge_block,
+                        as_value,
+                        builder,
+                        dtbl,
+                        scopes,
+                        types,
+                        oir,
+                    )?;
+                }
+            }
+        }
+        Ok(())
+    }
+    // a branch is either a block, or an anonymous function which is inlined with its first
+    // argument capturing the value that was checked.
+    pub fn handle_branch(
+        &mut self,
+        idx: TypeTreeIndex,
+        capture: Option<Value>,
+        merge_block: IrBlock,
+        as_value: bool,
+        builder: &mut FunctionBuilder,
+        dtbl: &DataTable,
+        scopes: &Vec<ScopeTable>,
+        types: &Vec<TypeTree>,
+        oir: &mut Oir,
+    ) -> ResultFir<()> {
+        match types.get(idx as usize).unwrap() {
+            TypeTree::AnonFuncInit(op) => {
+                if let (Some(arg), Some(val)) = (op.args.first(), capture) {
+                    let ident = &types.get(*arg as usize).unwrap().into_arg_init().ident;
+                    let result = self.add_var();
+                    builder.declare_var(result, builder.func.dfg.value_type(val));
+                    builder.def_var(result, val);
+                    self.sym.table.insert(ident.clone(), result.as_u32());
+                }
+                self.rets.push(merge_block);
+                let body = self.recurse(op.block, builder, dtbl, scopes, types, oir);
+                self.rets.pop();
+                body?;
+            }
+            _ => {
+                self.recurse(idx, builder, dtbl, scopes, types, oir)?;
+            }
+        }
+        if self.is_terminated(builder) {
+            return Ok(());
+        }
+        if as_value {
+            if builder.is_unreachable() {
+                builder.ins().trap(TrapCode::unwrap_user(1));
+                return Ok(());
+            }
+            return Err(FirError::new(
+                "if used as a value must break with a value in every branch".to_string(),
+            ));
+        }
+        builder.ins().jump(merge_block, &[]);
+        Ok(())
+    }
+    pub fn handle_match(
+        &mut self,
+        op: &MatchOp,
+        as_value: bool,
+        builder: &mut FunctionBuilder,
+        dtbl: &DataTable,
+        scopes: &Vec<ScopeTable>,
+        types: &Vec<TypeTree>,
+        oir: &mut Oir,
+    ) -> ResultFir<Variable> {
+        let scrut_var = self.recurse(op.expr, builder, dtbl, scopes, types, oir)?;
+        let scrut = builder.use_var(scrut_var);
+        // a tag is matched by its discriminant, the payload is read when an arm binds it
+        let key = match tag_info(&op.curried, scopes, types) {
+            Some(_) => {
+                let typ = ty_to_type(&TAG_DISCRIMINANT).unwrap();
+                builder.ins().load(typ, MemFlags::new(), scrut, 0)
+            }
+            None => scrut,
+        };
+        let merge_block = builder.create_block();
+        if as_value {
+            self.exits.push((merge_block, true));
+        }
+        let arms = self.handle_arms(
+            op,
+            scrut,
+            key,
+            merge_block,
+            as_value,
+            builder,
+            dtbl,
+            scopes,
+            types,
+            oir,
+        );
+        if as_value {
+            self.exits.pop();
+        }
+        arms?;
+        builder.seal_block(merge_block);
+        builder.switch_to_block(merge_block);
+        if !as_value {
+            return Ok(Variable::from_u32(0));
+        }
+        let params = builder.block_params(merge_block).to_vec();
+        match params.first().copied() {
+            Some(val) => {
+                let result = self.add_var();
+                builder.declare_var(result, builder.func.dfg.value_type(val));
+                builder.def_var(result, val);
+                // arms giving errors and values give an error union
+                if let Some(code) = params.get(1) {
+                    let var = self.add_var();
+                    builder.declare_var(var, I64);
+                    builder.def_var(var, *code);
+                    self.errors.insert(result.as_u32(), var);
+                }
+                Ok(result)
+            }
+            None => Err(FirError::new(
+                "match used as a value must give a value from an arm".to_string(),
+            )),
+        }
+    }
+    // when every pattern is a constant the arms are picked with a switch, which is a jump table
+    // when the constants are close together. otherwise each pattern is compared in order
+    fn handle_arms(
+        &mut self,
+        op: &MatchOp,
+        scrut: Value,
+        key: Value,
+        merge_block: IrBlock,
+        as_value: bool,
+        builder: &mut FunctionBuilder,
+        dtbl: &DataTable,
+        scopes: &Vec<ScopeTable>,
+        types: &Vec<TypeTree>,
+        oir: &mut Oir,
+    ) -> ResultFir<()> {
+        let typ = builder.func.dfg.value_type(key);
+        let union = op.curried_arms.error_union().filter(|_| as_value);
+        let mut arms: Vec<(Option<TypeTreeIndex>, TypeTreeIndex, IrBlock)> = vec![];
+        for x in op.arms.iter() {
+            let arm = types.get(*x as usize).unwrap().into_binary_op();
+            let pattern = match types.get(arm.left as usize).unwrap() {
+                TypeTree::RestAccess(_) => None,
+                _ => Some(arm.left),
+            };
+            arms.push((pattern, arm.right, builder.create_block()));
+            // nothing after the rest arm is reachable
+            if pattern.is_none() {
+                break;
+            }
+        }
+        let otherwise = match arms.last() {
+            Some((None, _, block)) => *block,
+            _ => builder.create_block(),
+        };
+        let max = match typ.bits() {
+            x if x >= 64 => u64::MAX as i128,
+            x => (1i128 << x) - 1,
+        };
+        let consts: Option<Vec<i128>> = arms
+            .iter()
+            .filter_map(|x| x.0)
+            .map(|x| pattern_const(x, scopes, types).filter(|c| *c >= 0 && *c <= max))
+            .collect();
+        match consts {
+            Some(consts) if typ.is_int() => {
+                let mut switch = Switch::new();
+                for (c, (_, _, block)) in consts.iter().zip(arms.iter()) {
+                    // a repeated pattern can't be reached, the first arm with it is taken
+                    if !switch.entries().contains_key(&(*c as u128)) {
+                        switch.set_entry(*c as u128, *block);
+                    }
+                }
+                switch.emit(builder, key, otherwise);
+            }
+            _ => {
+                for (pattern, _, block) in arms.iter() {
+                    let pattern = match pattern {
+                        Some(x) => *x,
+                        None => break,
+                    };
+                    let val = self.recurse(pattern, builder, dtbl, scopes, types, oir)?;
+                    let val = builder.use_var(val);
+                    let signed = types
+                        .get(pattern as usize)
+                        .unwrap()
+                        .get_curried()
+                        .is_signed();
+                    let test = match typ.is_float() {
+                        true => builder.ins().fcmp(FloatCC::Equal, key, val),
+                        false => {
+                            let val = self.coerce(val, typ, signed, builder);
+                            builder.ins().icmp(IntCC::Equal, key, val)
+                        }
+                    };
+                    let next = builder.create_block();
+                    builder.ins().brif(test, *block, &[], next, &[]);
+                    builder.seal_block(next);
+                    builder.switch_to_block(next);
+                }
+                builder.ins().jump(otherwise, &[]);
+            }
+        }
+        // the linter makes sure every value has an arm, so without a rest arm this is never taken
+        if !matches!(arms.last(), Some((None, _, _))) {
+            builder.seal_block(otherwise);
+            builder.switch_to_block(otherwise);
+            builder.ins().trap(TrapCode::unwrap_user(1));
+        }
+        for (pattern, right, block) in arms {
+            builder.seal_block(block);
+            builder.switch_to_block(block);
+            // `Shape.circle(r) =>` reads the payload into r before the arm
+            let bind = pattern.and_then(|x| match types.get(x as usize) {
+                Some(TypeTree::VariantInit(v)) => v.payload.map(|p| (v, p)),
+                _ => None,
+            });
+            if let Some((variant, payload)) = bind {
+                let ident = &types
+                    .get(payload as usize)
+                    .unwrap()
+                    .into_symbol_init()
+                    .ident;
+                let (offset, ty) = match payload_of(&variant.curried, &variant.ident, scopes, types)
+                {
+                    Some(x) => x,
+                    None => return Err(FirError::new(format!("{} has no payload", variant.ident))),
+                };
+                let var = self.read_prop(scrut, offset, &ty, builder, scopes, types);
+                self.sym.table.insert(ident.clone(), var.as_u32());
+            }
+            match types.get(right as usize).unwrap() {
+                TypeTree::AnonFuncInit(_) | TypeTree::Block(_) => {
+                    self.handle_branch(
+                        right,
+                        Some(scrut),
+                        merge_block,
+                        as_value,
+                        builder,
+                        dtbl,
+                        scopes,
+                        types,
+                        oir,
+                    )?;
+                }
+                _ => {
+                    let val = self.recurse(right, builder, dtbl, scopes, types, oir)?;
+                    if self.is_terminated(builder) {
+                        continue;
+                    }
+                    match (as_value, &union) {
+                        (true, Some(ok)) => {
+                            let from = types.get(right as usize).unwrap().get_curried();
+                            let (val, code) = self.union_parts(val, &from, ok, builder);
+                            self.jump_with(merge_block, &[val, code], builder);
+                        }
+                        (true, None) => {
+                            let val = builder.use_var(val);
+                            self.jump_with(merge_block, &[val], builder);
+                        }
+                        (false, _) => {
+                            builder.ins().jump(merge_block, &[]);
+                        }
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+    pub fn handle_variant(
+        &mut self,
+        op: &VariantAccess,
+        builder: &mut FunctionBuilder,
+        dtbl: &DataTable,
+        scopes: &Vec<ScopeTable>,
+        types: &Vec<TypeTree>,
+        oir: &mut Oir,
+    ) -> ResultFir<Variable> {
+        if tag_info(&op.curried, scopes, types).is_some() {
+            let ident = &op.ident;
+            return self.handle_tag_init(
+                &op.curried,
+                ident,
+                None,
+                builder,
+                dtbl,
+                scopes,
+                types,
+                oir,
+            );
+        }
+        let val = match discriminant_of(&op.curried, &op.ident, scopes, types) {
+            Some(x) => x,
+            None => {
+                return Err(FirError::new(format!(
+                    "{} has no variant {}",
+                    op.owner, op.ident
+                )))
+            }
+        };
+        let typ = ty_to_type(&op.curried).unwrap_or(I64);
+        let result = self.add_var();
+        builder.declare_var(result, typ);
+        let temp = builder.ins().iconst(typ, val as i64);
+        builder.def_var(result, temp);
+        Ok(result)
+    }
+    // a tag lives in its own stack slot like a struct, its discriminant is written first and then
+    // the payload of the variant, if it has one
+    pub fn handle_tag_init(
+        &mut self,
+        ty: &Ty,
+        ident: &str,
+        payload: Option<TypeTreeIndex>,
+        builder: &mut FunctionBuilder,
+        dtbl: &DataTable,
+        scopes: &Vec<ScopeTable>,
+        types: &Vec<TypeTree>,
+        oir: &mut Oir,
+    ) -> ResultFir<Variable> {
+        let (disc, (offset, payload_ty)) = match (
+            discriminant_of(ty, ident, scopes, types),
+            payload_of(ty, ident, scopes, types),
+        ) {
+            (Some(x), Some(y)) => (x, y),
+            _ => return Err(FirError::new(format!("{} has no variant {}", ty, ident))),
+        };
+        let layout = layout_of(ty, scopes, types);
+        let slot = self.stack_slot(layout.size, layout.align, builder);
+        let addr = builder.ins().stack_addr(I64, slot, 0);
+        let typ = ty_to_type(&TAG_DISCRIMINANT).unwrap();
+        let disc = builder.ins().iconst(typ, disc as i64);
+        builder.ins().store(MemFlags::new(), disc, addr, 0);
+        if let Some(x) = payload {
+            let temp = self.recurse(x, builder, dtbl, scopes, types, oir)?;
+            self.write_prop(addr, offset, &payload_ty, temp, builder, scopes, types, oir);
+        }
+        let result = self.add_var();
+        builder.declare_var(result, I64);
+        builder.def_var(result, addr);
+        Ok(result)
+    }
+    // `try x` returns the error from the function, or carries on with the value
+    pub fn handle_bubble_error(
+        &mut self,
+        op: &UnaryOp,
+        builder: &mut FunctionBuilder,
+        dtbl: &DataTable,
+        scopes: &Vec<ScopeTable>,
+        types: &Vec<TypeTree>,
+        oir: &mut Oir,
+    ) -> ResultFir<Variable> {
+        let val = self.recurse(op.val, builder, dtbl, scopes, types, oir)?;
+        let code = match self.errors.get(&val.as_u32()) {
+            Some(x) => builder.use_var(*x),
+            None => return Ok(val),
+        };
+        let fail = builder.create_block();
+        let next = builder.create_block();
+        builder.ins().brif(code, fail, &[], next, &[]);
+        builder.seal_block(fail);
+        builder.switch_to_block(fail);
+        let typ = builder.func.signature.returns[0].value_type;
+        let zero = self.zero(typ, builder);
+        builder.ins().return_(&[zero, code]);
+        builder.seal_block(next);
+        builder.switch_to_block(next);
+        let val = builder.use_var(val);
+        let result = self.add_var();
+        builder.declare_var(result, builder.func.dfg.value_type(val));
+        builder.def_var(result, val);
+        Ok(result)
+    }
+    // `x?` returns undefined from the function, or carries on with the value
+    pub fn handle_bubble_undefined(
+        &mut self,
+        op: &UnaryOp,
+        builder: &mut FunctionBuilder,
+        dtbl: &DataTable,
+        scopes: &Vec<ScopeTable>,
+        types: &Vec<TypeTree>,
+        oir: &mut Oir,
+    ) -> ResultFir<Variable> {
+        let val = self.recurse(op.val, builder, dtbl, scopes, types, oir)?;
+        // an optional with a niche is defined when it is not 0
+        let defined = match self.optionals.get(&val.as_u32()) {
+            Some(x) => builder.use_var(*x),
+            None => builder.use_var(val),
+        };
+        let fail = builder.create_block();
+        let next = builder.create_block();
+        builder.ins().brif(defined, next, &[], fail, &[]);
+        builder.seal_block(fail);
+        builder.switch_to_block(fail);
+        // undefined is returned as all zeros, a null pointer or a value with no defined flag
+        let rets: Vec<Type> = builder
+            .func
+            .signature
+            .returns
+            .iter()
+            .map(|x| x.value_type)
+            .collect();
+        let rets: Vec<Value> = rets.into_iter().map(|x| self.zero(x, builder)).collect();
+        builder.ins().return_(&rets);
+        builder.seal_block(next);
+        builder.switch_to_block(next);
+        let val = builder.use_var(val);
+        let result = self.add_var();
+        builder.declare_var(result, builder.func.dfg.value_type(val));
+        builder.def_var(result, val);
+        Ok(result)
+    }
+    // undefined is 0, and not defined. it takes on the type of the optional it is given to
+    pub fn handle_undefined(&mut self, builder: &mut FunctionBuilder) -> ResultFir<Variable> {
+        let result = self.add_var();
+        builder.declare_var(result, I64);
+        let zero = builder.ins().iconst(I64, 0);
+        builder.def_var(result, zero);
+        let flag = self.add_var();
+        builder.declare_var(flag, I8);
+        let zero = builder.ins().iconst(I8, 0);
+        builder.def_var(flag, zero);
+        self.optionals.insert(result.as_u32(), flag);
+        Ok(result)
+    }
+    // the handler is only run when there is an error, it's given the error code
+    pub fn handle_catch(
+        &mut self,
+        op: &CatchOp,
+        builder: &mut FunctionBuilder,
+        dtbl: &DataTable,
+        scopes: &Vec<ScopeTable>,
+        types: &Vec<TypeTree>,
+        oir: &mut Oir,
+    ) -> ResultFir<Variable> {
+        let val = self.recurse(op.expr, builder, dtbl, scopes, types, oir)?;
+        let code = match self.errors.get(&val.as_u32()) {
+            Some(x) => builder.use_var(*x),
+            None => return Ok(val),
+        };
+        let as_value = ty_to_type(&op.curried).is_some();
+        let merge_block = builder.create_block();
+        let handler = builder.create_block();
+        let next = builder.create_block();
+        builder.ins().brif(code, handler, &[], next, &[]);
+        builder.seal_block(next);
+        builder.switch_to_block(next);
+        match as_value {
+            true => {
+                let val = builder.use_var(val);
+                self.jump_with(merge_block, &[val], builder);
+            }
+            false => {
+                builder.ins().jump(merge_block, &[]);
+            }
+        }
+        builder.seal_block(handler);
+        builder.switch_to_block(handler);
+        self.handle_branch(
+            op.handler,
+            Some(code),
+            merge_block,
+            as_value,
+            builder,
+            dtbl,
+            scopes,
+            types,
+            oir,
+        )?;
+        builder.seal_block(merge_block);
+        builder.switch_to_block(merge_block);
+        if !as_value {
+            return Ok(Variable::from_u32(0));
+        }
+        let param = builder.block_params(merge_block)[0];
+        let result = self.add_var();
+        builder.declare_var(result, builder.func.dfg.value_type(param));
+        builder.def_var(result, param);
+        Ok(result)
+    }
+    pub fn handle_break(
+        &mut self,
+        op: &UnaryOp,
+        builder: &mut FunctionBuilder,
+        dtbl: &DataTable,
+        scopes: &Vec<ScopeTable>,
+        types: &Vec<TypeTree>,
+        oir: &mut Oir,
+    ) -> ResultFir<Variable> {
+        let temp = self.recurse(op.val, builder, dtbl, scopes, types, oir)?;
+        let arg = builder.use_var(temp);
+        match self.exits.last() {
+            Some((target, true)) => {
+                let target = *target;
+                self.jump_with(target, &[arg], builder);
+                Ok(temp)
+            }
+            Some((_, false)) => Err(FirError::new(
+                "break from a loop cannot carry a value".to_string(),
+            )),
+            None => Err(FirError::new(
+                "break found outside of a loop, or if value".to_string(),
+            )),
+        }
+    }
+    pub fn handle_break_void(&mut self, builder: &mut FunctionBuilder) -> ResultFir<Variable> {
+        match self.exits.last() {
+            Some((target, false)) => {
+                builder.ins().jump(*target, &[]);
+                Ok(Variable::from_u32(0))
+            }
+            Some((_, true)) => Err(FirError::new(
+                "break from an if used as a value must carry a value".to_string(),
+            )),
+            None => Err(FirError::new(
+                "break found outside of a loop, or if value".to_string(),
+            )),
+        }
+    }
+    pub fn handle_while(
+        &mut self,
+        op: &WhileOp,
+        builder: &mut FunctionBuilder,
+        dtbl: &DataTable,
+        scopes: &Vec<ScopeTable>,
+        types: &Vec<TypeTree>,
+        oir: &mut Oir,
+    ) -> ResultFir<Variable> {
+        let header_block = builder.create_block();
+        let body_block = builder.create_block();
+        let exit_block = builder.create_block();
+        builder.ins().jump(header_block, &[]);
+        builder.switch_to_block(header_block);
+        let cond_var = self.recurse(op.expr, builder, dtbl, scopes, types, oir)?;
+        let cond = builder.use_var(cond_var);
+        let test = self.truthy(cond, builder);
+        builder.ins().brif(test, body_block, &[], exit_block, &[]);
+        builder.seal_block(body_block);
+        builder.switch_to_block(body_block);
+        self.exits.push((exit_block, false));
+        let body = self.handle_branch(
+            op.var_loop,
+            Some(cond),
+            header_block,
+            false,
+            builder,
+            dtbl,
+            scopes,
+            types,
+            oir,
+        );
+        self.exits.pop();
+        body?;
+        builder.seal_block(header_block);
+        builder.seal_block(exit_block);
+        builder.switch_to_block(exit_block);
+        Ok(Variable::from_u32(0))
+    }
+    // a for loop counts an index from the start of a range, or 0 for an array, up to the end.
+    // the body captures the index for a range, and the element for an array.
+    pub fn handle_for(
+        &mut self,
+        op: &ForOp,
+        builder: &mut FunctionBuilder,
+        dtbl: &DataTable,
+        scopes: &Vec<ScopeTable>,
+        types: &Vec<TypeTree>,
+        oir: &mut Oir,
+    ) -> ResultFir<Variable> {
+        let (start, end, signed, array) = match types.get(op.in_expr as usize).unwrap() {
+            TypeTree::Range(range) => {
+                let left = self.recurse(range.left, builder, dtbl, scopes, types, oir)?;
+                let right = self.recurse(range.right, builder, dtbl, scopes, types, oir)?;
+                let signed = types
+                    .get(range.left as usize)
+                    .unwrap()
+                    .get_curried()
+                    .is_signed();
+                (builder.use_var(left), builder.use_var(right), signed, None)
+            }
+            _ => {
+                let arr = self.recurse(op.in_expr, builder, dtbl, scopes, types, oir)?;
+                let (len, elem) = match self.arrays.get(&arr.as_u32()) {
+                    Some(x) => x.clone(),
+                    None => {
+                        return Err(FirError::new(
+                            "for loop expects an array or a range".to_string(),
+                        ))
+                    }
+                };
+                let ptr = builder.use_var(arr);
+                let zero = builder.ins().iconst(I64, 0);
+                (zero, builder.use_var(len), false, Some((ptr, elem)))
+            }
+        };
+        let counter = self.add_var();
+        builder.declare_var(counter, builder.func.dfg.value_type(start));
+        builder.def_var(counter, start);
+        let header_block = builder.create_block();
+        let body_block = builder.create_block();
+        let next_block = builder.create_block();
+        let exit_block = builder.create_block();
+        builder.ins().jump(header_block, &[]);
+        builder.switch_to_block(header_block);
+        let idx = builder.use_var(counter);
+        let cc = match signed {
+            true => IntCC::SignedLessThan,
+            false => IntCC::UnsignedLessThan,
+        };
+        let test = builder.ins().icmp(cc, idx, end);
+        builder.ins().brif(test, body_block, &[], exit_block, &[]);
+        builder.seal_block(body_block);
+        builder.switch_to_block(body_block);
+        let capture = match array {
+            Some((ptr, elem)) => {
+                let size = layout_of(&elem, scopes, types).size;
+                let offset = builder.ins().imul_imm(idx, i64::from(size));
+                let addr = builder.ins().iadd(ptr, offset);
+                self.read_prop(addr, 0, &elem, builder, scopes, types)
+            }
+            None => {
+                let result = self.add_var();
+                builder.declare_var(result, builder.func.dfg.value_type(idx));
+                builder.def_var(result, idx);
+                result
+            }
+        };
+        // the capture is bound as it was read, so an array or string element keeps its length
+        if let TypeTree::AnonFuncInit(f) = types.get(op.body as usize).unwrap() {
+            if let Some(arg) = f.args.first() {
+                let ident = &types.get(*arg as usize).unwrap().into_arg_init().ident;
+                self.sym.table.insert(ident.clone(), capture.as_u32());
+            }
+        }
+        self.exits.push((exit_block, false));
+        let body = self.handle_branch(
+            op.body, None, next_block, false, builder, dtbl, scopes, types, oir,
+        );
+        self.exits.pop();
+        body?;
+        builder.seal_block(next_block);
+        builder.switch_to_block(next_block);
+        let idx = builder.use_var(counter);
+        let next = builder.ins().iadd_imm(idx, 1);
+        builder.def_var(counter, next);
+        builder.ins().jump(header_block, &[]);
+        builder.seal_block(header_block);
+        builder.seal_block(exit_block);
+        builder.switch_to_block(exit_block);
+        Ok(Variable::from_u32(0))
+    }
+    pub fn handle_array_init(
+        &mut self,
+        op: &ArrayInitialize,
+        builder: &mut FunctionBuilder,
+        dtbl: &DataTable,
+        scopes: &Vec<ScopeTable>,
+        types: &Vec<TypeTree>,
+        oir: &mut Oir,
+    ) -> ResultFir<Variable> {
+        let elem = elem_of(&op.curried).unwrap_or(Ty::Unknown);
+        let layout = layout_of(&elem, scopes, types);
+        let slot = self.stack_slot(layout.size * op.vals.len() as u32, layout.align, builder);
+        let addr = builder.ins().stack_addr(I64, slot, 0);
+        for (i, x) in op.vals.iter().enumerate() {
+            let temp = self.recurse(*x, builder, dtbl, scopes, types, oir)?;
+            let offset = layout.size * i as u32;
+            self.write_prop(addr, offset, &elem, temp, builder, scopes, types, oir);
+        }
+        let count = builder.ins().iconst(I64, op.vals.len() as i64);
+        Ok(self.slice(addr, count, elem, builder))
+    }
+    pub fn handle_invoke(
+        &mut self,
+        op: &Invoke,
+        builder: &mut FunctionBuilder,
+        dtbl: &DataTable,
+        scopes: &Vec<ScopeTable>,
+        types: &Vec<TypeTree>,
+        oir: &mut Oir,
+    ) -> ResultFir<Variable> {
+        let ident = match types.get(op.ident as usize).unwrap() {
+            TypeTree::SymbolAccess(sym) => &sym.ident,
+            _ => {
+                return Err(FirError::new(
+                    "only named functions can be invoked".to_string(),
+                ))
+            }
+        };
+        let params = params_of(ident, scopes, types).unwrap_or_default();
+        // arrays are passed as a pointer followed by a length
+        let mut args: Vec<Value> = vec![];
+        let mut signs: Vec<bool> = vec![];
+        for (i, x) in op.args.iter().enumerate() {
+            let result = self.recurse(*x, builder, dtbl, scopes, types, oir)?;
+            args.push(builder.use_var(result));
+            signs.push(op.args_curried.get(i).is_some_and(|t| t.is_signed()));
+            if let Some((len, _)) = self.arrays.get(&result.as_u32()) {
+                args.push(builder.use_var(*len));
+                signs.push(false);
+            }
+            // an optional is passed as its value and whether it is defined
+            if params.get(i).is_some_and(|x| flagged(x).is_some()) {
+                args.push(self.defined_flag(result, builder));
+                signs.push(false);
+            }
+        }
+        let func_id = match oir.ftable.get(ident) {
+            Some(x) => *x,
+            None => return Err(FirError::new(format!("unknown function {}", ident))),
+        };
+        let func_ref = oir.obj_mod.declare_func_in_func(func_id, builder.func);
+        let sig = builder.func.dfg.ext_funcs[func_ref].signature;
+        let params: Vec<Type> = builder.func.dfg.signatures[sig]
+            .params
+            .iter()
+            .map(|x| x.value_type)
+            .collect();
+        for (i, x) in args.iter_mut().enumerate() {
+            if let Some(to) = params.get(i) {
+                *x = self.coerce(*x, *to, signs[i], builder);
+            }
+        }
+        let call = builder.ins().call(func_ref, args.as_slice());
+        let rets = builder.inst_results(call).to_vec();
+        let ret = match rets.first() {
+            Some(x) => *x,
+            None => return Ok(Variable::from_u32(0)),
+        };
+        let result = self.add_var();
+        builder.declare_var(result, builder.func.dfg.value_type(ret));
+        builder.def_var(result, ret);
+        if let (true, Some(code)) = (op.curried.error_union().is_some(), rets.get(1)) {
+            let var = self.add_var();
+            builder.declare_var(var, I64);
+            builder.def_var(var, *code);
+            self.errors.insert(result.as_u32(), var);
+        }
+        if let (true, Some(flag)) = (flagged(&op.curried).is_some(), rets.get(1)) {
+            let var = self.add_var();
+            builder.declare_var(var, I8);
+            builder.def_var(var, *flag);
+            self.optionals.insert(result.as_u32(), var);
+        }
+        Ok(result)
+    }
+    pub fn handle_block(
+        &mut self,
+        op: &Block,
+        builder: &mut FunctionBuilder,
+        dtbl: &DataTable,
+        scopes: &Vec<ScopeTable>,
+        types: &Vec<TypeTree>,
+        oir: &mut Oir,
+    ) -> ResultFir<Variable> {
+        let mut temp: Vec<Variable> = vec![];
+        // what is declared in a block, and what it shadows, ends with it
+        let outer = self.sym.table.clone();
+        for x in op.exprs.iter() {
+            // an if as a statement does not produce a value, its breaks belong to the outer loop
+            let result = match types.get(*x as usize).unwrap() {
+                TypeTree::If(op) => {
+                    self.handle_if(&op, false, builder, dtbl, scopes, types, oir)?
+                }
+                TypeTree::Match(op) => {
+                    self.handle_match(&op, false, builder, dtbl, scopes, types, oir)?
+                }
+                _ => self.recurse(*x, builder, dtbl, scopes, types, oir)?,
+            };
+            temp.push(result);
+        }
+        self.sym.table = outer;
+        Ok(temp.last().copied().unwrap_or(Variable::from_u32(0)))
+    }
+    pub fn handle_ret_void(&mut self, builder: &mut FunctionBuilder) -> ResultFir<Variable> {
+        match self.rets.last() {
+            Some(target) => {
+                let target = *target;
+                self.jump_with(target, &[], builder);
+            }
+            None if self.ret.error_union().is_some() => {
+                let zero = builder.ins().iconst(I64, 0);
+                builder.ins().return_(&[zero, zero]);
+            }
+            None => {
+                builder.ins().return_(&[]);
+            }
+        }
+        Ok(Variable::from_u32(0))
+    }
+    pub fn handle_ret(
+        &mut self,
+        op: &UnaryOp,
+        builder: &mut FunctionBuilder,
+        dtbl: &DataTable,
+        scopes: &Vec<ScopeTable>,
+        types: &Vec<TypeTree>,
+        oir: &mut Oir,
+    ) -> ResultFir<Variable> {
+        let temp = self
+            .recurse(op.val, builder, dtbl, scopes, types, oir)
+            .unwrap();
+        let arg = builder.use_var(temp);
+        match self.rets.last() {
+            Some(target) => {
+                let target = *target;
+                self.jump_with(target, &[arg], builder);
+            }
+            None if self.ret.error_union().is_some() => {
+                let ok = self.ret.error_union().unwrap();
+                let from = types.get(op.val as usize).unwrap().get_curried();
+                let (val, code) = self.union_parts(temp, &from, &ok, builder);
+                builder.ins().return_(&[val, code]);
+            }
+            None if flagged(&self.ret).is_some() => {
+                let typ = builder.func.signature.returns[0].value_type;
+                let from = types.get(op.val as usize).unwrap().get_curried();
+                let val = self.coerce(arg, typ, from.is_signed(), builder);
+                let defined = self.defined_flag(temp, builder);
+                builder.ins().return_(&[val, defined]);
+            }
+            None => match builder.func.signature.returns.first() {
+                Some(ret) => {
+                    let signed = types
+                        .get(op.val as usize)
+                        .unwrap()
+                        .get_curried()
+                        .is_signed();
+                    let arg = self.coerce(arg, ret.value_type, signed, builder);
+                    builder.ins().return_(&[arg]);
+                }
+                None => {
+                    builder.ins().return_(&[]);
+                }
+            },
+        }
+        Ok(temp)
+    }
+    pub fn handle_self_init(
+        &mut self,
+        op: &NoOp,
+        builder: &mut FunctionBuilder,
+    ) -> ResultFir<Variable> {
+        let result = self.add_var();
+        builder.declare_var(result, ty_to_type(&op.curried).unwrap_or(I64));
+        self.sym.table.insert("self".to_string(), result.as_u32());
+        Ok(result)
+    }
+    pub fn handle_self_access(&mut self) -> ResultFir<Variable> {
+        match self.sym.table.get("self") {
+            Some(s) => Ok(Variable::from_u32(*s)),
+            None => Err(FirError::new("self used outside of a method".to_string())),
+        }
+    }
+    // a struct literal lives in its own stack slot, the variable holds its address
+    pub fn handle_struct_init(
+        &mut self,
+        op: &StructInitialize,
+        builder: &mut FunctionBuilder,
+        dtbl: &DataTable,
+        scopes: &Vec<ScopeTable>,
+        types: &Vec<TypeTree>,
+        oir: &mut Oir,
+    ) -> ResultFir<Variable> {
+        let layout = layout_of(&op.curried, scopes, types);
+        let slot = self.stack_slot(layout.size, layout.align, builder);
+        let addr = builder.ins().stack_addr(I64, slot, 0);
+        for x in op.idents.iter() {
+            let init = match types.get(*x as usize).unwrap() {
+                TypeTree::PropInit(init) => init,
+                _ => return Err(FirError::new("expected property in struct".to_string())),
+            };
+            let ident = &types
+                .get(init.left as usize)
+                .unwrap()
+                .into_symbol_init()
+                .ident;
+            let (offset, ty) = match prop_of(&op.curried, ident, scopes, types) {
+                Some(x) => x,
+                None => return Err(FirError::new(format!("unknown property {}", ident))),
+            };
+            let temp = self.recurse(init.right, builder, dtbl, scopes, types, oir)?;
+            self.write_prop(addr, offset, &ty, temp, builder, scopes, types, oir);
+        }
+        let result = self.add_var();
+        builder.declare_var(result, I64);
+        builder.def_var(result, addr);
+        Ok(result)
+    }
+    pub fn handle_prop_access(
+        &mut self,
+        op: &PropAccess,
+        builder: &mut FunctionBuilder,
+        dtbl: &DataTable,
+        scopes: &Vec<ScopeTable>,
+        types: &Vec<TypeTree>,
+        oir: &mut Oir,
+    ) -> ResultFir<Variable> {
+        // arrays and strings know how many elements they hold
+        let prev_ty = types.get(op.prev as usize).unwrap().get_curried();
+        if op.ident == "len" && elem_of(&prev_ty).is_some() {
+            let prev = self.recurse(op.prev, builder, dtbl, scopes, types, oir)?;
+            return match self.arrays.get(&prev.as_u32()) {
+                Some((len, _)) => Ok(*len),
+                None => Err(FirError::new("only arrays have a len".to_string())),
+            };
+        }
+        let (base, offset, ty) = self.prop_addr(op, builder, dtbl, scopes, types, oir)?;
+        Ok(self.read_prop(base, offset, &ty, builder, scopes, types))
+    }
+    // a property as a variable of its own, slices are read as their pointer and length
+    fn read_prop(
+        &mut self,
+        base: Value,
+        offset: u32,
+        ty: &Ty,
+        builder: &mut FunctionBuilder,
+        scopes: &Vec<ScopeTable>,
+        types: &Vec<TypeTree>,
+    ) -> Variable {
+        if let Some(elem) = elem_of(ty) {
+            let ptr = builder
+                .ins()
+                .load(I64, MemFlags::new(), base, offset as i32);
+            let count = builder
+                .ins()
+                .load(I64, MemFlags::new(), base, offset as i32 + 8);
+            return self.slice(ptr, count, elem, builder);
+        }
+        let val = self.load_prop(base, offset, ty, builder, scopes, types);
+        let result = self.add_var();
+        builder.declare_var(result, builder.func.dfg.value_type(val));
+        builder.def_var(result, val);
+        result
     }
-    pub fn run(
+    fn write_prop(
         &mut self,
-        func_def: &FunctionInitialize,
-        ctx: &mut FunctionBuilderContext,
-        namespace: u32,
-        index: u32,
-        dtbl: &DataTable,
+        base: Value,
+        offset: u32,
+        ty: &Ty,
+        var: Variable,
+        builder: &mut FunctionBuilder,
         scopes: &Vec<ScopeTable>,
         types: &Vec<TypeTree>,
         oir: &mut Oir,
-    ) -> Function {
-        let sig = Signature::new(CallConv::Fast);
-        let name = UserFuncName::user(namespace, index);
-        // todo:: types need to be worked out, params and returns defined
-        let mut func = Function::with_name_signature(name, sig);
-        let mut builder = FunctionBuilder::new(&mut func, ctx);
-        let root_block = builder.create_block();
-        let mut result_sets = vec![];
-        for x in func_def.args.iter() {
-            let z = self
-                .recurse(*x, &mut builder, dtbl, scopes, types, oir)
-                .unwrap();
-            builder.func.signature.params.push(AbiParam::new(I64));
-            result_sets.push(z);
-        }
-        builder.append_block_params_for_function_params(root_block);
-        builder.switch_to_block(root_block);
-
-        for (i, x) in result_sets.iter().enumerate() {
-            let res = builder.block_params(root_block)[i];
-            builder.def_var(*x, res);
+    ) -> () {
+        let val = builder.use_var(var);
+        match self.arrays.get(&var.as_u32()).cloned() {
+            Some((len, _)) => {
+                let len = builder.use_var(len);
+                self.store_slice(base, offset, val, len, builder);
+            }
+            None => self.store_prop(base, offset, ty, val, builder, scopes, types, oir),
         }
-        builder.func.signature.returns.push(AbiParam::new(I64));
-
-        let _result = self.recurse(func_def.block, &mut builder, dtbl, scopes, types, oir);
-        builder.seal_block(root_block);
-        builder.finalize();
-        func
     }
-    pub fn handle_arg_init(
+    // a slice is stored as its pointer, followed by its length
+    fn store_slice(
         &mut self,
-        op: &SymbolInit,
+        base: Value,
+        offset: u32,
+        ptr: Value,
+        len: Value,
+        builder: &mut FunctionBuilder,
+    ) -> () {
+        builder
+            .ins()
+            .store(MemFlags::new(), ptr, base, offset as i32);
+        builder
+            .ins()
+            .store(MemFlags::new(), len, base, offset as i32 + 8);
+    }
+    fn slice(
+        &mut self,
+        ptr: Value,
+        count: Value,
+        elem: Ty,
+        builder: &mut FunctionBuilder,
+    ) -> Variable {
+        let result = self.add_var();
+        builder.declare_var(result, I64);
+        builder.def_var(result, ptr);
+        let len = self.add_var();
+        builder.declare_var(len, I64);
+        builder.def_var(len, count);
+        self.arrays.insert(result.as_u32(), (len, elem));
+        result
+    }
+    pub fn handle_array_access(
+        &mut self,
+        op: &ArrayAccess,
+        builder: &mut FunctionBuilder,
+        dtbl: &DataTable,
+        scopes: &Vec<ScopeTable>,
+        types: &Vec<TypeTree>,
+        oir: &mut Oir,
+    ) -> ResultFir<Variable> {
+        let (addr, elem) = self.elem_addr(op, builder, dtbl, scopes, types, oir)?;
+        Ok(self.read_prop(addr, 0, &elem, builder, scopes, types))
+    }
+    // the address of an element, indexing past the end traps
+    fn elem_addr(
+        &mut self,
+        op: &ArrayAccess,
         builder: &mut FunctionBuilder,
         dtbl: &DataTable,
         scopes: &Vec<ScopeTable>,
         types: &Vec<TypeTree>,
         oir: &mut Oir,
+    ) -> ResultFir<(Value, Ty)> {
+        let arr = self.recurse(op.prev, builder, dtbl, scopes, types, oir)?;
+        let (len, elem) = match self.arrays.get(&arr.as_u32()) {
+            Some(x) => x.clone(),
+            None => return Err(FirError::new("only arrays can be indexed".to_string())),
+        };
+        let inner = self.recurse(op.inner, builder, dtbl, scopes, types, oir)?;
+        let idx = builder.use_var(inner);
+        let idx = self.coerce(idx, I64, false, builder);
+        let len = builder.use_var(len);
+        let out = builder
+            .ins()
+            .icmp(IntCC::UnsignedGreaterThanOrEqual, idx, len);
+        builder.ins().trapnz(out, TrapCode::HEAP_OUT_OF_BOUNDS);
+        let ptr = builder.use_var(arr);
+        let size = layout_of(&elem, scopes, types).size;
+        let offset = builder.ins().imul_imm(idx, i64::from(size));
+        Ok((builder.ins().iadd(ptr, offset), elem))
+    }
+    pub fn handle_string_init(
+        &mut self,
+        op: &ArrayInitialize,
+        builder: &mut FunctionBuilder,
+        types: &Vec<TypeTree>,
+        oir: &mut Oir,
     ) -> ResultFir<Variable> {
+        let text = string_of(op, types);
+        let id = oir.declare_string(&text);
+        let gv = oir.obj_mod.declare_data_in_func(id, builder.func);
+        let ptr = builder.ins().global_value(I64, gv);
+        let count = builder.ins().iconst(I64, text.len() as i64);
+        Ok(self.slice(ptr, count, Ty::Char, builder))
+    }
+    pub fn handle_char(&mut self, val: char, builder: &mut FunctionBuilder) -> ResultFir<Variable> {
+        let byte = match u8::try_from(val) {
+            Ok(x) => x,
+            Err(_) => {
+                return Err(FirError::new(format!(
+                    "char {} does not fit in a byte",
+                    val
+                )))
+            }
+        };
         let result = self.add_var();
-        builder.declare_var(result, I64);
-        self.sym.table.insert(op.ident.clone(), result.as_u32());
+        builder.declare_var(result, I8);
+        let temp = builder.ins().iconst(I8, i64::from(byte));
+        builder.def_var(result, temp);
         Ok(result)
     }
-    pub fn handle_const_init(
+    // arrays and structs are already held by address, borrowing them hands out the same address
+    pub fn handle_borrow(
         &mut self,
-        op: &Initialization,
+        op: &UnaryOp,
         builder: &mut FunctionBuilder,
         dtbl: &DataTable,
         scopes: &Vec<ScopeTable>,
         types: &Vec<TypeTree>,
         oir: &mut Oir,
     ) -> ResultFir<Variable> {
-        let result = self.add_var();
-        builder.declare_var(result, I64);
-        let temp = self
-            .recurse(op.right, builder, dtbl, scopes, types, oir)
-            .unwrap();
-        // todo:: optimization: not all paths need declare var if value is only ever read. or something similar, this statement is in the same ballpark, but might not be totally correct
-        let x = builder.use_var(temp);
-        let tt = types.get(op.left as usize).unwrap();
-
-        self.sym
-            .table
-            .insert(tt.into_symbol_init().ident.clone(), temp.as_u32());
-        builder.def_var(temp, x);
-        Ok(temp)
+        let val = self.recurse(op.val, builder, dtbl, scopes, types, oir)?;
+        let ty = types.get(op.val as usize).unwrap().get_curried();
+        if self.arrays.contains_key(&val.as_u32()) || is_aggregate(&ty, scopes, types) {
+            return Ok(val);
+        }
+        Err(FirError::new(
+            "borrowing a scalar is not yet supported".to_string(),
+        ))
     }
-    pub fn handle_invoke(
+    // a copied struct gets a home of its own, scalars are copied by being used
+    pub fn handle_copy(
         &mut self,
-        op: &Invoke,
+        op: &UnaryOp,
         builder: &mut FunctionBuilder,
         dtbl: &DataTable,
         scopes: &Vec<ScopeTable>,
         types: &Vec<TypeTree>,
         oir: &mut Oir,
     ) -> ResultFir<Variable> {
-        let args: Vec<Value> = op
-            .args
-            .iter()
-            .map(|x| {
-                let result = self.recurse(*x, builder, dtbl, scopes, types, oir).unwrap();
-                return builder.use_var(result).clone();
-            })
-            .collect::<Vec<Value>>();
-        // todo:: get this correct with funcref. on how to get this from slt?
-        let call = builder.ins().call(FuncRef::from_u32(0), args.as_slice());
+        let val = self.recurse(op.val, builder, dtbl, scopes, types, oir)?;
+        if self.arrays.contains_key(&val.as_u32()) {
+            return Err(FirError::new(
+                "copying an array is not yet supported".to_string(),
+            ));
+        }
+        if !is_aggregate(&op.curried, scopes, types) {
+            return Ok(val);
+        }
+        let layout = layout_of(&op.curried, scopes, types);
+        let slot = self.stack_slot(layout.size, layout.align, builder);
+        let addr = builder.ins().stack_addr(I64, slot, 0);
+        let src = builder.use_var(val);
+        self.copy(addr, src, layout.size, layout.align, builder, oir);
         let result = self.add_var();
         builder.declare_var(result, I64);
-        builder.def_var(result, builder.inst_results(call)[0]);
+        builder.def_var(result, addr);
         Ok(result)
     }
-    pub fn handle_block(
+    // the address of the struct being accessed, and where the property is within it
+    fn prop_addr(
         &mut self,
-        op: &Block,
+        op: &PropAccess,
         builder: &mut FunctionBuilder,
         dtbl: &DataTable,
         scopes: &Vec<ScopeTable>,
         types: &Vec<TypeTree>,
         oir: &mut Oir,
-    ) -> ResultFir<Variable> {
-        let temp: Vec<Variable> = op
-            .exprs
-            .iter()
-            .map(|x| {
-                return self.recurse(*x, builder, dtbl, scopes, types, oir).unwrap();
-            })
-            .collect();
-        Ok(*temp.last().unwrap())
+    ) -> ResultFir<(Value, u32, Ty)> {
+        let prev = self.recurse(op.prev, builder, dtbl, scopes, types, oir)?;
+        let base = builder.use_var(prev);
+        let prev_ty = types.get(op.prev as usize).unwrap().get_curried();
+        match prop_of(&prev_ty, &op.ident, scopes, types) {
+            Some((offset, ty)) => Ok((base, offset, ty)),
+            None => Err(FirError::new(format!("unknown property {}", op.ident))),
+        }
     }
-    pub fn handle_ret_void(&mut self, builder: &mut FunctionBuilder) -> ResultFir<Variable> {
-        builder.ins().return_(&[]);
-        Ok(Variable::from_u32(0))
+    // a struct within a struct is not loaded, its address is used instead
+    fn load_prop(
+        &mut self,
+        base: Value,
+        offset: u32,
+        ty: &Ty,
+        builder: &mut FunctionBuilder,
+        scopes: &Vec<ScopeTable>,
+        types: &Vec<TypeTree>,
+    ) -> Value {
+        if is_aggregate(ty, scopes, types) {
+            return builder.ins().iadd_imm(base, i64::from(offset));
+        }
+        let typ = ty_to_type(ty).unwrap_or(I64);
+        let (mem, signed) = mem_type(ty, scopes, types);
+        let val = builder
+            .ins()
+            .load(mem, MemFlags::new(), base, offset as i32);
+        self.coerce(val, typ, signed, builder)
     }
-    pub fn handle_ret(
+    fn store_prop(
         &mut self,
-        op: &UnaryOp,
+        base: Value,
+        offset: u32,
+        ty: &Ty,
+        val: Value,
         builder: &mut FunctionBuilder,
-        dtbl: &DataTable,
         scopes: &Vec<ScopeTable>,
         types: &Vec<TypeTree>,
         oir: &mut Oir,
-    ) -> ResultFir<Variable> {
-        let temp = self
-            .recurse(op.val, builder, dtbl, scopes, types, oir)
-            .unwrap();
-        let arg = builder.use_var(temp);
-        builder.ins().return_(&[arg]);
-        Ok(temp)
+    ) -> () {
+        if is_aggregate(ty, scopes, types) {
+            let layout = layout_of(ty, scopes, types);
+            let dest = builder.ins().iadd_imm(base, i64::from(offset));
+            self.copy(dest, val, layout.size, layout.align, builder, oir);
+            return;
+        }
+        let (mem, signed) = mem_type(ty, scopes, types);
+        let val = self.coerce(val, mem, signed, builder);
+        builder
+            .ins()
+            .store(MemFlags::new(), val, base, offset as i32);
+    }
+    fn stack_slot(&mut self, size: u32, align: u32, builder: &mut FunctionBuilder) -> StackSlot {
+        builder.create_sized_stack_slot(StackSlotData::new(
+            StackSlotKind::ExplicitSlot,
+            size,
+            align.trailing_zeros() as u8,
+        ))
+    }
+    fn copy(
+        &mut self,
+        dest: Value,
+        src: Value,
+        size: u32,
+        align: u32,
+        builder: &mut FunctionBuilder,
+        oir: &mut Oir,
+    ) -> () {
+        let config = oir.obj_mod.target_config();
+        builder.emit_small_memory_copy(
+            config,
+            dest,
+            src,
+            u64::from(size),
+            align as u8,
+            align as u8,
+            true,
+            MemFlags::new(),
+        );
     }
     pub fn handle_sym_access(
         &mut self,
@@ -185,13 +1605,30 @@ impl Fir {
         if let Some(s) = sym {
             return Ok(Variable::from_u32(*s));
         }
+        // an error named by its type is its code
+        if let Some(info) = error_info(&op.curried, scopes, types).filter(|x| x.name == op.ident) {
+            let result = self.add_var();
+            builder.declare_var(result, I64);
+            let code = builder.ins().iconst(I64, info.code as i64);
+            builder.def_var(result, code);
+            return Ok(result);
+        }
         let id = dtbl.table.get(&op.ident).unwrap();
         let gv = oir.obj_mod.declare_data_in_func(*id, builder.func);
         let val = builder.ins().global_value(I64, gv);
+        if let Some((count, elem)) = global_array(&op.ident, scopes, types) {
+            let count = builder.ins().iconst(I64, count as i64);
+            return Ok(self.slice(val, count, elem, builder));
+        }
+        if is_aggregate(&op.curried, scopes, types) {
+            let result = self.add_var();
+            builder.declare_var(result, I64);
+            builder.def_var(result, val);
+            return Ok(result);
+        }
+        let loaded = self.load_prop(val, 0, &op.curried, builder, scopes, types);
         let result = self.add_var();
-        builder.declare_var(result, I64);
-        let mem = MemFlags::new();
-        let loaded = builder.ins().load(I64, mem, val, Offset32::new(0));
+        builder.declare_var(result, builder.func.dfg.value_type(loaded));
         builder.def_var(result, loaded);
         Ok(result)
     }
@@ -211,6 +1648,96 @@ impl Fir {
         builder.def_var(result, temp);
         Ok(result)
     }
+    pub fn handle_negate(
+        &mut self,
+        op: &UnaryOp,
+        builder: &mut FunctionBuilder,
+        dtbl: &DataTable,
+        scopes: &Vec<ScopeTable>,
+        types: &Vec<TypeTree>,
+        oir: &mut Oir,
+    ) -> ResultFir<Variable> {
+        let temp = self.recurse(op.val, builder, dtbl, scopes, types, oir)?;
+        let val = builder.use_var(temp);
+        let ty = builder.func.dfg.value_type(val);
+        let negated = match ty.is_float() {
+            true => builder.ins().fneg(val),
+            false => builder.ins().ineg(val),
+        };
+        let result = self.add_var();
+        builder.declare_var(result, ty);
+        builder.def_var(result, negated);
+        Ok(result)
+    }
+    pub fn handle_f64(&mut self, num: f64, builder: &mut FunctionBuilder) -> ResultFir<Variable> {
+        let result = self.add_var();
+        builder.declare_var(result, F64);
+        let temp = builder.ins().f64const(num);
+        builder.def_var(result, temp);
+        Ok(result)
+    }
+    pub fn handle_bool(&mut self, val: bool, builder: &mut FunctionBuilder) -> ResultFir<Variable> {
+        let result = self.add_var();
+        builder.declare_var(result, I8);
+        let temp = builder.ins().iconst(I8, i64::from(val));
+        builder.def_var(result, temp);
+        Ok(result)
+    }
+    pub fn handle_cast(
+        &mut self,
+        op: &BinaryOp,
+        builder: &mut FunctionBuilder,
+        dtbl: &DataTable,
+        scopes: &Vec<ScopeTable>,
+        types: &Vec<TypeTree>,
+        oir: &mut Oir,
+    ) -> ResultFir<Variable> {
+        let temp = self.recurse(op.left, builder, dtbl, scopes, types, oir)?;
+        let val = builder.use_var(temp);
+        let to = match scalar_type(&op.curried) {
+            Some(x) => x,
+            None => return Ok(temp),
+        };
+        let signed = types
+            .get(op.left as usize)
+            .unwrap()
+            .get_curried()
+            .is_signed();
+        let val = self.coerce(val, to, signed, builder);
+        let result = self.add_var();
+        builder.declare_var(result, to);
+        builder.def_var(result, val);
+        Ok(result)
+    }
+    pub fn handle_cmp(
+        &mut self,
+        num: &BinaryOp,
+        int_cc: IntCC,
+        float_cc: FloatCC,
+        builder: &mut FunctionBuilder,
+        dtbl: &DataTable,
+        scopes: &Vec<ScopeTable>,
+        types: &Vec<TypeTree>,
+        oir: &mut Oir,
+    ) -> ResultFir<Variable> {
+        let result = self.add_var();
+        builder.declare_var(result, I8);
+        let (arg1, arg2) = self.operands(num, builder, dtbl, scopes, types, oir)?;
+        let temp = if builder.func.dfg.value_type(arg1).is_float() {
+            builder.ins().fcmp(float_cc, arg1, arg2)
+        } else {
+            let signed = [num.left, num.right]
+                .iter()
+                .any(|x| types.get(*x as usize).unwrap().get_curried().is_signed());
+            let cc = match signed {
+                true => int_cc,
+                false => int_cc.unsigned(),
+            };
+            builder.ins().icmp(cc, arg1, arg2)
+        };
+        builder.def_var(result, temp);
+        Ok(result)
+    }
     pub fn handle_minus(
         &mut self,
         num: &BinaryOp,
@@ -220,17 +1747,13 @@ impl Fir {
         types: &Vec<TypeTree>,
         oir: &mut Oir,
     ) -> ResultFir<Variable> {
+        let (arg1, arg2) = self.operands(num, builder, dtbl, scopes, types, oir)?;
+        let temp = match builder.func.dfg.value_type(arg1).is_float() {
+            true => builder.ins().fsub(arg1, arg2),
+            false => builder.ins().isub(arg1, arg2),
+        };
         let result = self.add_var();
-        builder.declare_var(result, I64);
-        let left = self
-            .recurse(num.left, builder, dtbl, scopes, types, oir)
-            .unwrap();
-        let right = self
-            .recurse(num.right, builder, dtbl, scopes, types, oir)
-            .unwrap();
-        let arg1 = builder.use_var(left);
-        let arg2 = builder.use_var(right);
-        let temp = builder.ins().isub(arg1, arg2);
+        builder.declare_var(result, builder.func.dfg.value_type(temp));
         builder.def_var(result, temp);
         Ok(result)
     }
@@ -243,20 +1766,80 @@ impl Fir {
         types: &Vec<TypeTree>,
         oir: &mut Oir,
     ) -> ResultFir<Variable> {
+        let (arg1, arg2) = self.operands(num, builder, dtbl, scopes, types, oir)?;
+        let temp = match builder.func.dfg.value_type(arg1).is_float() {
+            true => builder.ins().fadd(arg1, arg2),
+            false => builder.ins().iadd(arg1, arg2),
+        };
         let result = self.add_var();
-        builder.declare_var(result, I64);
-        let left = self
-            .recurse(num.left, builder, dtbl, scopes, types, oir)
-            .unwrap();
-        let right = self
-            .recurse(num.right, builder, dtbl, scopes, types, oir)
-            .unwrap();
-        let arg1 = builder.use_var(left);
-        let arg2 = builder.use_var(right);
-        let temp = builder.ins().iadd(arg1, arg2);
+        builder.declare_var(result, builder.func.dfg.value_type(temp));
+        builder.def_var(result, temp);
+        Ok(result)
+    }
+    pub fn handle_mul(
+        &mut self,
+        num: &BinaryOp,
+        builder: &mut FunctionBuilder,
+        dtbl: &DataTable,
+        scopes: &Vec<ScopeTable>,
+        types: &Vec<TypeTree>,
+        oir: &mut Oir,
+    ) -> ResultFir<Variable> {
+        let (arg1, arg2) = self.operands(num, builder, dtbl, scopes, types, oir)?;
+        let temp = match builder.func.dfg.value_type(arg1).is_float() {
+            true => builder.ins().fmul(arg1, arg2),
+            false => builder.ins().imul(arg1, arg2),
+        };
+        let result = self.add_var();
+        builder.declare_var(result, builder.func.dfg.value_type(temp));
         builder.def_var(result, temp);
         Ok(result)
     }
+    // both sides of a binary operation are brought to the same type. a literal takes the type of
+    // the other side, floats win over integers, otherwise the wider integer wins
+    fn operands(
+        &mut self,
+        num: &BinaryOp,
+        builder: &mut FunctionBuilder,
+        dtbl: &DataTable,
+        scopes: &Vec<ScopeTable>,
+        types: &Vec<TypeTree>,
+        oir: &mut Oir,
+    ) -> ResultFir<(Value, Value)> {
+        let left = self.recurse(num.left, builder, dtbl, scopes, types, oir)?;
+        let right = self.recurse(num.right, builder, dtbl, scopes, types, oir)?;
+        let arg1 = builder.use_var(left);
+        let arg2 = builder.use_var(right);
+        let t1 = builder.func.dfg.value_type(arg1);
+        let t2 = builder.func.dfg.value_type(arg2);
+        if t1 == t2 {
+            return Ok((arg1, arg2));
+        }
+        let left_tree = types.get(num.left as usize).unwrap();
+        let right_tree = types.get(num.right as usize).unwrap();
+        let (lsigned, rsigned) = (
+            left_tree.get_curried().is_signed(),
+            right_tree.get_curried().is_signed(),
+        );
+        let target = if t1.is_float() != t2.is_float() {
+            match t1.is_float() {
+                true => t1,
+                false => t2,
+            }
+        } else if is_literal(left_tree) {
+            t2
+        } else if is_literal(right_tree) {
+            t1
+        } else if t1.bits() >= t2.bits() {
+            t1
+        } else {
+            t2
+        };
+        Ok((
+            self.coerce(arg1, target, lsigned, builder),
+            self.coerce(arg2, target, rsigned, builder),
+        ))
+    }
     pub fn recurse(
         &mut self,
         idx: TypeTreeIndex,
@@ -272,20 +1855,246 @@ impl Fir {
             TypeTree::Invoke(op) => self.handle_invoke(&op, builder, dtbl, scopes, types, oir),
             TypeTree::Plus(op) => self.handle_plus(&op, builder, dtbl, scopes, types, oir),
             TypeTree::Minus(op) => self.handle_minus(&op, builder, dtbl, scopes, types, oir),
+            TypeTree::Multiply(op) => self.handle_mul(&op, builder, dtbl, scopes, types, oir),
             TypeTree::Return(op) => self.handle_ret(&op, builder, dtbl, scopes, types, oir),
             TypeTree::ReturnVoid(_) => self.handle_ret_void(builder),
             TypeTree::ConstInit(op) => {
                 self.handle_const_init(&op, builder, dtbl, scopes, types, oir)
             }
             TypeTree::ArgInit(op) => self.handle_arg_init(&op, builder, dtbl, scopes, types, oir),
+            TypeTree::SelfInit(op) => self.handle_self_init(&op, builder),
+            TypeTree::SelfAccess(_) => self.handle_self_access(),
+            TypeTree::StructInit(op) => {
+                self.handle_struct_init(&op, builder, dtbl, scopes, types, oir)
+            }
+            TypeTree::PropAccess(op) => {
+                self.handle_prop_access(&op, builder, dtbl, scopes, types, oir)
+            }
             TypeTree::SymbolAccess(op) => {
                 self.handle_sym_access(&op, dtbl, scopes, types, oir, builder)
             }
+            TypeTree::MutInit(op) => self.handle_const_init(&op, builder, dtbl, scopes, types, oir),
+            TypeTree::As(op) => self.handle_reassign(&op, None, builder, dtbl, scopes, types, oir),
+            TypeTree::PlusAs(op)
+            | TypeTree::MinusAs(op)
+            | TypeTree::MultiplyAs(op)
+            | TypeTree::DivideAs(op)
+            | TypeTree::ModAs(op)
+            | TypeTree::OrAs(op)
+            | TypeTree::XorAs(op)
+            | TypeTree::LShiftAs(op)
+            | TypeTree::RShiftAs(op) => {
+                self.handle_reassign(&op, Some(expr), builder, dtbl, scopes, types, oir)
+            }
+            TypeTree::While(op) => self.handle_while(&op, builder, dtbl, scopes, types, oir),
+            TypeTree::For(op) => self.handle_for(&op, builder, dtbl, scopes, types, oir),
+            TypeTree::ArrayInit(op) => {
+                self.handle_array_init(&op, builder, dtbl, scopes, types, oir)
+            }
+            TypeTree::BreakVoid(_) => self.handle_break_void(builder),
+            TypeTree::If(op) => self.handle_if(&op, true, builder, dtbl, scopes, types, oir),
+            TypeTree::Match(op) => self.handle_match(&op, true, builder, dtbl, scopes, types, oir),
+            TypeTree::VariantAccess(op) => {
+                self.handle_variant(&op, builder, dtbl, scopes, types, oir)
+            }
+            TypeTree::VariantInit(op) => self.handle_tag_init(
+                &op.curried,
+                &op.ident,
+                op.payload,
+                builder,
+                dtbl,
+                scopes,
+                types,
+                oir,
+            ),
+            TypeTree::Break(op) => self.handle_break(&op, builder, dtbl, scopes, types, oir),
+            TypeTree::BubbleError(op) => {
+                self.handle_bubble_error(&op, builder, dtbl, scopes, types, oir)
+            }
+            TypeTree::Catch(op) => self.handle_catch(&op, builder, dtbl, scopes, types, oir),
+            TypeTree::BubbleUndef(op) => {
+                self.handle_bubble_undefined(&op, builder, dtbl, scopes, types, oir)
+            }
+            TypeTree::UndefinedValue => self.handle_undefined(builder),
+            TypeTree::CastAs(op) => self.handle_cast(&op, builder, dtbl, scopes, types, oir),
+            TypeTree::Gt(op) => self.handle_cmp(
+                &op,
+                IntCC::SignedGreaterThan,
+                FloatCC::GreaterThan,
+                builder,
+                dtbl,
+                scopes,
+                types,
+                oir,
+            ),
+            TypeTree::Eq(op) => self.handle_cmp(
+                &op,
+                IntCC::Equal,
+                FloatCC::Equal,
+                builder,
+                dtbl,
+                scopes,
+                types,
+                oir,
+            ),
+            TypeTree::NotEq(op) => self.handle_cmp(
+                &op,
+                IntCC::NotEqual,
+                FloatCC::NotEqual,
+                builder,
+                dtbl,
+                scopes,
+                types,
+                oir,
+            ),
             TypeTree::U64(op) => self.handle_u64(*op, builder),
             TypeTree::I64(op) => self.handle_i64(*op, builder),
+            TypeTree::F64(op) => self.handle_f64(*op, builder),
+            TypeTree::Negate(op) => self.handle_negate(&op, builder, dtbl, scopes, types, oir),
+            TypeTree::BoolValue(op) => self.handle_bool(*op, builder),
+            TypeTree::Char(op) => self.handle_char(*op, builder),
+            TypeTree::StringInit(op) => self.handle_string_init(&op, builder, types, oir),
+            TypeTree::ArrayAccess(op) => {
+                self.handle_array_access(&op, builder, dtbl, scopes, types, oir)
+            }
+            TypeTree::ReadBorrow(op) | TypeTree::MutBorrow(op) => {
+                self.handle_borrow(&op, builder, dtbl, scopes, types, oir)
+            }
+            TypeTree::Copy(op) | TypeTree::Clone(op) => {
+                self.handle_copy(&op, builder, dtbl, scopes, types, oir)
+            }
             _ => panic!("developer error unexpected expression {:?}", expr),
         }
     }
+    // a compound assignment like `+=` combines the current value with the new one. division,
+    // remainder and right shifts follow the sign of what is assigned to
+    fn combine(
+        &self,
+        op: &TypeTree,
+        x: Value,
+        y: Value,
+        signed: bool,
+        builder: &mut FunctionBuilder,
+    ) -> Value {
+        let float = builder.func.dfg.value_type(x).is_float();
+        let ins = builder.ins();
+        match (op, float, signed) {
+            (TypeTree::PlusAs(_), true, _) => ins.fadd(x, y),
+            (TypeTree::PlusAs(_), false, _) => ins.iadd(x, y),
+            (TypeTree::MinusAs(_), true, _) => ins.fsub(x, y),
+            (TypeTree::MinusAs(_), false, _) => ins.isub(x, y),
+            (TypeTree::MultiplyAs(_), true, _) => ins.fmul(x, y),
+            (TypeTree::MultiplyAs(_), false, _) => ins.imul(x, y),
+            (TypeTree::DivideAs(_), true, _) => ins.fdiv(x, y),
+            (TypeTree::DivideAs(_), false, true) => ins.sdiv(x, y),
+            (TypeTree::DivideAs(_), false, false) => ins.udiv(x, y),
+            (TypeTree::ModAs(_), false, true) => ins.srem(x, y),
+            (TypeTree::ModAs(_), false, false) => ins.urem(x, y),
+            (TypeTree::OrAs(_), false, _) => ins.bor(x, y),
+            (TypeTree::XorAs(_), false, _) => ins.bxor(x, y),
+            (TypeTree::LShiftAs(_), false, _) => ins.ishl(x, y),
+            (TypeTree::RShiftAs(_), false, true) => ins.sshr(x, y),
+            (TypeTree::RShiftAs(_), false, false) => ins.ushr(x, y),
+            _ => panic!("developer error unexpected compound assignment {:?}", op),
+        }
+    }
+    // only what is non zero, is true
+    fn truthy(&mut self, val: Value, builder: &mut FunctionBuilder) -> Value {
+        let typ = builder.func.dfg.value_type(val);
+        if typ.is_float() {
+            let zero = match typ {
+                F32 => builder.ins().f32const(0.0),
+                _ => builder.ins().f64const(0.0),
+            };
+            return builder.ins().fcmp(FloatCC::NotEqual, val, zero);
+        }
+        val
+    }
+    // block params for a jump target are made on the first jump carrying values
+    fn jump_with(&mut self, target: IrBlock, args: &[Value], builder: &mut FunctionBuilder) {
+        if builder.block_params(target).len() < args.len() {
+            for x in args {
+                let typ = builder.func.dfg.value_type(*x);
+                builder.append_block_param(target, typ);
+            }
+        }
+        // every jump to the block gives values of the types of the first
+        let params: Vec<Type> = builder
+            .block_params(target)
+            .iter()
+            .map(|x| builder.func.dfg.value_type(*x))
+            .collect();
+        let args: Vec<Value> = args
+            .iter()
+            .zip(params)
+            .map(|(x, to)| self.coerce(*x, to, false, builder))
+            .collect();
+        builder.ins().jump(target, &args);
+    }
+    // an error union is held as its value and its error code. an error has no value, and a value
+    // has no error
+    fn union_parts(
+        &mut self,
+        var: Variable,
+        from: &Ty,
+        ok: &Ty,
+        builder: &mut FunctionBuilder,
+    ) -> (Value, Value) {
+        let typ = ty_to_type(ok).unwrap_or(I64);
+        if let Some(code) = self.errors.get(&var.as_u32()).copied() {
+            return (builder.use_var(var), builder.use_var(code));
+        }
+        if from.is_error() {
+            let code = builder.use_var(var);
+            return (self.zero(typ, builder), code);
+        }
+        let val = match ty_to_type(from) {
+            Some(_) => {
+                let val = builder.use_var(var);
+                self.coerce(val, typ, from.is_signed(), builder)
+            }
+            None => self.zero(typ, builder),
+        };
+        (val, builder.ins().iconst(I64, 0))
+    }
+    // a value given to an optional is defined, unless it is an optional itself
+    fn defined_flag(&mut self, var: Variable, builder: &mut FunctionBuilder) -> Value {
+        match self.optionals.get(&var.as_u32()) {
+            Some(flag) => builder.use_var(*flag),
+            None => builder.ins().iconst(I8, 1),
+        }
+    }
+    fn zero(&self, typ: Type, builder: &mut FunctionBuilder) -> Value {
+        match typ {
+            F32 => builder.ins().f32const(0.0),
+            F64 => builder.ins().f64const(0.0),
+            x => builder.ins().iconst(x, 0),
+        }
+    }
+    fn coerce(&self, val: Value, to: Type, signed: bool, builder: &mut FunctionBuilder) -> Value {
+        let from = builder.func.dfg.value_type(val);
+        if from == to {
+            return val;
+        }
+        match (from.is_float(), to.is_float()) {
+            (false, false) if from.bits() > to.bits() => builder.ins().ireduce(to, val),
+            (false, false) if signed => builder.ins().sextend(to, val),
+            (false, false) => builder.ins().uextend(to, val),
+            (false, true) if signed => builder.ins().fcvt_from_sint(to, val),
+            (false, true) => builder.ins().fcvt_from_uint(to, val),
+            (true, false) if signed => builder.ins().fcvt_to_sint_sat(to, val),
+            (true, false) => builder.ins().fcvt_to_uint_sat(to, val),
+            (true, true) if from.bits() > to.bits() => builder.ins().fdemote(to, val),
+            (true, true) => builder.ins().fpromote(to, val),
+        }
+    }
+    fn is_terminated(&self, builder: &FunctionBuilder) -> bool {
+        let block = builder.current_block().unwrap();
+        match builder.func.layout.last_inst(block) {
+            Some(inst) => builder.func.dfg.insts[inst].opcode().is_terminator(),
+            None => false,
+        }
+    }
     pub fn get_ir(self, func: &Function) -> Result<DisplayFunction> {
         let flags = settings::Flags::new(settings::builder());
         let res = verify_function(func, &flags);
@@ -300,3 +2109,156 @@ impl Fir {
         temp
     }
 }
+
+fn make_signature(args: &Vec<Ty>, ret: &Ty, call_conv: CallConv) -> Signature {
+    let mut sig = Signature::new(call_conv);
+    for x in args.iter() {
+        sig.params.push(AbiParam::new(ty_to_type(x).unwrap_or(I64)));
+        if elem_of(x).is_some() {
+            sig.params.push(AbiParam::new(I64));
+        }
+        if flagged(x).is_some() {
+            sig.params.push(AbiParam::new(I8));
+        }
+    }
+    // an error union returns its value, and then its error code
+    if let Some(ok) = ret.error_union() {
+        sig.returns
+            .push(AbiParam::new(ty_to_type(&ok).unwrap_or(I64)));
+        sig.returns.push(AbiParam::new(I64));
+        return sig;
+    }
+    // an optional without a niche returns its value, and then whether it is defined
+    if let Some(some) = flagged(ret) {
+        sig.returns
+            .push(AbiParam::new(ty_to_type(&some).unwrap_or(I64)));
+        sig.returns.push(AbiParam::new(I8));
+        return sig;
+    }
+    if let Some(ret) = ty_to_type(ret) {
+        sig.returns.push(AbiParam::new(ret));
+    }
+    sig
+}
+
+// the cranelift type a value of ty is held in, void has none
+pub fn ty_to_type(ty: &Ty) -> Option<Type> {
+    match ty {
+        Ty::Void | Ty::Never => None,
+        Ty::F64 | Ty::FloatLiteral => Some(F64),
+        Ty::F32 => Some(F32),
+        Ty::F128 => Some(F128),
+        Ty::Bool | Ty::Bit => Some(I8),
+        Ty::Const(x) | Ty::Mut(x) => ty_to_type(x),
+        // decimals are only held in their bits, they have no arithmetic yet
+        x if x.is_int() || x.is_utf() || x.is_decimal() => Type::int(x.bits()? as u16),
+        x if flagged(x).is_some() => ty_to_type(&flagged(x).unwrap()),
+        _ => Some(I64),
+    }
+}
+
+// how a value is held in memory. enums are i64 while in use, and their backing type in memory
+fn mem_type(ty: &Ty, scopes: &Vec<ScopeTable>, types: &Vec<TypeTree>) -> (Type, bool) {
+    match enum_info(ty, scopes, types) {
+        Some(info) => (
+            ty_to_type(&info.backing).unwrap_or(I64),
+            info.backing.is_signed(),
+        ),
+        None => (ty_to_type(ty).unwrap_or(I64), ty.is_signed()),
+    }
+}
+
+// what an optional holds, when it is held as that value and a defined flag. an error union
+// holding an optional is not one
+fn flagged(ty: &Ty) -> Option<Ty> {
+    match ty.error_union() {
+        Some(_) => None,
+        None => ty.optional().filter(|x| !has_niche(x)),
+    }
+}
+
+// the parameters of a function declared in this module
+fn params_of(ident: &str, scopes: &Vec<ScopeTable>, types: &Vec<TypeTree>) -> Option<Vec<Ty>> {
+    let idx = scopes.get(0)?.this_tree.get(ident)?;
+    match types.get(*idx as usize)? {
+        TypeTree::FuncInit(x) => Some(x.args_curried.clone()),
+        TypeTree::ExternInit(x) => Some(x.args_curried.clone()),
+        TypeTree::ImportInit(x) => x.args_curried.clone(),
+        _ => None,
+    }
+}
+
+// the cranelift type of a value known to be a number, bool, or char
+fn scalar_type(ty: &Ty) -> Option<Type> {
+    match ty {
+        Ty::Const(x) | Ty::Mut(x) => scalar_type(x),
+        x if x.is_int() || x.is_float() || x.is_utf() || x.is_decimal() => ty_to_type(x),
+        Ty::Bool => ty_to_type(ty),
+        _ => None,
+    }
+}
+
+// the length and element type of an array defined at the top level, the elements are held as
+// the declared type says
+fn global_array(
+    ident: &str,
+    scopes: &Vec<ScopeTable>,
+    types: &Vec<TypeTree>,
+) -> Option<(usize, Ty)> {
+    let idx = scopes.first()?.this_tree.get(ident)?;
+    let init = match types.get(*idx as usize)? {
+        TypeTree::TopConstInit(x) | TypeTree::TopMutInit(x) => x,
+        _ => return None,
+    };
+    match types.get(init.right as usize)? {
+        TypeTree::StringInit(x) => Some((string_of(x, types).len(), Ty::Char)),
+        TypeTree::ArrayInit(x) => Some((x.vals.len(), elem_of(&init.curried)?)),
+        _ => None,
+    }
+}
+
+fn string_of(op: &ArrayInitialize, types: &Vec<TypeTree>) -> String {
+    op.vals
+        .iter()
+        .filter_map(|x| match types.get(*x as usize) {
+            Some(TypeTree::Char(c)) => Some(*c),
+            _ => None,
+        })
+        .collect()
+}
+
+// the value of a pattern that is known when compiling
+fn pattern_const(
+    idx: TypeTreeIndex,
+    scopes: &Vec<ScopeTable>,
+    types: &Vec<TypeTree>,
+) -> Option<i128> {
+    match types.get(idx as usize)? {
+        TypeTree::U64(x) => Some(*x as i128),
+        TypeTree::I64(x) => Some(*x as i128),
+        TypeTree::Char(x) => Some(*x as i128),
+        TypeTree::BoolValue(x) => Some(*x as i128),
+        TypeTree::VariantAccess(x) => discriminant_of(&x.curried, &x.ident, scopes, types),
+        TypeTree::VariantInit(x) => discriminant_of(&x.curried, &x.ident, scopes, types),
+        _ => None,
+    }
+}
+
+// structs and tags are held in memory, their variables hold the address
+fn is_aggregate(ty: &Ty, scopes: &Vec<ScopeTable>, types: &Vec<TypeTree>) -> bool {
+    match ty {
+        Ty::Const(x) | Ty::Mut(x) => is_aggregate(x, scopes, types),
+        Ty::Custom(name) => {
+            struct_info(name, scopes, types).is_some() || tag_info(ty, scopes, types).is_some()
+        }
+        _ => false,
+    }
+}
+
+fn is_literal(tree: &TypeTree) -> bool {
+    match tree {
+        // only literals can be negated
+        TypeTree::U64(_) | TypeTree::I64(_) | TypeTree::F64(_) | TypeTree::Negate(_) => true,
+        _ => false,
+    }
+}
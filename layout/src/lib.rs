@@ -0,0 +1,355 @@
+use scopetable::ScopeTable;
+use types::*;
+
+// Data layout of a type, as it is held in memory.
+// offsets are only present for structs, one per property in declaration order.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Layout {
+    pub size: u32,
+    pub align: u32,
+    pub offsets: Vec<u32>,
+}
+
+impl Layout {
+    pub fn scalar(size: u32) -> Self {
+        Layout {
+            size,
+            align: size,
+            offsets: vec![],
+        }
+    }
+}
+
+// structs are declared at the top level, their properties are found through the global scope
+pub fn struct_info<'a>(
+    name: &str,
+    scopes: &Vec<ScopeTable>,
+    types: &'a Vec<TypeTree>,
+) -> Option<&'a StructInfo> {
+    let idx = scopes.get(0)?.this_tree.get(name)?;
+    match types.get(*idx as usize)? {
+        TypeTree::StructInfo(x) => Some(x),
+        _ => None,
+    }
+}
+
+// enums, tags, and errors are declared at the top level, found by the name of their type
+fn variant_info<'a>(
+    ty: &Ty,
+    scopes: &Vec<ScopeTable>,
+    types: &'a Vec<TypeTree>,
+) -> Option<&'a TypeTree> {
+    let name = match ty {
+        Ty::Const(x) | Ty::Mut(x) | Ty::ReadBorrow(x) | Ty::MutBorrow(x) => {
+            return variant_info(x, scopes, types)
+        }
+        Ty::Custom(x) | Ty::CustomError(x) => x,
+        _ => return None,
+    };
+    let idx = scopes.get(0)?.this_tree.get(name)?;
+    types.get(*idx as usize)
+}
+
+pub fn enum_info<'a>(
+    ty: &Ty,
+    scopes: &Vec<ScopeTable>,
+    types: &'a Vec<TypeTree>,
+) -> Option<&'a EnumInfo> {
+    match variant_info(ty, scopes, types)? {
+        TypeTree::EnumInfo(x) => Some(x),
+        _ => None,
+    }
+}
+
+pub fn error_info<'a>(
+    ty: &Ty,
+    scopes: &Vec<ScopeTable>,
+    types: &'a Vec<TypeTree>,
+) -> Option<&'a ErrorInfo> {
+    match variant_info(ty, scopes, types)? {
+        TypeTree::ErrorInfo(x) => Some(x),
+        _ => None,
+    }
+}
+
+pub fn tag_info<'a>(
+    ty: &Ty,
+    scopes: &Vec<ScopeTable>,
+    types: &'a Vec<TypeTree>,
+) -> Option<&'a TagInfo> {
+    match variant_info(ty, scopes, types)? {
+        TypeTree::TagInfo(x) => Some(x),
+        _ => None,
+    }
+}
+
+// the variants of an enum, tag, or error type by name, in declaration order
+pub fn variants_of(
+    ty: &Ty,
+    scopes: &Vec<ScopeTable>,
+    types: &Vec<TypeTree>,
+) -> Option<Vec<String>> {
+    let props = match variant_info(ty, scopes, types)? {
+        TypeTree::EnumInfo(x) => &x.props,
+        TypeTree::TagInfo(x) => &x.props,
+        TypeTree::ErrorInfo(x) => &x.props,
+        _ => return None,
+    };
+    let names = props.iter().map(|x| match types.get(*x as usize) {
+        Some(TypeTree::SymbolInit(x)) => x.ident.clone(),
+        Some(TypeTree::DeclaratorInfo(x)) => x.name.clone(),
+        _ => "".to_string(),
+    });
+    Some(names.collect())
+}
+
+// the value of a variant, enums may give their own, otherwise it's the declaration order
+pub fn discriminant_of(
+    ty: &Ty,
+    ident: &str,
+    scopes: &Vec<ScopeTable>,
+    types: &Vec<TypeTree>,
+) -> Option<i128> {
+    let pos = variants_of(ty, scopes, types)?
+        .iter()
+        .position(|x| x == ident)?;
+    match variant_info(ty, scopes, types)? {
+        TypeTree::EnumInfo(x) => x.values.get(pos).copied(),
+        // error variants take the codes after the error's own
+        TypeTree::ErrorInfo(x) => Some((x.code + 1 + pos) as i128),
+        _ => Some(pos as i128),
+    }
+}
+
+pub fn layout_of(ty: &Ty, scopes: &Vec<ScopeTable>, types: &Vec<TypeTree>) -> Layout {
+    match ty {
+        Ty::Bool | Ty::Bit => Layout::scalar(1),
+        x if x.bits().is_some() => Layout::scalar(x.bits().unwrap() / 8),
+        Ty::Const(x) | Ty::Mut(x) => layout_of(x, scopes, types),
+        // an error union is its error code, followed by the value it holds when there is no error
+        Ty::Tag(_) if ty.error_union().is_some() => {
+            tag_layout(&vec![ty.error_union().unwrap()], scopes, types)
+        }
+        Ty::Tag(_) if ty.optional().is_some() => {
+            optional_layout(&ty.optional().unwrap(), scopes, types)
+        }
+        // a pointer and a length
+        Ty::Array(_) | Ty::String => Layout {
+            size: 16,
+            align: 8,
+            offsets: vec![0, 8],
+        },
+        Ty::Custom(name) => match struct_info(name, scopes, types) {
+            Some(info) => struct_layout(&info.types, scopes, types),
+            None => match (tag_info(ty, scopes, types), enum_info(ty, scopes, types)) {
+                (Some(info), _) => tag_layout(&info.types, scopes, types),
+                // enums are held as the integer type backing them
+                (_, Some(info)) => layout_of(&info.backing, scopes, types),
+                _ => Layout::scalar(8),
+            },
+        },
+        _ => Layout::scalar(8),
+    }
+}
+
+// each property is placed at the next offset meeting its alignment, the struct is aligned to its
+// largest property, and padded to a multiple of that alignment
+pub fn struct_layout(props: &Vec<Ty>, scopes: &Vec<ScopeTable>, types: &Vec<TypeTree>) -> Layout {
+    let mut offsets = vec![];
+    let mut size = 0;
+    let mut align = 1;
+    for x in props.iter() {
+        let prop = layout_of(x, scopes, types);
+        let offset = align_to(size, prop.align);
+        offsets.push(offset);
+        size = offset + prop.size;
+        align = align.max(prop.align);
+    }
+    Layout {
+        size: align_to(size, align),
+        align,
+        offsets,
+    }
+}
+
+// the type a tag's discriminant is held in
+pub const TAG_DISCRIMINANT: Ty = Ty::U32;
+
+// a tag is its discriminant followed by the payloads, which share the same memory. the payloads
+// start at the alignment of the most aligned one, the tag is as large as its largest variant.
+// offsets are the discriminant, then the payloads
+pub fn tag_layout(payloads: &Vec<Ty>, scopes: &Vec<ScopeTable>, types: &Vec<TypeTree>) -> Layout {
+    let disc = layout_of(&TAG_DISCRIMINANT, scopes, types);
+    let mut size = 0;
+    let mut align = disc.align;
+    for x in payloads.iter().filter(|x| **x != Ty::Void) {
+        let payload = layout_of(x, scopes, types);
+        size = size.max(payload.size);
+        align = align.max(payload.align);
+    }
+    let offset = align_to(disc.size, align);
+    Layout {
+        size: align_to(offset + size, align),
+        align,
+        offsets: vec![0, offset],
+    }
+}
+
+// what each element of an array or string is held as, one after another. borrowed elements are
+// still held in place
+pub fn elem_of(ty: &Ty) -> Option<Ty> {
+    match ty {
+        Ty::Const(x) | Ty::Mut(x) | Ty::ReadBorrow(x) | Ty::MutBorrow(x) => elem_of(x),
+        Ty::Array(x) => match x.as_ref() {
+            Ty::ReadBorrow(e) | Ty::MutBorrow(e) => Some(*e.clone()),
+            e => Some(e.clone()),
+        },
+        Ty::String => Some(Ty::Char),
+        _ => None,
+    }
+}
+
+// a pointer is never 0, an optional pointer uses 0 for undefined instead of a flag
+pub fn has_niche(ty: &Ty) -> bool {
+    match ty {
+        Ty::Const(x) | Ty::Mut(x) => has_niche(x),
+        Ty::ReadBorrow(_) | Ty::MutBorrow(_) => true,
+        _ => false,
+    }
+}
+
+// an optional is held as a flag set when it is defined, followed by the value. offsets are the
+// flag, then the value. an optional with a niche is held just as its value, and has no offsets
+pub fn optional_layout(some: &Ty, scopes: &Vec<ScopeTable>, types: &Vec<TypeTree>) -> Layout {
+    if has_niche(some) {
+        return layout_of(some, scopes, types);
+    }
+    struct_layout(&vec![Ty::Bool, some.clone()], scopes, types)
+}
+
+// the offset and type of a variant's payload, for a tag type
+pub fn payload_of(
+    ty: &Ty,
+    variant: &str,
+    scopes: &Vec<ScopeTable>,
+    types: &Vec<TypeTree>,
+) -> Option<(u32, Ty)> {
+    let info = tag_info(ty, scopes, types)?;
+    let idx = variants_of(ty, scopes, types)?
+        .iter()
+        .position(|x| x == variant)?;
+    let layout = tag_layout(&info.types, scopes, types);
+    Some((layout.offsets[1], info.types.get(idx)?.clone()))
+}
+
+// the offset and type of a property by name, for a struct type
+pub fn prop_of(
+    ty: &Ty,
+    prop: &str,
+    scopes: &Vec<ScopeTable>,
+    types: &Vec<TypeTree>,
+) -> Option<(u32, Ty)> {
+    match ty {
+        Ty::Const(x) | Ty::Mut(x) | Ty::ReadBorrow(x) | Ty::MutBorrow(x) => {
+            prop_of(x, prop, scopes, types)
+        }
+        Ty::Custom(name) => {
+            let info = struct_info(name, scopes, types)?;
+            let idx = info.props.iter().position(|x| x == prop)?;
+            let layout = struct_layout(&info.types, scopes, types);
+            Some((layout.offsets[idx], info.types[idx].clone()))
+        }
+        _ => None,
+    }
+}
+
+fn align_to(offset: u32, align: u32) -> u32 {
+    (offset + align - 1) / align * align
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    fn it_should_layout_struct() {
+        let scopes = vec![];
+        let types = vec![];
+        let props = vec![Ty::U8, Ty::F64, Ty::U32, Ty::Bool];
+        let layout = struct_layout(&props, &scopes, &types);
+        assert_eq!(layout.offsets, vec![0, 8, 16, 20]);
+        assert_eq!(layout.size, 24);
+        assert_eq!(layout.align, 8);
+    }
+    #[test]
+    fn it_should_layout_tag_by_largest_variant() {
+        let scopes = vec![];
+        let types = vec![];
+        let payloads = vec![Ty::U8, Ty::Void, Ty::F64, Ty::U16];
+        let layout = tag_layout(&payloads, &scopes, &types);
+        assert_eq!(layout.offsets, vec![0, 8]);
+        assert_eq!(layout.size, 16);
+        assert_eq!(layout.align, 8);
+        let layout = tag_layout(&vec![Ty::U8, Ty::Void], &scopes, &types);
+        assert_eq!(layout.offsets, vec![0, 4]);
+        assert_eq!(layout.size, 8);
+        assert_eq!(layout.align, 4);
+        let layout = tag_layout(&vec![Ty::Void], &scopes, &types);
+        assert_eq!(layout.offsets, vec![0, 4]);
+        assert_eq!(layout.size, 4);
+    }
+    #[test]
+    fn it_should_layout_optional_pointers_in_their_niche() {
+        let scopes = vec![];
+        let types = vec![];
+        let ptr = Ty::Tag(vec![Ty::ReadBorrow(Box::new(Ty::U64)), Ty::Undefined]);
+        let layout = layout_of(&ptr, &scopes, &types);
+        assert_eq!(layout, Layout::scalar(8));
+        let layout = layout_of(&Ty::Tag(vec![Ty::U8, Ty::Undefined]), &scopes, &types);
+        assert_eq!(layout.offsets, vec![0, 1]);
+        assert_eq!(layout.size, 2);
+        let layout = layout_of(&Ty::Tag(vec![Ty::U64, Ty::Undefined]), &scopes, &types);
+        assert_eq!(layout.offsets, vec![0, 8]);
+        assert_eq!(layout.size, 16);
+    }
+    #[test]
+    fn it_should_layout_error_unions_as_code_and_value() {
+        let scopes = vec![];
+        let types = vec![];
+        let layout = layout_of(&Ty::Tag(vec![Ty::U64, Ty::Error]), &scopes, &types);
+        assert_eq!(layout.offsets, vec![0, 8]);
+        assert_eq!(layout.size, 16);
+        let layout = layout_of(&Ty::Tag(vec![Ty::U8, Ty::Error]), &scopes, &types);
+        assert_eq!(layout.offsets, vec![0, 4]);
+        assert_eq!(layout.size, 8);
+        let layout = layout_of(&Ty::Tag(vec![Ty::Void, Ty::Error]), &scopes, &types);
+        assert_eq!(layout.size, 4);
+    }
+    #[test]
+    fn it_should_size_narrow_and_wide_scalars() {
+        let scopes = vec![];
+        let types = vec![];
+        let props = vec![Ty::I8, Ty::I16, Ty::F32, Ty::F128, Ty::Utf16];
+        let layout = struct_layout(&props, &scopes, &types);
+        assert_eq!(layout.offsets, vec![0, 2, 4, 16, 32]);
+        assert_eq!(layout.size, 48);
+        assert_eq!(layout.align, 16);
+    }
+    #[test]
+    fn it_should_layout_enums_by_their_backing() {
+        let mut scopes = vec![ScopeTable::new(0, 0)];
+        scopes[0].this_tree.insert("Code".to_string(), 0);
+        let types = vec![TypeTree::EnumInfo(EnumInfo {
+            name: "Code".to_string(),
+            props: vec![],
+            backing: Ty::U16,
+            values: vec![200, 404],
+            curried: Ty::Custom("Code".to_string()),
+            child_scope: 0,
+        })];
+        let code = Ty::Custom("Code".to_string());
+        assert_eq!(layout_of(&code, &scopes, &types), Layout::scalar(2));
+        let layout = struct_layout(&vec![Ty::U8, code, Ty::U8], &scopes, &types);
+        assert_eq!(layout.offsets, vec![0, 2, 4]);
+        assert_eq!(layout.size, 6);
+    }
+}
@@ -37,11 +37,9 @@ impl<'s> TLexer<'s> {
         if self.current.is_none() {
             match self.lexer.next() {
                 Some(val) => {
-                    if val.is_err() {
-                        return None;
-                    }
+                    // what the lexer can't match is kept, so the parser can report where it is
                     self.current = Some(Lexeme {
-                        token: val.unwrap(),
+                        token: val.unwrap_or(Token::Invalid),
                         span: self.lexer.span(),
                         slice: String::from(self.lexer.slice()),
                     });
@@ -51,6 +49,10 @@ impl<'s> TLexer<'s> {
         }
         self.current.clone()
     }
+    // the peeked token waiting to be collected
+    pub fn current(&self) -> Option<&Lexeme> {
+        self.current.as_ref()
+    }
     pub fn has_token_consume(&mut self, token: Token) -> bool {
         match self.peek() {
             Some(lexeme) => {
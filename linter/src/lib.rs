@@ -1,33 +1,84 @@
 use ast::*;
 use codelocation::*;
+use layout::{enum_info, payload_of, prop_of, struct_info, variants_of};
 use lexer::*;
+use ownership::{Borrow, Ownership};
 use perror::LinterError;
 use perror::LinterErrorPoint;
+use perror::ParserError;
+use perror::Severity;
 use scopetable::ScopeTable;
+use std::collections::BTreeMap;
 use token::Token;
 use types::*;
 
 type ResultTreeType = Result<(TypeTreeIndex, Ty), usize>;
 
+// a module after it has been linted, importers only see what is in its pub scope
+#[derive(Debug)]
+pub struct LintedModule {
+    pub scopes: Vec<ScopeTable>,
+    pub types: Vec<TypeTree>,
+    pub top: Vec<TypeTreeIndex>,
+    pub pub_scope: ScopeTable,
+    pub parse_errors: Vec<ParserError>,
+    pub issues: Vec<LinterError>,
+    pub warnings: Vec<LinterError>,
+}
+
+// linted modules by their import path, `a.b` for a/b.ty
+pub type ModuleTable = BTreeMap<String, LintedModule>;
+
 #[derive(Debug)]
 pub struct LintSource<'buf, 'ttb, 'sco> {
-    buffer: &'buf str,
+    source: SourceFile,
     idx: u32,
     curr_scope: u32,
     pub scopes: &'sco mut Vec<ScopeTable>,
     pub ttbls: &'ttb mut Vec<TypeTree>,
     pub issues: Vec<LinterError>,
+    pub warnings: Vec<LinterError>,
+    // the top level items marked pub
+    pub pub_scope: ScopeTable,
+    modules: Option<&'buf ModuleTable>,
+    // the declared return type of each function being linted, innermost last
+    rets: Vec<Ty>,
+    owners: Ownership,
+    // locals that have not been read yet, by their declaration
+    unused: BTreeMap<TypeTreeIndex, Lexeme>,
+    // where each name was declared, by its scope
+    declared: BTreeMap<(u32, String), Lexeme>,
+    // the code the next error type is given, 0 is no error
+    next_code: usize,
+    // optionals known to be defined, by their path like `self.current`, while in the body of
+    // an `if` checking them
+    narrowed: Vec<String>,
+    // what the untyped argument of the anonymous function a `for` runs is given, the element of
+    // the array
+    capture: Option<Ty>,
+    // the `if` about to be checked is written in a block on its own, it does not produce a value
+    statement: bool,
 }
 
 impl<'buf, 'ttb, 'sco> LintSource<'buf, 'ttb, 'sco> {
     pub fn reinit(&mut self, new_buffer: &'buf str) -> () {
-        self.buffer = new_buffer;
+        self.source = SourceFile::new(self.source.id, &self.source.name, new_buffer);
         self.idx = 0;
         self.curr_scope = 0;
         self.scopes.clear();
         self.scopes.push(ScopeTable::new(0, 0));
         self.ttbls.clear();
         self.issues.clear();
+        self.warnings.clear();
+        self.pub_scope = ScopeTable::new(0, 0);
+        self.rets.clear();
+        self.owners.clear();
+        self.unused.clear();
+        self.declared.clear();
+        self.next_code = 1;
+        self.narrowed.clear();
+        self.capture = None;
+        self.statement = false;
     }
     pub fn new(
         buffer: &'buf str,
@@ -36,14 +87,35 @@ impl<'buf, 'ttb, 'sco> LintSource<'buf, 'ttb, 'sco> {
     ) -> Self {
         scopes.push(ScopeTable::new(0, 0));
         LintSource {
-            buffer,
+            source: SourceFile::new(0, "", buffer),
             idx: 0,
             curr_scope: 0,
             scopes,
             ttbls,
             issues: vec![],
+            warnings: vec![],
+            pub_scope: ScopeTable::new(0, 0),
+            modules: None,
+            rets: vec![],
+            owners: Ownership::new(),
+            unused: BTreeMap::new(),
+            declared: BTreeMap::new(),
+            next_code: 1,
+            narrowed: vec![],
+            capture: None,
+            statement: false,
         }
     }
+    // the file diagnostics point into
+    pub fn with_file(mut self, file: &SourceFile) -> Self {
+        self.source = file.clone();
+        self
+    }
+    // the already linted modules that imports are resolved against
+    pub fn with_modules(mut self, modules: &'buf ModuleTable) -> Self {
+        self.modules = Some(modules);
+        self
+    }
 
     pub fn lint_recurse(&mut self, to_cmp: &Expr) -> ResultTreeType {
         match to_cmp {
@@ -78,9 +150,12 @@ impl<'buf, 'ttb, 'sco> LintSource<'buf, 'ttb, 'sco> {
                 Token::Try => self.check_try(un),
                 Token::Ampersand => self.check_borrow_ro(un),
                 Token::Asterisk => self.check_borrow_mut(un),
+                Token::Const | Token::Let => self.check_type_mut(un),
                 Token::Copy => self.check_copy(un),
                 Token::Clone => self.check_clone(un),
-                _ => panic!("type-lang linter issue, unary op not implemented"),
+                _ => {
+                    Err(self.not_supported(&format!("the unary {} operator", un.op.slice), to_cmp))
+                }
             },
             Expr::BinOp(bin) => match bin.op.token {
                 Token::Plus => self.check_plus(&bin),
@@ -92,15 +167,12 @@ impl<'buf, 'ttb, 'sco> LintSource<'buf, 'ttb, 'sco> {
                 Token::Range => self.check_range(&bin),
                 Token::CastAs => self.check_cast(&bin),
                 Token::Gt => self.check_gt(&bin),
-                _ => panic!(
-                    "type-lang linter issue, binary op not implemented {:?}",
-                    bin
-                ),
+                _ => Err(self.not_supported(&format!("the {} operator", bin.op.slice), to_cmp)),
             },
             Expr::Number(num) => match num.val.token {
                 Token::Decimal => self.check_dec(num),
                 Token::Number => self.check_num(num),
-                _ => panic!("type-lang linter issue, number not implemented"),
+                _ => Err(self.not_supported("this kind of number", to_cmp)),
             },
             Expr::TopDecl(top) => self.check_top_decl(&top),
             Expr::Symbol(symbol) => self.check_symbol_ref(&symbol),
@@ -112,7 +184,14 @@ impl<'buf, 'ttb, 'sco> LintSource<'buf, 'ttb, 'sco> {
             Expr::ArrayType(arr) => self.check_array_type(&arr),
             Expr::ArrayAccess(arr) => self.check_array_access(&arr),
             Expr::UndefBubble(u) => self.check_undefined_bubble(&u),
-            _ => panic!("type-lang linter issue, expr not implemented {:?}", to_cmp),
+            Expr::BoolValue(b) => self.check_bool(&b),
+            Expr::TraitDecl(_) => Err(self.not_supported("trait declarations", to_cmp)),
+            Expr::CatchDecl(c) => self.check_catch(&c),
+            Expr::ErrBubble(_) => Err(self.not_supported("bubbling errors with !", to_cmp)),
+            Expr::Destructure(_) => Err(self.not_supported("destructuring", to_cmp)),
+            Expr::FuncType(_) => Err(self.not_supported("function types", to_cmp)),
+            Expr::Never(_) => Err(self.not_supported("never", to_cmp)),
+            _ => Err(self.not_supported("this expression here", to_cmp)),
         }
     }
 
@@ -136,8 +215,34 @@ impl<'buf, 'ttb, 'sco> LintSource<'buf, 'ttb, 'sco> {
                 largs_curried.push(Ty::Unknown);
             });
         }
-        let result = self.lint_recurse(&td.block)?;
         let slice = td.identifier.into_symbol().val.slice;
+        let block = match &td.block {
+            Some(b) => b,
+            None => {
+                let ret = self.lint_recurse(&td.ret_typ)?;
+                let ext = ExternInitialize {
+                    name: slice.clone(),
+                    args_curried: largs_curried,
+                    ret_curried: ret.1,
+                };
+                self.dec_scope_tracker();
+                let curried = ext.ret_curried.clone();
+                let idx = self.push_tt_symbol_idx(tree!(ExternInit, ext), slice);
+                return Ok((idx, curried));
+            }
+        };
+        let ret = self.lint_recurse(&td.ret_typ)?;
+        self.rets.push(ret.1.clone());
+        let result = self.lint_recurse(block);
+        self.rets.pop();
+        let result = result?;
+        // main is always called from the c runtime
+        let export = td
+            .visibility
+            .as_ref()
+            .is_some_and(|v| v.token == Token::Export)
+            || slice == "main";
+        let vis = td.visibility.is_some() || slice == "main";
 
         let init = FunctionInitialize {
             name: slice.clone(),
@@ -145,15 +250,27 @@ impl<'buf, 'ttb, 'sco> LintSource<'buf, 'ttb, 'sco> {
             args_curried: largs_curried,
             block: result.0,
             block_curried: result.1,
+            ret_curried: ret.1,
+            export,
+            vis,
         };
         self.dec_scope_tracker();
-        let curried = init.block_curried.clone();
+        let curried = init.ret_curried.clone();
         let idx = self.push_tt_symbol_idx(tree!(FuncInit, init), slice);
         Ok((idx, curried))
     }
 
     pub fn check_block(&mut self, td: &ast::Block) -> ResultTreeType {
-        let result: Vec<ResultTreeType> = td.exprs.iter().map(|e| self.lint_recurse(&e)).collect();
+        self.inc_scope_tracker();
+        let result: Vec<ResultTreeType> = td
+            .exprs
+            .iter()
+            .map(|e| {
+                self.statement = matches!(e.as_ref(), Expr::If(_));
+                self.lint_recurse(&e)
+            })
+            .collect();
+        self.dec_scope_tracker();
         let mut blk = types::Block {
             exprs: vec![],
             curried: Ty::Unknown,
@@ -183,14 +300,45 @@ impl<'buf, 'ttb, 'sco> LintSource<'buf, 'ttb, 'sco> {
     }
 
     pub fn check_if(&mut self, _if: &If) -> ResultTreeType {
+        let statement = std::mem::take(&mut self.statement);
         let res = self.lint_recurse(&_if.expr)?;
-        let body = self.lint_recurse(&_if.body)?;
-        let if_op = IfOp {
+        if let (false, None, Some(lexeme)) = (statement, &_if.else_body, lexeme_of(&_if.expr)) {
+            return Err(self.set_error(
+                "E0118",
+                "if used as a value without an else".to_string(),
+                "add an else branch, so there is a value when the condition is false".to_string(),
+                lexeme,
+            ));
+        }
+        let before = self.owners.snapshot();
+        // `if (x)` on an optional only runs the body when x is defined, x is its value in there
+        let narrowed = self.narrowed.clone();
+        if let (Some(_), Some(path)) = (res.1.optional(), path_of(&_if.expr)) {
+            self.narrowed.push(path);
+        }
+        let body = self.lint_recurse(&_if.body);
+        // what was given undefined in the body is no longer known to be defined
+        let after = std::mem::replace(&mut self.narrowed, narrowed);
+        self.narrowed.retain(|x| after.contains(x));
+        let body = body?;
+        let after_body = self.owners.snapshot();
+        self.owners.restore(before);
+        let mut if_op = IfOp {
             in_expr: res.0,
             in_curried: res.1,
             body: body.0,
             body_curried: body.1,
+            else_body: None,
+            else_curried: None,
         };
+        if let Some(els) = &_if.else_body {
+            // an `else if` is a statement or a value along with the `if` it follows
+            self.statement = statement && matches!(els.as_ref(), Expr::If(_));
+            let else_body = self.lint_recurse(els)?;
+            if_op.else_body = Some(else_body.0);
+            if_op.else_curried = Some(else_body.1);
+        }
+        self.owners.merge(after_body);
         let cur = if_op.body_curried.clone();
         let idx = self.push_tt_idx(tree!(If, if_op));
         return Ok((idx, cur));
@@ -198,7 +346,25 @@ impl<'buf, 'ttb, 'sco> LintSource<'buf, 'ttb, 'sco> {
 
     pub fn check_for(&mut self, _for: &For) -> ResultTreeType {
         let res = self.lint_recurse(&_for.expr)?;
-        let body = self.lint_recurse(&_for.var_loop)?;
+        self.capture = match strip(&res.1) {
+            Ty::Array(_) | Ty::String => Some(elem_of(&res.1)),
+            _ => None,
+        };
+        let range = matches!(self.ttbls.get(res.0 as usize), Some(TypeTree::Range(_)));
+        let known = !matches!(strip(&res.1), Ty::Unknown | Ty::Any);
+        if let (None, false, true, Some(lexeme)) =
+            (&self.capture, range, known, lexeme_of(&_for.expr))
+        {
+            return Err(self.set_error(
+                "E0205",
+                "for loop needs an array or a range".to_string(),
+                format!("{} cannot be looped over, use a range like 0..x", res.1),
+                lexeme,
+            ));
+        }
+        let body = self.lint_recurse(&_for.var_loop);
+        self.capture = None;
+        let body = body?;
         let for_op = ForOp {
             in_expr: res.0,
             in_curried: res.1,
@@ -216,29 +382,44 @@ impl<'buf, 'ttb, 'sco> LintSource<'buf, 'ttb, 'sco> {
             expr: res.0,
             curried: res.1,
             arms: vec![],
-            curried_arms: Ty::Tag(vec![]),
+            curried_arms: Ty::Void,
         };
+        // arms giving different types make the match a tag of them
+        let mut arms_curried: Vec<Ty> = vec![];
         _match.arms.iter().for_each(|m| {
             let mres = self.lint_recurse(m);
             if let Ok(arm) = mres {
+                let _ = self.check_pattern(&mat.curried, arm.0, m);
                 mat.arms.push(arm.0);
-                mat.curried_arms.into_vec().push(arm.1);
+                if matches!(arm.1, Ty::Void | Ty::Never | Ty::Unknown) {
+                    return;
+                }
+                match arms_curried.iter().position(|x| meet(x, &arm.1).is_some()) {
+                    Some(i) => arms_curried[i] = meet(&arms_curried[i], &arm.1).unwrap(),
+                    None => arms_curried.push(arm.1),
+                }
                 return;
             }
             let idx = self.push_tt_idx(TypeTree::UnknownValue);
             mat.arms.push(idx);
-            mat.curried_arms.into_vec().push(Ty::Unknown);
         });
-        let cur = mat.curried.clone();
+        self.check_coverage(&mat.curried, &mat.arms, _match)?;
+        mat.curried_arms = match arms_curried.len() {
+            0 => Ty::Void,
+            1 => arms_curried.pop().unwrap(),
+            _ => Ty::Tag(arms_curried),
+        };
+        let cur = mat.curried_arms.clone();
         let idx = self.push_tt_idx(tree!(Match, mat));
         return Ok((idx, cur));
     }
 
     pub fn check_declarator(&mut self, decl: &Declarator) -> ResultTreeType {
         let slice = decl.ident.into_symbol().val.slice.clone();
+        let typ = self.lint_recurse(&decl.typ)?;
         let dec = DeclaratorInfo {
             name: slice.clone(),
-            curried: Ty::Undefined,
+            curried: typ.1,
         };
         let curried = dec.curried.clone();
         let idx = self.push_tt_symbol_idx(tree!(DeclaratorInfo, dec), slice);
@@ -247,6 +428,32 @@ impl<'buf, 'ttb, 'sco> LintSource<'buf, 'ttb, 'sco> {
 
     pub fn check_symbol_decl(&mut self, symbol: &Symbol) -> ResultTreeType {
         let slice = symbol.val.slice.clone();
+        // a name can be shadowed in an inner scope, but only declared once in each
+        let tbl = self.scopes.get(self.curr_scope as usize).unwrap();
+        if let Some(x) = tbl.this_tree.get(&slice) {
+            if !matches!(self.ttbls.get(*x as usize), Some(TypeTree::UnknownValue)) {
+                let issue = self.set_error(
+                    "E0102",
+                    format!("{} is already declared in this scope", slice),
+                    format!(
+                        "give it another name, or shadow {} from within a block",
+                        slice
+                    ),
+                    symbol.val.clone(),
+                );
+                let key = (self.curr_scope, slice.clone());
+                if let Some(first) = self.declared.get(&key).cloned() {
+                    self.add_note(issue, "previously declared here".to_string(), &first);
+                }
+                return Err(issue);
+            }
+        }
+        self.declared
+            .insert((self.curr_scope, slice.clone()), symbol.val.clone());
+        // a new binding of the name is not what was checked
+        let inner = format!("{}.", slice);
+        self.narrowed
+            .retain(|x| x != &slice && !x.starts_with(&inner));
         let sym = SymbolInit {
             ident: slice.clone(),
             curried: Ty::Unknown,
@@ -259,13 +466,41 @@ impl<'buf, 'ttb, 'sco> LintSource<'buf, 'ttb, 'sco> {
 
     pub fn check_symbol_ref(&mut self, symbol: &Symbol) -> ResultTreeType {
         let ss = self.scopes.get(self.curr_scope as usize).unwrap();
-        let tt = ss
-            .get_tt_idx_same_up(&symbol.val.slice, self.scopes)
-            .unwrap();
-        let ty = self.ttbls.get(tt as usize).unwrap();
+        let tt = match ss.get_tt_idx_same_up(&symbol.val.slice, self.scopes) {
+            Some(x) => x,
+            None => {
+                return Err(self.set_error(
+                    "E0101",
+                    format!("cannot find {} in this scope", symbol.val.slice),
+                    format!(
+                        "{} is not declared here, or in any scope around it",
+                        symbol.val.slice
+                    ),
+                    symbol.val.clone(),
+                ))
+            }
+        };
+        self.unused.remove(&tt);
+        if let Some(at) = self.owners.moved_at(tt) {
+            let line = self.line_of(at);
+            let name = &symbol.val.slice;
+            return Err(self.set_error(
+                "E0300",
+                format!("use of moved value {}", name),
+                format!(
+                    "{} was moved on line {}, use copy {} or borrow it with & instead",
+                    name, line, name
+                ),
+                symbol.val.clone(),
+            ));
+        }
+        let mut curried = self.ttbls.get(tt as usize).unwrap().get_curried();
+        if self.narrowed.contains(&symbol.val.slice) {
+            curried = narrow(&curried);
+        }
         let sym = SymbolAccess {
             ident: symbol.val.slice.clone(),
-            curried: ty.get_curried(),
+            curried,
         };
         let curried = sym.curried.clone();
         let full = tree!(SymbolAccess, sym);
@@ -280,10 +515,23 @@ impl<'buf, 'ttb, 'sco> LintSource<'buf, 'ttb, 'sco> {
             curried: Ty::Unknown,
         };
         let mut err_unk = false;
+        let mut elem = Ty::Unknown;
         if let Some(args) = &arr.args {
             args.into_iter().for_each(|e| {
                 if let Ok(r) = self.lint_recurse(&e) {
+                    // every element has to agree on one type
+                    match meet(&elem, &r.1) {
+                        Some(x) => elem = x,
+                        None => {
+                            if let Some(lexeme) = lexeme_of(&e) {
+                                self.mismatch(&elem, &r.1, lexeme);
+                            }
+                            err_unk = true;
+                        }
+                    }
+                    let _ = self.check_move(&e, &r.1);
                     array.vals.push(r.0);
+                    array.vals_curried.push(r.1);
                 } else {
                     let idx = self.push_tt_idx(TypeTree::UnknownValue);
                     array.vals.push(idx);
@@ -294,6 +542,8 @@ impl<'buf, 'ttb, 'sco> LintSource<'buf, 'ttb, 'sco> {
         }
         if err_unk {
             array.curried = Ty::Unknown;
+        } else {
+            array.curried = Ty::Array(Box::new(elem));
         }
 
         let curried = array.curried.clone();
@@ -302,12 +552,29 @@ impl<'buf, 'ttb, 'sco> LintSource<'buf, 'ttb, 'sco> {
     }
 
     pub fn check_error_decl(&mut self, err: &ErrorDecl) -> ResultTreeType {
+        self.inc_scope_tracker();
+        let result: Vec<ResultTreeType> =
+            err.variants.iter().map(|e| self.lint_recurse(&e)).collect();
+        self.dec_scope_tracker();
         let slice = err.identifier.into_symbol().val.slice;
-        let err_info = ErrorInfo {
+        // the error is given a code, and each of its variants the codes after it
+        let code = self.next_code;
+        self.next_code += 1 + err.variants.len();
+        let mut err_info = ErrorInfo {
+            name: slice.clone(),
             message: "".to_string(),
-            code: 0,
-            curried: Ty::Error,
+            code,
+            props: vec![],
+            curried: Ty::CustomError(slice.clone()),
         };
+        result.into_iter().for_each(|res| {
+            if let Ok(exp) = res {
+                err_info.props.push(exp.0);
+                return;
+            }
+            let idx = self.push_tt_idx(TypeTree::UnknownValue);
+            err_info.props.push(idx);
+        });
 
         let curried = err_info.curried.clone();
         let full = tree!(ErrorInfo, err_info);
@@ -316,15 +583,16 @@ impl<'buf, 'ttb, 'sco> LintSource<'buf, 'ttb, 'sco> {
     }
 
     pub fn check_value_type(&mut self, _vt: &ValueType) -> ResultTreeType {
-        let curried = match _vt.val.token {
-            Token::U64 => Ty::U64,
-            Token::U32 => Ty::U32,
-            Token::USize => Ty::USize,
-            Token::ISize => Ty::ISize,
-            Token::F64 => Ty::F64,
-            Token::U8 => Ty::U8,
-            Token::Char => Ty::Char,
-            _ => panic!("type lang issue, unmatched value type: {:?}", _vt.val),
+        let curried = match _vt.into_type() {
+            Some(x) => x,
+            None => {
+                return Err(self.set_error(
+                    "E0100",
+                    format!("unsupported type {}", _vt.val.slice),
+                    format!("{} is not yet supported", _vt.val.slice),
+                    _vt.val.clone(),
+                ))
+            }
         };
         let copied = curried.clone();
         let full = tree!(ValueType, copied);
@@ -359,15 +627,23 @@ impl<'buf, 'ttb, 'sco> LintSource<'buf, 'ttb, 'sco> {
             }
         }
         if let Some(left) = &_sig.left_most_type {
-            c_left = match self.lint_recurse(&left) {
-                Err(_) => {
-                    tag.push(Ty::Unknown);
-                    Ty::Unknown
-                }
-                Ok(v) => {
-                    tag.push(v.1.clone());
-                    v.1
+            // the left of `!` names an error type
+            c_left = match left.as_ref() {
+                Expr::Symbol(x) if _sig.err.is_some() => {
+                    let curried = Ty::CustomError(x.val.slice.clone());
+                    tag.push(curried.clone());
+                    curried
                 }
+                _ => match self.lint_recurse(&left) {
+                    Err(_) => {
+                        tag.push(Ty::Unknown);
+                        Ty::Unknown
+                    }
+                    Ok(v) => {
+                        tag.push(v.1.clone());
+                        v.1
+                    }
+                },
             }
         }
         if let Some(_) = &_sig.err {
@@ -396,9 +672,12 @@ impl<'buf, 'ttb, 'sco> LintSource<'buf, 'ttb, 'sco> {
     }
 
     pub fn check_self_value(&mut self) -> ResultTreeType {
-        let self_ref = NoOp {
-            curried: Ty::Unknown,
+        let ss = self.scopes.get(self.curr_scope as usize).unwrap();
+        let curried = match ss.get_tt_idx_same_up("self", self.scopes) {
+            Some(tt) => self.get_curried_here(tt),
+            None => Ty::Unknown,
         };
+        let self_ref = NoOp { curried };
         let curried = self_ref.curried.clone();
         let full = tree!(SelfAccess, self_ref);
         let idx = self.push_tt_idx(full);
@@ -406,9 +685,28 @@ impl<'buf, 'ttb, 'sco> LintSource<'buf, 'ttb, 'sco> {
     }
 
     pub fn check_chars_value(&mut self, chars: &ast::CharsValue) -> ResultTreeType {
+        let slice = &chars.val.slice;
+        let text = match unescape(&slice[1..slice.len() - 1]) {
+            Some(x) => x,
+            None => {
+                return Err(self.set_error(
+                    "E0204",
+                    "invalid escape sequence".to_string(),
+                    "valid escapes are \\n, \\t, \\r, \\0, \\\\, \\', \\\", and \\`".to_string(),
+                    chars.val.clone(),
+                ))
+            }
+        };
+        // a single quoted, single character is a char, everything else is a string
+        if slice.starts_with('\'') && text.chars().count() == 1 {
+            let c = text.chars().next().unwrap();
+            let full = tree!(Char, c);
+            let idx = self.push_tt_idx(full);
+            return Ok((idx, Ty::Char));
+        }
         let mut vals: Vec<TypeTreeIndex> = vec![];
         let mut vals_curried: Vec<Ty> = vec![];
-        chars.val.slice.chars().for_each(|x| {
+        text.chars().for_each(|x| {
             let tree = tree!(Char, x);
             let idx = self.push_tt_idx(tree);
             vals.push(idx);
@@ -427,23 +725,52 @@ impl<'buf, 'ttb, 'sco> LintSource<'buf, 'ttb, 'sco> {
     }
 
     pub fn check_reassignment(&mut self, reas: &ast::Reassignment) -> ResultTreeType {
-        let maybe_access = self.lint_recurse(&reas.left)?;
         let result = self.lint_recurse(&reas.expr)?;
+        self.check_move(&reas.expr, &result.1)?;
+        // a moved binding is usable again once it is given a new value
+        if let (Expr::Symbol(x), Token::As) = (reas.left.as_ref(), reas.op.token) {
+            if let Some(b) = self.binding_of(&x.val.slice) {
+                self.owners.revive(b);
+            }
+        }
+        if result.1.optional().is_some() || strip(&result.1) == &Ty::Undefined {
+            let path = path_of(&reas.left);
+            self.narrowed.retain(|x| Some(x) != path.as_ref());
+        }
+        let maybe_access = self.lint_recurse(&reas.left)?;
+        if reas.op.token != Token::As {
+            self.check_defined(&maybe_access.1, reas.op.clone())?;
+            self.check_compound(&maybe_access.1, reas.op.clone())?;
+        }
+        self.check_into(&maybe_access.1, &result, reas.op.clone())?;
+        self.check_place(maybe_access.0, result.0, reas.op.clone())?;
+        self.check_mutable(maybe_access.0, reas.op.clone())?;
+        // a binding given a new borrow gives back the one it held
+        if let (Expr::Symbol(x), Token::As) = (reas.left.as_ref(), reas.op.token) {
+            if let Some(b) = self.binding_of(&x.val.slice) {
+                self.owners.end_borrow(b);
+                self.hold_borrow(b, &reas.expr, result.0);
+            }
+        }
         let reassignment = types::Reassignment {
             left: maybe_access.0,
             right: result.0,
             curried: maybe_access.1,
         };
-        // todo :: put constness check back
-        // reassignment.left.get_curried().ensure_mut().or_else(|x| {
-        //     Err(self.set_error(
-        //         format!("found {}", x),
-        //         format!("{} is immutable, did you mean to declare with let?", x),
-        //         reas.left.into_symbol().val,
-        //     ))
-        // })?;
         let curried = reassignment.curried.clone();
-        let full = tree!(As, reassignment);
+        let full = match reas.op.token {
+            Token::AddAs => tree!(PlusAs, reassignment),
+            Token::SubAs => tree!(MinusAs, reassignment),
+            Token::MulAs => tree!(MultiplyAs, reassignment),
+            Token::DivAs => tree!(DivideAs, reassignment),
+            Token::ModAs => tree!(ModAs, reassignment),
+            Token::OrAs => tree!(OrAs, reassignment),
+            Token::NotAs => tree!(NotAs, reassignment),
+            Token::XorAs => tree!(XorAs, reassignment),
+            Token::LShiftAs => tree!(LShiftAs, reassignment),
+            Token::RShiftAs => tree!(RShiftAs, reassignment),
+            _ => tree!(As, reassignment),
+        };
         let idx = self.push_tt_idx(full);
         return Ok((idx, curried));
     }
@@ -482,6 +809,7 @@ impl<'buf, 'ttb, 'sco> LintSource<'buf, 'ttb, 'sco> {
             return Ok((idx, curried));
         }
         Err(self.set_error(
+            "E0105",
             "expected at least one declarator".to_string(),
             format!("found empty {{}}, expected declarator"),
             obj.identifier.into_symbol().val,
@@ -490,10 +818,15 @@ impl<'buf, 'ttb, 'sco> LintSource<'buf, 'ttb, 'sco> {
 
     pub fn check_prop_init(&mut self, prop: &PropAssignment) -> ResultTreeType {
         let result = self.lint_recurse(&prop.val)?;
-        let decl = self.lint_recurse(&prop.ident)?;
+        // the property is named by the struct, it is not a symbol in this scope
+        let sym = SymbolInit {
+            ident: prop.ident.into_symbol().val.slice,
+            curried: result.1.clone(),
+        };
+        let decl = self.push_tt_idx(tree!(SymbolInit, sym));
 
         let init = Initialization {
-            left: decl.0,
+            left: decl,
             right: result.0,
             curried: result.1,
         };
@@ -505,11 +838,6 @@ impl<'buf, 'ttb, 'sco> LintSource<'buf, 'ttb, 'sco> {
 
     pub fn check_props_init(&mut self, props: &PropAssignments) -> ResultTreeType {
         let prev = self.lint_recurse(&props.prev)?;
-        let current_scope = self.curr_scope;
-        let slice = props.prev.into_symbol().val.slice;
-        let scope = self.get_tt_by_symbol(&slice);
-        let temp_scope = scope.into_child_scope();
-        self.curr_scope = temp_scope;
 
         if let Some(p) = &props.props {
             let result: Vec<ResultTreeType> =
@@ -521,8 +849,16 @@ impl<'buf, 'ttb, 'sco> LintSource<'buf, 'ttb, 'sco> {
                 vals_curried: vec![],
                 curried: prev_tt.into_symbol_access().curried.clone(),
             };
-            result.into_iter().for_each(|res| {
+            result.into_iter().zip(p.iter()).for_each(|(res, e)| {
                 if let Ok(x) = res {
+                    if let Expr::PropAssignment(prop) = e.as_ref() {
+                        let val = match self.ttbls.get(x.0 as usize) {
+                            Some(TypeTree::PropInit(init)) => init.right,
+                            _ => x.0,
+                        };
+                        self.check_prop_type(&struct_init.curried, prop, &(val, x.1.clone()));
+                        let _ = self.check_move(&prop.val, &x.1);
+                    }
                     struct_init.idents.push(x.0);
                     struct_init.vals_curried.push(x.1);
                 } else {
@@ -531,16 +867,16 @@ impl<'buf, 'ttb, 'sco> LintSource<'buf, 'ttb, 'sco> {
                     struct_init.vals_curried.push(Ty::Unknown);
                 }
             });
-            self.curr_scope = current_scope;
 
             let curried = struct_init.curried.clone();
             let full = tree!(StructInit, struct_init);
 
-            let idx = self.push_tt_symbol_idx(full, slice);
+            let idx = self.push_tt_idx(full);
 
             return Ok((idx, curried));
         }
         Err(self.set_error(
+            "E0105",
             "expected at least one property".to_string(),
             format!("found empty {{}}, expected property"),
             props.prev.into_symbol().val,
@@ -548,29 +884,87 @@ impl<'buf, 'ttb, 'sco> LintSource<'buf, 'ttb, 'sco> {
     }
 
     pub fn check_enum_decl(&mut self, _enum: &EnumDecl) -> ResultTreeType {
+        let slice = _enum.identifier.into_symbol().val.slice;
+        // enums are backed by a u8 unless they say otherwise
+        let backing = match &_enum.enum_type {
+            Some(x) => match self.lint_recurse(x) {
+                Ok((_, ty)) if ty.is_int() => ty,
+                Ok((_, ty)) => {
+                    self.set_error(
+                        "E0108",
+                        format!("{} cannot back an enum", ty),
+                        "enums are backed by an integer type, like u8".to_string(),
+                        lexeme_of(x).unwrap_or(_enum.mutability.clone()),
+                    );
+                    Ty::U8
+                }
+                Err(_) => Ty::U8,
+            },
+            None => Ty::U8,
+        };
         self.inc_scope_tracker();
         let temp = self.curr_scope;
-        let result: Vec<ResultTreeType> = _enum
-            .variants
-            .iter()
-            .map(|e| self.lint_recurse(&e))
-            .collect();
-        self.dec_scope_tracker();
-        let slice = _enum.identifier.into_symbol().val.slice;
         let mut e_info = EnumInfo {
             name: slice.clone(),
             props: vec![],
-            curried: Ty::Enum(Box::new(Ty::U8)),
+            backing: backing.clone(),
+            values: vec![],
+            curried: Ty::Custom(slice.clone()),
             child_scope: temp,
         };
-        result.into_iter().for_each(|res| {
-            if let Ok(exp) = res {
-                e_info.props.push(exp.0);
-                return;
+        let mut seen: Vec<(i128, Lexeme)> = vec![];
+        let mut next: i128 = 0;
+        for variant in _enum.variants.iter() {
+            let (decl, val) = match variant.as_ref() {
+                Expr::PropAssignment(x) => (&x.ident, Some(&x.val)),
+                _ => (variant, None),
+            };
+            let lexeme = decl.into_symbol().val;
+            match self.lint_recurse(decl) {
+                Ok(exp) => e_info.props.push(exp.0),
+                Err(_) => {
+                    let idx = self.push_tt_idx(TypeTree::UnknownValue);
+                    e_info.props.push(idx);
+                }
             }
-            let idx = self.push_tt_idx(TypeTree::UnknownValue);
-            e_info.props.push(idx);
-        });
+            // a variant is one more than the one before it, unless given its own value
+            let value = match val {
+                Some(x) => self
+                    .check_discriminant(x, &backing, &lexeme)
+                    .unwrap_or(next),
+                None => {
+                    let fits = backing
+                        .int_range()
+                        .map_or(true, |(lo, hi)| next >= lo && next <= hi);
+                    if !fits {
+                        self.set_error(
+                            "E0203",
+                            format!("{} is out of range for {}", lexeme.slice, backing),
+                            format!(
+                                "{} would be {}, give it a value that fits",
+                                lexeme.slice, next
+                            ),
+                            lexeme.clone(),
+                        );
+                    }
+                    next
+                }
+            };
+            if let Some((_, prev)) = seen.iter().find(|x| x.0 == value) {
+                let prev = prev.clone();
+                let issue = self.set_error(
+                    "E0107",
+                    format!("{} and {} are both {}", prev.slice, lexeme.slice, value),
+                    "each variant of an enum needs its own value".to_string(),
+                    lexeme.clone(),
+                );
+                self.add_note(issue, format!("{} is {} here", prev.slice, value), &prev);
+            }
+            seen.push((value, lexeme));
+            e_info.values.push(value);
+            next = value + 1;
+        }
+        self.dec_scope_tracker();
 
         let curried = e_info.curried.clone();
         let full = tree!(EnumInfo, e_info);
@@ -578,6 +972,29 @@ impl<'buf, 'ttb, 'sco> LintSource<'buf, 'ttb, 'sco> {
         let idx = self.push_tt_symbol_idx(full, slice);
         return Ok((idx, curried));
     }
+    // the value given to a variant has to be an integer known when compiling
+    // a value with nothing to point at is reported at the variant
+    fn check_discriminant(
+        &mut self,
+        val: &Expr,
+        backing: &Ty,
+        variant: &Lexeme,
+    ) -> Result<i128, usize> {
+        let lexeme = lexeme_of(val).unwrap_or(variant.clone());
+        let idx = self.lint_recurse(val)?.0;
+        match literal_of(idx, self.ttbls) {
+            Some(Literal::Int(x)) => {
+                self.check_literal(idx, backing, lexeme)?;
+                Ok(x)
+            }
+            _ => Err(self.set_error(
+                "E0109",
+                "enum values must be integer literals".to_string(),
+                "give the variant a value like 1 or -1".to_string(),
+                lexeme,
+            )),
+        }
+    }
 
     pub fn check_tag_decl(&mut self, tag: &TagDecl) -> ResultTreeType {
         self.inc_scope_tracker();
@@ -596,8 +1013,13 @@ impl<'buf, 'ttb, 'sco> LintSource<'buf, 'ttb, 'sco> {
         };
         result.into_iter().for_each(|res| {
             if let Ok(exp) = res {
+                // a variant without a payload is only its discriminant
+                let payload = match self.ttbls.get(exp.0 as usize) {
+                    Some(TypeTree::DeclaratorInfo(_)) => exp.1,
+                    _ => Ty::Void,
+                };
                 tag_info.props.push(exp.0);
-                tag_info.types.push(exp.1);
+                tag_info.types.push(payload);
                 return;
             }
             let idx = self.push_tt_idx(TypeTree::UnknownValue);
@@ -614,9 +1036,14 @@ impl<'buf, 'ttb, 'sco> LintSource<'buf, 'ttb, 'sco> {
     pub fn check_anon_func(&mut self, anon: &AnonFuncDecl) -> ResultTreeType {
         let mut largs = vec![];
         let mut largs_curried = vec![];
+        // only the first argument is given what a `for` captures
+        let mut capture = self.capture.take();
+        self.inc_scope_tracker();
         if let Some(args) = anon.args.as_ref() {
             args.iter().for_each(|x| {
+                self.capture = capture.take();
                 let res = self.lint_recurse(x);
+                self.capture = None;
                 if let Ok(a) = res {
                     largs.push(a.0);
                     largs_curried.push(a.1);
@@ -627,7 +1054,15 @@ impl<'buf, 'ttb, 'sco> LintSource<'buf, 'ttb, 'sco> {
                 largs_curried.push(Ty::Unknown);
             });
         }
-        let result = self.lint_recurse(&anon.block)?;
+        let ret = self.lint_recurse(&anon.ret_typ);
+        let result = ret.and_then(|ret| {
+            self.rets.push(ret.1.clone());
+            let result = self.lint_recurse(&anon.block);
+            self.rets.pop();
+            Ok((ret, result?))
+        });
+        self.dec_scope_tracker();
+        let (ret, result) = result?;
         let slice = format!(":anon_{}", self.idx);
         self.idx += 1;
 
@@ -637,80 +1072,286 @@ impl<'buf, 'ttb, 'sco> LintSource<'buf, 'ttb, 'sco> {
             args_curried: largs_curried,
             block: result.0,
             block_curried: result.1,
+            ret_curried: ret.1,
+            export: false,
+            vis: false,
         };
-        let curried = init.block_curried.clone();
-        let full = tree!(FuncInit, init);
+        let curried = init.ret_curried.clone();
+        let full = tree!(AnonFuncInit, init);
 
         let idx = self.push_tt_symbol_idx(full, slice);
         return Ok((idx, curried));
     }
 
+    // `import "a.b.symbol"` brings in a pub symbol from the module at a/b.ty
     pub fn check_import(&mut self, import: &Import) -> ResultTreeType {
-        let result = self.lint_recurse(&import.expr)?;
-        let decl = self.lint_recurse(&import.identifier)?;
-        let slice = &import.identifier.into_symbol().val.slice;
-        let mut init = Initialization {
-            left: decl.0,
-            right: result.0,
-            curried: result.1,
+        let slice = import.identifier.into_symbol().val.slice;
+        let path = import.expr.into_chars_value().val;
+        let trimmed = &path.slice[1..path.slice.len() - 1];
+        let (module_name, symbol) = match trimmed.rsplit_once('.') {
+            Some(x) => x,
+            None => {
+                return Err(self.set_error(
+                    "E0400",
+                    "expected an import path".to_string(),
+                    "imports are written as \"file.symbol\"".to_string(),
+                    path.clone(),
+                ))
+            }
+        };
+        // linting a file on its own, imports can't be resolved
+        if self.modules.is_none() {
+            let init = ImportInitialize {
+                name: slice.clone(),
+                symbol: mangle(module_name, symbol),
+                args_curried: None,
+                curried: Ty::Unknown,
+                export: false,
+                writable: false,
+            };
+            let idx = self.push_tt_symbol_idx(tree!(ImportInit, init), slice);
+            return Ok((idx, Ty::Unknown));
+        }
+        let module = match self.modules.and_then(|m| m.get(module_name)) {
+            Some(x) => x,
+            None => {
+                return Err(self.set_error(
+                    "E0401",
+                    format!("unknown module {}", module_name),
+                    format!("is there a {}.ty file?", module_name.replace('.', "/")),
+                    path.clone(),
+                ))
+            }
+        };
+        let found = module
+            .pub_scope
+            .this_tree
+            .get(symbol)
+            .and_then(|x| module.types.get(*x as usize));
+        let tree = match found {
+            Some(x) => x,
+            None => {
+                return Err(self.set_error(
+                    "E0402",
+                    format!("{} is not a pub item of {}", symbol, module_name),
+                    format!("mark {} as pub in {}", symbol, module_name),
+                    path.clone(),
+                ))
+            }
+        };
+        let init = match tree {
+            TypeTree::FuncInit(x) => ImportInitialize {
+                name: slice.clone(),
+                symbol: match x.export {
+                    true => x.name.clone(),
+                    false => mangle(module_name, &x.name),
+                },
+                args_curried: Some(
+                    x.args_curried
+                        .iter()
+                        .map(|x| self.qualify(x, module_name, module))
+                        .collect(),
+                ),
+                curried: self.qualify(&x.ret_curried, module_name, module),
+                export: x.export,
+                writable: false,
+            },
+            TypeTree::TopConstInit(_) | TypeTree::TopMutInit(_) => ImportInitialize {
+                name: slice.clone(),
+                symbol: mangle(module_name, symbol),
+                args_curried: None,
+                curried: self.qualify(&tree.get_curried(), module_name, module),
+                export: false,
+                writable: matches!(tree, TypeTree::TopMutInit(_)),
+            },
+            // structs have no symbol, the layout is copied in under the struct's qualified name,
+            // and the name it is imported as refers to it
+            TypeTree::StructInfo(x) => {
+                let curried = self.qualify(&x.curried, module_name, module);
+                let idx = match &curried {
+                    Ty::Custom(x) => *self.scopes[0].this_tree.get(x).unwrap(),
+                    _ => unreachable!(),
+                };
+                self.scopes[0].this_tree.insert(slice, idx);
+                self.owners.declare(idx, self.curr_scope);
+                return Ok((idx, curried));
+            }
+            _ => {
+                return Err(self.set_error(
+                    "E0100",
+                    format!("importing {} is not yet supported", tree.whatami()),
+                    "only functions, data, and structs can be imported".to_string(),
+                    path.clone(),
+                ))
+            }
         };
         let curried = init.curried.clone();
-        if import.mutability.token == Token::Const {
-            init.curried = Ty::Const(Box::new(init.curried));
-            let full = tree!(ConstInit, init);
-            let idx = self.push_tt_symbol_idx(full, slice.to_string());
-            return Ok((idx, Ty::Const(Box::new(curried))));
-        }
-        init.curried = Ty::Mut(Box::new(init.curried));
-        let full = tree!(MutInit, init);
-        let idx = self.push_tt_symbol_idx(full, slice.to_string());
-        return Ok((idx, Ty::Const(Box::new(curried))));
+        let idx = self.push_tt_symbol_idx(tree!(ImportInit, init), slice);
+        Ok((idx, curried))
+    }
+
+    // a type from another module, with its structs named by the module they are declared in,
+    // so they never mix with structs of the same name here
+    fn qualify(&mut self, ty: &Ty, module_name: &str, module: &LintedModule) -> Ty {
+        let mut each = |x: &Vec<Ty>| -> Vec<Ty> {
+            x.iter()
+                .map(|x| self.qualify(x, module_name, module))
+                .collect()
+        };
+        match ty {
+            Ty::Const(x) => Ty::Const(Box::new(self.qualify(x, module_name, module))),
+            Ty::Mut(x) => Ty::Mut(Box::new(self.qualify(x, module_name, module))),
+            Ty::MutBorrow(x) => Ty::MutBorrow(Box::new(self.qualify(x, module_name, module))),
+            Ty::ReadBorrow(x) => Ty::ReadBorrow(Box::new(self.qualify(x, module_name, module))),
+            Ty::Array(x) => Ty::Array(Box::new(self.qualify(x, module_name, module))),
+            Ty::Frame(x) => Ty::Frame(each(x)),
+            Ty::Struct(x) => Ty::Struct(each(x)),
+            Ty::Tag(x) => Ty::Tag(each(x)),
+            Ty::Function(x, y) => {
+                let args = each(x);
+                Ty::Function(args, Box::new(self.qualify(y, module_name, module)))
+            }
+            Ty::Custom(x) => match struct_info(x, &module.scopes, &module.types) {
+                Some(info) => self.import_struct(info, module_name, module),
+                None => ty.clone(),
+            },
+            x => x.clone(),
+        }
+    }
+
+    // the struct is copied in once, structs it holds are brought in along with it
+    fn import_struct(&mut self, info: &StructInfo, module_name: &str, module: &LintedModule) -> Ty {
+        let name = match &info.curried {
+            // already imported into that module, it keeps the name of where it is declared
+            Ty::Custom(x) if x.contains('.') => x.clone(),
+            Ty::Custom(x) => mangle(module_name, x),
+            x => return x.clone(),
+        };
+        let curried = Ty::Custom(name.clone());
+        if self.scopes[0].this_tree.contains_key(&name) {
+            return curried;
+        }
+        // known by its name before its properties are, a struct may hold a borrow of itself
+        let copied = StructInfo {
+            props: info.props.clone(),
+            types: vec![],
+            curried: curried.clone(),
+            child_scope: 0,
+        };
+        let idx = self.push_tt_idx(tree!(StructInfo, copied));
+        self.scopes[0].this_tree.insert(name, idx);
+        let types = info
+            .types
+            .iter()
+            .map(|x| self.qualify(x, module_name, module))
+            .collect();
+        if let Some(TypeTree::StructInfo(x)) = self.ttbls.get_mut(idx as usize) {
+            x.types = types;
+        }
+        curried
+    }
+
+    // the value a binding is declared with. a value with an error is unknown, the binding is still
+    // declared with it so uses of the binding aren't reported as well
+    fn check_binding_value(
+        &mut self,
+        ident: &Expr,
+        typ: &Option<Box<Expr>>,
+        expr: &Expr,
+    ) -> ((TypeTreeIndex, Ty), Ty, Option<usize>) {
+        let checked = self.lint_recurse(expr).and_then(|x| {
+            self.check_move(expr, &x.1)?;
+            Ok(x)
+        });
+        let (result, failed) = match checked {
+            Ok(x) => (x, None),
+            Err(x) => (
+                (self.push_tt_idx(TypeTree::UnknownValue), Ty::Unknown),
+                Some(x),
+            ),
+        };
+        match self.check_declared(ident, typ, &result) {
+            Ok(curried) => (result, curried, failed),
+            Err(x) => (result, Ty::Unknown, failed.or(Some(x))),
+        }
     }
 
     pub fn check_inner_decl(&mut self, inner: &InnerDecl) -> ResultTreeType {
-        let result = self.lint_recurse(&inner.expr)?;
+        let (result, curried, failed) =
+            self.check_binding_value(&inner.identifier, &inner.typ, &inner.expr);
         let decl = self.lint_recurse(&inner.identifier)?;
         let slice = inner.identifier.into_symbol().val.slice;
 
         let mut init = Initialization {
             left: decl.0,
             right: result.0,
-            curried: result.1,
+            curried,
         };
         let curried = init.curried.clone();
-        if inner.mutability.token == Token::Const {
-            init.curried = Ty::Const(Box::new(init.curried));
-            let full = tree!(ConstInit, init);
-            let idx = self.push_tt_symbol_idx(full, slice.to_string());
-            return Ok((idx, Ty::Const(Box::new(curried))));
-        }
-        init.curried = Ty::Mut(Box::new(init.curried));
-        let full = tree!(MutInit, init);
+        let full = match inner.mutability.token {
+            Token::Const => {
+                init.curried = Ty::Const(Box::new(init.curried));
+                tree!(ConstInit, init)
+            }
+            _ => {
+                init.curried = Ty::Mut(Box::new(init.curried));
+                tree!(MutInit, init)
+            }
+        };
         let idx = self.push_tt_symbol_idx(full, slice.to_string());
+        self.hold_borrow(idx, &inner.expr, result.0);
+        self.unused.insert(idx, inner.identifier.into_symbol().val);
+        if let Some(x) = failed {
+            return Err(x);
+        }
         return Ok((idx, Ty::Const(Box::new(curried))));
     }
 
     pub fn check_top_decl(&mut self, td: &TopDecl) -> ResultTreeType {
-        let result = self.lint_recurse(&td.expr)?;
+        if let Some(vis) = td.visibility.as_ref().filter(|v| v.token == Token::Extern) {
+            return Err(self.set_error(
+                "E0403",
+                "extern on data".to_string(),
+                "only functions can be extern, did you mean to use pub?".to_string(),
+                vis.clone(),
+            ));
+        }
+        let (result, curried, mut failed) =
+            self.check_binding_value(&td.identifier, &td.typ, &td.expr);
+        if let (None, Some(why)) = (failed, unfolded(result.0, true, self.scopes, self.ttbls)) {
+            let lexeme = lexeme_of(&td.expr).unwrap_or(td.identifier.into_symbol().val);
+            failed = Some(self.set_error(
+                "E0117",
+                "global values must be known at compile time".to_string(),
+                why,
+                lexeme,
+            ));
+        }
         let decl = self.lint_recurse(&td.identifier)?;
         let slice = td.identifier.into_symbol().val.slice;
 
         let mut init = TopInitialization {
             left: decl.0,
             right: result.0,
-            curried: result.1,
+            curried,
             vis: td.visibility.is_some(),
         };
         let curried = init.curried.clone();
-        if td.mutability.token == Token::Const {
-            init.curried = Ty::Const(Box::new(init.curried));
-            let full = tree!(TopConstInit, init);
-            let idx = self.push_tt_symbol_idx(full, slice.to_string());
-            return Ok((idx, Ty::Const(Box::new(curried))));
-        }
-        init.curried = Ty::Mut(Box::new(init.curried));
-        let full = tree!(TopMutInit, init);
+        let full = match td.mutability.token {
+            Token::Const => {
+                init.curried = Ty::Const(Box::new(init.curried));
+                tree!(TopConstInit, init)
+            }
+            _ => {
+                init.curried = Ty::Mut(Box::new(init.curried));
+                tree!(TopMutInit, init)
+            }
+        };
         let idx = self.push_tt_symbol_idx(full, slice.to_string());
+        self.hold_borrow(idx, &td.expr, result.0);
+        if let Some(x) = failed {
+            return Err(x);
+        }
         return Ok((idx, Ty::Const(Box::new(curried))));
     }
 
@@ -722,13 +1363,14 @@ impl<'buf, 'ttb, 'sco> LintSource<'buf, 'ttb, 'sco> {
         };
         let tt = self.ttbls.get(unop.val as usize).unwrap();
         match tt {
-            TypeTree::F64(_) => unop.curried = Ty::F64,
-            TypeTree::U64(_) => unop.curried = Ty::I64,
+            TypeTree::F64(_) => unop.curried = Ty::FloatLiteral,
+            TypeTree::U64(_) => unop.curried = Ty::IntLiteral,
             TypeTree::U32(_) => unop.curried = Ty::I32,
             TypeTree::I64(_) => unop.curried = Ty::I64,
             TypeTree::I32(_) => unop.curried = Ty::I32,
             TypeTree::Negate(_) => {
                 return Err(self.set_error(
+                    "E0202",
                     "invalid negation".to_string(),
                     "double negation superfluous. decrement must be done with (val - 1)"
                         .to_string(),
@@ -737,6 +1379,7 @@ impl<'buf, 'ttb, 'sco> LintSource<'buf, 'ttb, 'sco> {
             }
             _ => {
                 return Err(self.set_error(
+                    "E0202",
                     "invalid negation".to_string(),
                     format!("found type {}, expected negatable value", tt.whatami()),
                     un.op.clone(),
@@ -762,11 +1405,46 @@ impl<'buf, 'ttb, 'sco> LintSource<'buf, 'ttb, 'sco> {
         return Ok((idx, curried));
     }
 
+    // const or let on a type, like the elements of [const char]
+    pub fn check_type_mut(&mut self, un: &UnOp) -> ResultTreeType {
+        let result = self.lint_recurse(&un.val)?;
+        let inner = Box::new(strip_binding(&result.1).clone());
+        match un.op.token {
+            Token::Let => Ok((result.0, Ty::Mut(inner))),
+            _ => Ok((result.0, Ty::Const(inner))),
+        }
+    }
+
+    // `x?` gives what x holds when it is defined, undefined is returned from the function
     pub fn check_undefined_bubble(&mut self, un: &UndefBubble) -> ResultTreeType {
         let result = self.lint_recurse(&un.prev)?;
+        let lexeme = lexeme_of(&un.prev).unwrap_or(un.span.clone());
+        let some = match result.1.optional() {
+            Some(x) => x,
+            None => {
+                return Err(self.set_error(
+                    "E0115",
+                    format!("{} is never undefined", strip(&result.1)),
+                    "only an optional, like ?T, can be given on with ?".to_string(),
+                    lexeme,
+                ))
+            }
+        };
+        match self.rets.first() {
+            Some(x) if x.optional().is_some() => (),
+            x => {
+                let found = x.map_or("nothing".to_string(), |x| strip(x).to_string());
+                return Err(self.set_error(
+                    "E0116",
+                    "? outside of a function returning an optional".to_string(),
+                    format!("undefined is returned, but the function returns {}", found),
+                    lexeme,
+                ));
+            }
+        }
         let unop = UnaryOp {
             val: result.0,
-            curried: result.1,
+            curried: some,
         };
         let curried = unop.curried.clone();
         let full = tree!(BubbleUndef, unop);
@@ -787,13 +1465,36 @@ impl<'buf, 'ttb, 'sco> LintSource<'buf, 'ttb, 'sco> {
     }
 
     pub fn check_ret_op(&mut self, ret: &RetOp) -> ResultTreeType {
-        let result = self.lint_recurse(&ret.expr)?;
+        let expr = match &ret.expr {
+            Some(x) => x,
+            None => {
+                if ret.span.token != Token::Break {
+                    self.check_ret_type(None, ret)?;
+                }
+                let noop = NoOp { curried: Ty::Void };
+                let full = match ret.span.token {
+                    Token::Break => tree!(BreakVoid, noop),
+                    _ => tree!(ReturnVoid, noop),
+                };
+                let idx = self.push_tt_idx(full);
+                return Ok((idx, Ty::Void));
+            }
+        };
+        let result = self.lint_recurse(expr)?;
+        if ret.span.token != Token::Break {
+            self.check_ret_type(Some(&result), ret)?;
+            self.check_escape(result.0, 0, ret.span.clone())?;
+        }
+        self.check_move(expr, &result.1)?;
         let unop = UnaryOp {
             val: result.0,
             curried: result.1,
         };
         let curried = unop.curried.clone();
-        let full = tree!(Return, unop);
+        let full = match ret.span.token {
+            Token::Break => tree!(Break, unop),
+            _ => tree!(Return, unop),
+        };
         let idx = self.push_tt_idx(full);
         return Ok((idx, curried));
     }
@@ -801,11 +1502,11 @@ impl<'buf, 'ttb, 'sco> LintSource<'buf, 'ttb, 'sco> {
     pub fn check_array_access(&mut self, arr: &ast::ArrayAccess) -> ResultTreeType {
         let prev = self.lint_recurse(&arr.prev)?;
         let inner = self.lint_recurse(&arr.inner)?;
-        let curried = prev.1.clone();
+        let curried = elem_of(&prev.1);
         let arrtype = types::ArrayAccess {
             prev: prev.0,
             inner: inner.0,
-            curried: prev.1,
+            curried: curried.clone(),
         };
         let full = tree!(ArrayAccess, arrtype);
         let idx = self.push_tt_idx(full);
@@ -814,10 +1515,10 @@ impl<'buf, 'ttb, 'sco> LintSource<'buf, 'ttb, 'sco> {
 
     pub fn check_array_type(&mut self, arr: &ArrayType) -> ResultTreeType {
         let result = self.lint_recurse(&arr.arr_of)?;
-        let curried = result.1.clone();
+        let curried = Ty::Array(Box::new(result.1));
         let arrtype = ArrType {
             arr_of: result.0,
-            curried: result.1,
+            curried: curried.clone(),
         };
         let full = tree!(ArrayType, arrtype);
         let idx = self.push_tt_idx(full);
@@ -828,7 +1529,7 @@ impl<'buf, 'ttb, 'sco> LintSource<'buf, 'ttb, 'sco> {
         match arg.ident.as_ref() {
             Expr::SymbolDecl(x) => {
                 let slice = x.val.slice.clone();
-                let typ = self.lint_recurse(&arg.typ)?;
+                let typ = self.check_arg_type(&arg.typ)?;
                 let a = SymbolInit {
                     ident: slice.clone(),
                     curried: typ.1,
@@ -839,7 +1540,7 @@ impl<'buf, 'ttb, 'sco> LintSource<'buf, 'ttb, 'sco> {
                 return Ok((idx, curried));
             }
             Expr::SelfDecl(_) => {
-                let typ = self.lint_recurse(&arg.typ)?;
+                let typ = self.check_arg_type(&arg.typ)?;
                 let a = NoOp { curried: typ.1 };
 
                 let curried = a.curried.clone();
@@ -847,13 +1548,33 @@ impl<'buf, 'ttb, 'sco> LintSource<'buf, 'ttb, 'sco> {
                 let idx = self.push_tt_symbol_idx(full, "self".to_string());
                 return Ok((idx, curried));
             }
-            _ => panic!("unexpected expression in arg_def"),
+            _ => Err(self.not_supported("this argument", &arg.ident)),
+        }
+    }
+
+    // anonymous function arguments without a type are inferred by their usage, or are what a
+    // `for` captures
+    fn check_arg_type(&mut self, typ: &Option<Box<Expr>>) -> ResultTreeType {
+        match typ {
+            Some(t) => self.lint_recurse(t),
+            None => {
+                let idx = self.push_tt_idx(TypeTree::UnknownValue);
+                Ok((idx, self.capture.take().unwrap_or(Ty::Unknown)))
+            }
         }
     }
 
+    pub fn check_bool(&mut self, b: &BoolValue) -> ResultTreeType {
+        let val = b.val.token == Token::True;
+        let full = tree!(BoolValue, val);
+        let idx = self.push_tt_idx(full);
+        return Ok((idx, Ty::Bool));
+    }
+
     pub fn check_gt(&mut self, bin: &BinOp) -> ResultTreeType {
         let left = self.lint_recurse(&bin.left)?;
         let right = self.lint_recurse(&bin.right)?;
+        self.check_operands(bin, &left, &right)?;
         let binop = BinaryOp {
             left: left.0,
             right: right.0,
@@ -868,10 +1589,21 @@ impl<'buf, 'ttb, 'sco> LintSource<'buf, 'ttb, 'sco> {
     pub fn check_cast(&mut self, bin: &BinOp) -> ResultTreeType {
         let left = self.lint_recurse(&bin.left)?;
         let right = self.lint_recurse(&bin.right)?;
+        // enums can be turned into their values, but not back
+        let from_enum =
+            enum_info(&left.1, self.scopes, self.ttbls).is_some() && strip(&right.1).is_int();
+        if !from_enum && (!castable(&left.1) || !castable(&right.1)) {
+            return Err(self.set_error(
+                "E0201",
+                "invalid cast".to_string(),
+                format!("cannot cast {} to {}", strip(&left.1), strip(&right.1)),
+                bin.op.clone(),
+            ));
+        }
         let binop = BinaryOp {
             left: left.0,
             right: right.0,
-            curried: Ty::Bool,
+            curried: right.1,
         };
         let curried = binop.curried.clone();
         let full = tree!(CastAs, binop);
@@ -910,6 +1642,7 @@ impl<'buf, 'ttb, 'sco> LintSource<'buf, 'ttb, 'sco> {
     pub fn check_not_eq(&mut self, bin: &BinOp) -> ResultTreeType {
         let left = self.lint_recurse(&bin.left)?;
         let right = self.lint_recurse(&bin.right)?;
+        self.check_operands(bin, &left, &right)?;
         let binop = BinaryOp {
             left: left.0,
             right: right.0,
@@ -922,10 +1655,34 @@ impl<'buf, 'ttb, 'sco> LintSource<'buf, 'ttb, 'sco> {
     }
 
     pub fn check_try(&mut self, un: &UnOp) -> ResultTreeType {
-        let result = self.lint_recurse(&un.val)?;
+        self.check_bubble_error(&un.val, un.op.clone())
+    }
+
+    // `try x` gives what x holds when it is not an error, an error is returned from the function
+    pub fn check_bubble_error(&mut self, val: &Expr, at: Lexeme) -> ResultTreeType {
+        let result = self.lint_recurse(val)?;
+        let lexeme = lexeme_of(val).unwrap_or(at);
+        let ok = self.check_error_union(&result.1, lexeme.clone())?;
+        let ret = match self.rets.first() {
+            Some(x) if x.error_union().is_some() => x.clone(),
+            x => {
+                let found = x.map_or("nothing".to_string(), |x| strip(x).to_string());
+                return Err(self.set_error(
+                    "E0112",
+                    "try outside of a function returning an error".to_string(),
+                    format!("the error is returned, but the function returns {}", found),
+                    lexeme,
+                ));
+            }
+        };
+        for err in errors_of(&result.1) {
+            if !returns_error(&ret, &err) {
+                return Err(self.mismatch(&ret, &err, lexeme));
+            }
+        }
         let unop = UnaryOp {
             val: result.0,
-            curried: result.1,
+            curried: ok,
         };
         let curried = unop.curried.clone();
         let full = tree!(BubbleError, unop);
@@ -933,6 +1690,82 @@ impl<'buf, 'ttb, 'sco> LintSource<'buf, 'ttb, 'sco> {
         return Ok((idx, curried));
     }
 
+    // `x catch (e) { ... }` is the value of x, or what the block returns when x is an error
+    pub fn check_catch(&mut self, c: &CatchDecl) -> ResultTreeType {
+        let result = self.lint_recurse(&c.prev)?;
+        let lexeme = lexeme_of(&c.prev).unwrap_or(c.span.clone());
+        let ok = self.check_error_union(&result.1, lexeme)?;
+        // the error the handler is given, one of several errors is given as any error
+        let errs = errors_of(&result.1);
+        let err = match errs.len() {
+            1 => errs[0].clone(),
+            _ => Ty::Error,
+        };
+        self.inc_scope_tracker();
+        let handler = self.check_catch_handler(c, err, ok);
+        self.dec_scope_tracker();
+        let (handler, curried) = handler?;
+        let catch = CatchOp {
+            expr: result.0,
+            handler,
+            curried,
+        };
+        let curried = catch.curried.clone();
+        let idx = self.push_tt_idx(tree!(Catch, catch));
+        return Ok((idx, curried));
+    }
+
+    // the handler is inlined like an anonymous function, it's given the error and returns what
+    // is used in place of the value
+    fn check_catch_handler(&mut self, c: &CatchDecl, err: Ty, ok: Ty) -> ResultTreeType {
+        let mut args = vec![];
+        let mut args_curried = vec![];
+        if let Some(Expr::ArgDef(arg)) = c.args.as_ref().and_then(|x| x.first()).map(|x| x.as_ref())
+        {
+            let lexeme = lexeme_of(&arg.ident).unwrap_or(c.span.clone());
+            let found = self.check_arg_type(&arg.typ)?;
+            if unify(&found.1, &err).is_none() {
+                return Err(self.mismatch(&found.1, &err, lexeme));
+            }
+            let a = SymbolInit {
+                ident: lexeme.slice.clone(),
+                curried: err.clone(),
+            };
+            args.push(self.push_tt_symbol_idx(tree!(ArgInit, a), lexeme.slice));
+            args_curried.push(err);
+        }
+        let ret = match &c.ret_typ {
+            Some(x) => {
+                let found = self.lint_recurse(x)?;
+                if unify(&ok, &found.1).is_none() {
+                    let lexeme = lexeme_of(x).unwrap_or(c.span.clone());
+                    return Err(self.mismatch(&ok, &found.1, lexeme));
+                }
+                found.1
+            }
+            None => ok,
+        };
+        self.rets.push(ret.clone());
+        let block = self.lint_recurse(&c.block);
+        self.rets.pop();
+        let block = block?;
+        let slice = format!(":anon_{}", self.idx);
+        self.idx += 1;
+        let init = FunctionInitialize {
+            name: slice.clone(),
+            args,
+            args_curried,
+            block: block.0,
+            block_curried: block.1,
+            ret_curried: ret,
+            export: false,
+            vis: false,
+        };
+        let curried = init.ret_curried.clone();
+        let idx = self.push_tt_symbol_idx(tree!(AnonFuncInit, init), slice);
+        return Ok((idx, curried));
+    }
+
     pub fn check_not(&mut self, un: &UnOp) -> ResultTreeType {
         let result = self.lint_recurse(&un.val)?;
         let unop = UnaryOp {
@@ -946,11 +1779,38 @@ impl<'buf, 'ttb, 'sco> LintSource<'buf, 'ttb, 'sco> {
     }
 
     pub fn check_prop_access(&mut self, prop: &ast::PropAccess) -> ResultTreeType {
+        if let Some(owner) = self.variant_owner(&prop.prev) {
+            let variant = self.check_variant(owner.clone(), prop)?;
+            self.check_payload_count(&owner, prop, 0)?;
+            return Ok(variant);
+        }
         let prev = self.lint_recurse(&prop.prev)?;
+        let ident = prop.identifier.into_symbol().val;
+        self.check_defined(&prev.1, ident.clone())?;
+        let path = path_of(&prop.prev).map(|x| format!("{}.{}", x, ident.slice));
+        let curried = match prop_of(&prev.1, &ident.slice, self.scopes, self.ttbls) {
+            Some((_, ty)) if path.is_some_and(|x| self.narrowed.contains(&x)) => narrow(&ty),
+            Some((_, ty)) => ty,
+            // arrays and strings know how many elements they hold
+            None if ident.slice == "len" && matches!(strip(&prev.1), Ty::Array(_) | Ty::String) => {
+                Ty::USize
+            }
+            None => match prev.1 {
+                Ty::Custom(x) => {
+                    return Err(self.set_error(
+                        "E0103",
+                        format!("unknown property {}", ident.slice),
+                        format!("{} has no property {}", x, ident.slice),
+                        ident,
+                    ))
+                }
+                _ => Ty::Unknown,
+            },
+        };
         let access = types::PropAccess {
             prev: prev.0,
-            ident: prop.identifier.into_symbol().val.slice,
-            curried: prev.1,
+            ident: ident.slice,
+            curried,
         };
         let curried = access.curried.clone();
         let full = tree!(PropAccess, access);
@@ -958,16 +1818,124 @@ impl<'buf, 'ttb, 'sco> LintSource<'buf, 'ttb, 'sco> {
         return Ok((idx, curried));
     }
 
-    pub fn check_invoke(&mut self, inv: &ast::Invoke) -> ResultTreeType {
-        let prev = self.lint_recurse(&inv.prev)?;
-        let mut invoke = types::Invoke {
-            args: vec![],
-            args_curried: vec![],
-            ident: prev.0,
-            curried: prev.1,
+    pub fn check_variant(&mut self, owner: (String, Ty), prop: &ast::PropAccess) -> ResultTreeType {
+        let ident = prop.identifier.into_symbol().val;
+        let known = variants_of(&owner.1, self.scopes, self.ttbls).unwrap_or_default();
+        if !known.contains(&ident.slice) {
+            return Err(self.set_error(
+                "E0103",
+                format!("unknown variant {}", ident.slice),
+                format!("{} has no variant {}", owner.0, ident.slice),
+                ident,
+            ));
+        }
+        let access = VariantAccess {
+            owner: owner.0,
+            ident: ident.slice,
+            curried: owner.1,
         };
-        if let Some(args) = &inv.args {
-            args.iter().for_each(|a| {
+        let curried = access.curried.clone();
+        let idx = self.push_tt_idx(tree!(VariantAccess, access));
+        return Ok((idx, curried));
+    }
+
+    // `Shape.circle(2.0)` gives a tag variant its payload
+    pub fn check_variant_init(
+        &mut self,
+        owner: (String, Ty),
+        prop: &ast::PropAccess,
+        inv: &ast::Invoke,
+    ) -> ResultTreeType {
+        self.check_variant(owner.clone(), prop)?;
+        let ident = prop.identifier.into_symbol().val.slice;
+        let args = inv.args.clone().unwrap_or_default();
+        self.check_payload_count(&owner, prop, args.len())?;
+        let payload = match args.first() {
+            Some(x) => {
+                let expected = payload_of(&owner.1, &ident, self.scopes, self.ttbls)
+                    .unwrap()
+                    .1;
+                let val = self.lint_recurse(x)?;
+                let lexeme = lexeme_of(x).unwrap_or(prop.identifier.into_symbol().val);
+                self.check_into(&expected, &val, lexeme)?;
+                self.check_move(x, &val.1)?;
+                Some(val.0)
+            }
+            None => None,
+        };
+        let init = VariantInitialize {
+            owner: owner.0,
+            ident,
+            payload,
+            curried: owner.1,
+        };
+        let curried = init.curried.clone();
+        let idx = self.push_tt_idx(tree!(VariantInit, init));
+        return Ok((idx, curried));
+    }
+
+    // `Shape.circle(r) =>` reads the payload into r, which is in scope for the arm
+    pub fn check_variant_pattern(
+        &mut self,
+        owner: (String, Ty),
+        prop: &ast::PropAccess,
+        inv: &ast::Invoke,
+    ) -> ResultTreeType {
+        self.check_variant(owner.clone(), prop)?;
+        let ident = prop.identifier.into_symbol().val.slice;
+        let args = inv.args.clone().unwrap_or_default();
+        self.check_payload_count(&owner, prop, args.len())?;
+        let payload = match args.first().map(|x| x.as_ref()) {
+            Some(Expr::Symbol(sym)) => {
+                let expected = payload_of(&owner.1, &ident, self.scopes, self.ttbls)
+                    .unwrap()
+                    .1;
+                let idx = self.check_symbol_decl(sym)?.0;
+                if let Some(TypeTree::SymbolInit(x)) = self.ttbls.get_mut(idx as usize) {
+                    x.curried = expected;
+                }
+                self.unused.insert(idx, sym.val.clone());
+                Some(idx)
+            }
+            Some(Expr::Rest(_)) | None => None,
+            Some(x) => {
+                return Err(self.set_error(
+                    "E0110",
+                    "expected a name for the payload".to_string(),
+                    format!(
+                        "the payload of {}.{} can only be bound to a name, or _",
+                        owner.0, ident
+                    ),
+                    lexeme_of(x).unwrap_or(prop.identifier.into_symbol().val),
+                ))
+            }
+        };
+        let init = VariantInitialize {
+            owner: owner.0,
+            ident,
+            payload,
+            curried: owner.1,
+        };
+        let curried = init.curried.clone();
+        let idx = self.push_tt_idx(tree!(VariantInit, init));
+        return Ok((idx, curried));
+    }
+
+    pub fn check_invoke(&mut self, inv: &ast::Invoke) -> ResultTreeType {
+        if let Expr::PropAccess(prop) = inv.prev.as_ref() {
+            if let Some(owner) = self.variant_owner(&prop.prev) {
+                return self.check_variant_init(owner, prop, inv);
+            }
+        }
+        let prev = self.lint_recurse(&inv.prev)?;
+        let mut invoke = types::Invoke {
+            args: vec![],
+            args_curried: vec![],
+            ident: prev.0,
+            curried: prev.1,
+        };
+        if let Some(args) = &inv.args {
+            args.iter().for_each(|a| {
                 if let Ok(prev) = self.lint_recurse(&a) {
                     invoke.args.push(prev.0);
                     invoke.args_curried.push(prev.1);
@@ -978,19 +1946,62 @@ impl<'buf, 'ttb, 'sco> LintSource<'buf, 'ttb, 'sco> {
                 invoke.args_curried.push(Ty::Unknown);
             })
         };
+        let mut params = None;
+        if let Expr::Symbol(sym) = inv.prev.as_ref() {
+            self.check_args(sym, inv, &invoke.args, &invoke.args_curried)?;
+            params = self.params_of(&sym.val.slice);
+        }
+        // borrows made for the call are held until it returns, so they can't conflict
+        if let Some(args) = &inv.args {
+            let mut held = vec![];
+            let checked = self.check_call_args(args, &invoke, params.as_deref(), &mut held);
+            held.iter().for_each(|x| self.owners.end_borrow(*x));
+            checked?;
+        }
         let curried = invoke.curried.clone();
         let full = tree!(Invoke, invoke);
         let idx = self.push_tt_idx(full);
         return Ok((idx, curried));
     }
 
+    // arguments are moved into the call, unless they are borrowed or the parameter borrows them
+    fn check_call_args(
+        &mut self,
+        args: &[Box<Expr>],
+        invoke: &types::Invoke,
+        params: Option<&[Ty]>,
+        held: &mut Vec<TypeTreeIndex>,
+    ) -> Result<(), usize> {
+        for (i, a) in args.iter().enumerate() {
+            let param = params.and_then(|x| x.get(i)).map(strip_binding);
+            let arg = invoke.args[i];
+            let (place, mutable) = match (self.ttbls.get(arg as usize), param) {
+                (Some(TypeTree::ReadBorrow(x)), _) => (x.val, false),
+                (Some(TypeTree::MutBorrow(x)), _) => (x.val, true),
+                (_, Some(Ty::ReadBorrow(_))) => (arg, false),
+                (_, Some(Ty::MutBorrow(_))) => (arg, true),
+                _ => {
+                    self.check_move(a, &invoke.args_curried[i])?;
+                    continue;
+                }
+            };
+            self.check_borrow(place, mutable, a)?;
+            if let (Some((root, _)), Some(at)) = (self.root_of(place), lexeme_of(a)) {
+                self.owners.borrow(arg, Borrow { root, mutable, at });
+                held.push(arg);
+            }
+        }
+        Ok(())
+    }
+
     pub fn check_mul(&mut self, bin: &BinOp) -> ResultTreeType {
         let left = self.lint_recurse(&bin.left)?;
         let right = self.lint_recurse(&bin.right)?;
+        let curried = self.check_operands(bin, &left, &right)?;
         let binop = BinaryOp {
             left: left.0,
             right: right.0,
-            curried: Ty::F64,
+            curried,
         };
         let curried = binop.curried.clone();
         let full = tree!(Multiply, binop);
@@ -1001,6 +2012,7 @@ impl<'buf, 'ttb, 'sco> LintSource<'buf, 'ttb, 'sco> {
     pub fn check_equality(&mut self, bin: &BinOp) -> ResultTreeType {
         let left = self.lint_recurse(&bin.left)?;
         let right = self.lint_recurse(&bin.right)?;
+        self.check_operands(bin, &left, &right)?;
         let binop = BinaryOp {
             left: left.0,
             right: right.0,
@@ -1015,10 +2027,11 @@ impl<'buf, 'ttb, 'sco> LintSource<'buf, 'ttb, 'sco> {
     pub fn check_minus(&mut self, bin: &BinOp) -> ResultTreeType {
         let left = self.lint_recurse(&bin.left)?;
         let right = self.lint_recurse(&bin.right)?;
+        let curried = self.check_operands(bin, &left, &right)?;
         let binop = BinaryOp {
             left: left.0,
             right: right.0,
-            curried: left.1,
+            curried,
         };
         let curried = binop.curried.clone();
         let full = tree!(Minus, binop);
@@ -1029,10 +2042,11 @@ impl<'buf, 'ttb, 'sco> LintSource<'buf, 'ttb, 'sco> {
     pub fn check_plus(&mut self, bin: &BinOp) -> ResultTreeType {
         let left = self.lint_recurse(&bin.left)?;
         let right = self.lint_recurse(&bin.right)?;
+        let curried = self.check_operands(bin, &left, &right)?;
         let binop = BinaryOp {
             left: left.0,
             right: right.0,
-            curried: left.1,
+            curried,
         };
         let curried = binop.curried.clone();
         let full = tree!(Plus, binop);
@@ -1049,12 +2063,35 @@ impl<'buf, 'ttb, 'sco> LintSource<'buf, 'ttb, 'sco> {
     }
 
     pub fn check_arm(&mut self, arm: &Arm) -> ResultTreeType {
-        let left = self.lint_recurse(&arm.left)?;
-        let right = self.lint_recurse(&arm.right)?;
+        let pattern = match arm.left.as_ref() {
+            Expr::Invoke(inv) => match inv.prev.as_ref() {
+                Expr::PropAccess(prop) => self.variant_owner(&prop.prev).map(|x| (x, prop, inv)),
+                _ => None,
+            },
+            _ => None,
+        };
+        let (left, right) = match pattern {
+            Some((owner, prop, inv)) => {
+                self.inc_scope_tracker();
+                let left = self.check_variant_pattern(owner, prop, inv);
+                let right = self.lint_recurse(&arm.right);
+                self.dec_scope_tracker();
+                (left?, right?)
+            }
+            None => (
+                self.lint_recurse(&arm.left)?,
+                self.lint_recurse(&arm.right)?,
+            ),
+        };
+        // an arm is the value it gives, an inlined function gives what it returns
+        let curried = match self.ttbls.get(right.0 as usize) {
+            Some(TypeTree::AnonFuncInit(x)) => x.ret_curried.clone(),
+            _ => right.1,
+        };
         let binop = BinaryOp {
             left: left.0,
             right: right.0,
-            curried: left.1,
+            curried,
         };
         let curried = binop.curried.clone();
         let full = tree!(Arm, binop);
@@ -1079,20 +2116,11 @@ impl<'buf, 'ttb, 'sco> LintSource<'buf, 'ttb, 'sco> {
 
     pub fn check_borrow_mut(&mut self, un: &UnOp) -> ResultTreeType {
         let result = self.lint_recurse(&un.val)?;
+        self.check_borrow(result.0, true, &un.val)?;
         let unop = UnaryOp {
             val: result.0,
-            curried: result.1,
+            curried: Ty::MutBorrow(Box::new(strip_binding(&result.1).clone())),
         };
-        // todo:: at mut check back
-        // match unop.val.as_ref().as_ref() {
-        //     TypeTree::SymbolAccess(sym) => {
-        //         unop.curried = Ty::MutBorrow(Box::new(sym.curried.clone()))
-        //     }
-        //     TypeTree::SelfAccess(sym) => {
-        //         unop.curried = Ty::MutBorrow(Box::new(sym.curried.clone()))
-        //     }
-        //     _ => panic!("borrow_check failed"),
-        // }
         let curried = unop.curried.clone();
         let full = tree!(MutBorrow, unop);
         let idx = self.push_tt_idx(full);
@@ -1101,17 +2129,11 @@ impl<'buf, 'ttb, 'sco> LintSource<'buf, 'ttb, 'sco> {
 
     pub fn check_borrow_ro(&mut self, un: &UnOp) -> ResultTreeType {
         let result = self.lint_recurse(&un.val)?;
+        self.check_borrow(result.0, false, &un.val)?;
         let unop = UnaryOp {
             val: result.0,
-            curried: result.1,
+            curried: Ty::ReadBorrow(Box::new(strip_binding(&result.1).clone())),
         };
-        // todo:: add read borrow back
-        // match unop.val.as_ref().as_ref() {
-        //     TypeTree::SymbolAccess(sym) => {
-        //         unop.curried = Ty::ReadBorrow(Box::new(sym.curried.clone()))
-        //     }
-        //     _ => panic!("borrow_check failed"),
-        // }
         let curried = unop.curried.clone();
         let full = tree!(ReadBorrow, unop);
         let idx = self.push_tt_idx(full);
@@ -1120,16 +2142,26 @@ impl<'buf, 'ttb, 'sco> LintSource<'buf, 'ttb, 'sco> {
 
     pub fn check_dec(&mut self, num: &Number) -> ResultTreeType {
         let val = num.val.slice.parse::<f64>().unwrap();
-        let typ = Ty::F64;
+        let typ = Ty::FloatLiteral;
         let full = tree!(F64, val);
         let idx = self.push_tt_idx(full);
         return Ok((idx, typ));
     }
 
-    // todo:: convert this back to u64, need to check to see if it fits in i64 and return type
+    // whether the literal fits is checked once it is given a type
     pub fn check_num(&mut self, num: &Number) -> ResultTreeType {
-        let val = num.val.slice.parse::<u64>().unwrap();
-        let typ = Ty::U64;
+        let val = match num.val.slice.parse::<u64>() {
+            Ok(x) => x,
+            Err(_) => {
+                return Err(self.set_error(
+                    "E0203",
+                    "integer literal too large".to_string(),
+                    format!("the largest integer literal is {}", u64::MAX),
+                    num.val.clone(),
+                ))
+            }
+        };
+        let typ = Ty::IntLiteral;
         let full = tree!(U64, val);
         let idx = self.push_tt_idx(full);
         return Ok((idx, typ));
@@ -1139,10 +2171,21 @@ impl<'buf, 'ttb, 'sco> LintSource<'buf, 'ttb, 'sco> {
         let mut vals: Vec<u32> = vec![];
         match start {
             Expr::FileAll(all) => {
+                // functions can be called before they are defined, so they are known up front
+                for x in &all.top_decls {
+                    if let Expr::FuncDecl(decl) = x.as_ref() {
+                        let slice = decl.identifier.into_symbol().val.slice;
+                        self.push_tt_symbol_idx(TypeTree::UnknownValue, slice);
+                    }
+                }
                 for x in &all.top_decls {
                     let res = self.lint_recurse(&x);
                     if res.is_ok() {
-                        vals.push(res.unwrap().0);
+                        let idx = res.unwrap().0;
+                        if let Some(name) = pub_name(&x) {
+                            self.pub_scope.this_tree.insert(name, idx);
+                        }
+                        vals.push(idx);
                     }
                 }
                 return vals;
@@ -1158,6 +2201,24 @@ impl<'buf, 'ttb, 'sco> LintSource<'buf, 'ttb, 'sco> {
         self.curr_scope = new_curr as u32;
     }
     fn dec_scope_tracker(&mut self) -> () {
+        self.owners.release(self.curr_scope);
+        let scope = Some(self.curr_scope);
+        let ended: Vec<TypeTreeIndex> = self
+            .unused
+            .keys()
+            .filter(|x| self.owners.scope_of(**x) == scope)
+            .copied()
+            .collect();
+        for x in ended {
+            let lexeme = self.unused.remove(&x).unwrap();
+            let name = lexeme.slice.clone();
+            self.set_warning(
+                "W0001",
+                format!("unused variable {}", name),
+                format!("{} is never read, remove it if it is not needed", name),
+                lexeme,
+            );
+        }
         self.curr_scope = self
             .scopes
             .get(self.curr_scope as usize)
@@ -1177,29 +2238,767 @@ impl<'buf, 'ttb, 'sco> LintSource<'buf, 'ttb, 'sco> {
 
         let tbl = self.scopes.get_mut(self.curr_scope as usize).unwrap();
         tbl.this_tree.insert(slice, idx);
+        self.owners.declare(idx, self.curr_scope);
 
         return idx;
     }
 
-    fn get_tt_by_symbol(&self, sym: &str) -> &TypeTree {
-        let scope = self.scopes.get(self.curr_scope as usize).unwrap();
-        let idx = scope.get_tt_idx_same_up(sym, self.scopes).unwrap();
-        return self.ttbls.get(idx as usize).unwrap();
-    }
-
     fn get_tt_index(&self) -> u32 {
         (self.ttbls.len() - 1) as u32
     }
 
-    fn set_error(&mut self, title: String, suggestion: String, lexeme: Lexeme) -> usize {
-        let mut le = LinterError::new(title);
-        let xcl = CodeLocation::new(self.buffer, lexeme);
-        let lep = LinterErrorPoint::new(xcl.code, xcl.line, xcl.col);
-        le.add_point(lep, suggestion);
+    // a value binding in scope, something that can be moved or borrowed
+    fn binding_of(&self, name: &str) -> Option<TypeTreeIndex> {
+        let ss = self.scopes.get(self.curr_scope as usize)?;
+        let tt = ss.get_tt_idx_same_up(name, self.scopes)?;
+        match self.ttbls.get(tt as usize)? {
+            TypeTree::ConstInit(_)
+            | TypeTree::MutInit(_)
+            | TypeTree::TopConstInit(_)
+            | TypeTree::TopMutInit(_)
+            | TypeTree::ArgInit(_)
+            | TypeTree::SelfInit(_)
+            | TypeTree::SymbolInit(_) => Some(tt),
+            _ => None,
+        }
+    }
+    // the binding a place, like a.b[2], is part of
+    fn root_of(&self, idx: TypeTreeIndex) -> Option<(TypeTreeIndex, String)> {
+        match self.ttbls.get(idx as usize)? {
+            TypeTree::SymbolAccess(x) => Some((self.binding_of(&x.ident)?, x.ident.clone())),
+            TypeTree::SelfAccess(_) => Some((self.binding_of("self")?, "self".to_string())),
+            TypeTree::PropAccess(x) => self.root_of(x.prev),
+            TypeTree::ArrayAccess(x) => self.root_of(x.prev),
+            _ => None,
+        }
+    }
+    // the place is reached through a & borrow, or is a read only element
+    fn behind_read(&self, idx: TypeTreeIndex) -> bool {
+        let tree = match self.ttbls.get(idx as usize) {
+            Some(x) => x,
+            None => return false,
+        };
+        let read = matches!(strip_binding(&tree.get_curried()), Ty::ReadBorrow(_));
+        match tree {
+            TypeTree::SymbolAccess(_) | TypeTree::SelfAccess(_) => read,
+            TypeTree::PropAccess(x) => self.behind_read(x.prev),
+            TypeTree::ArrayAccess(x) => read || self.behind_read(x.prev),
+            _ => false,
+        }
+    }
+    // strings, arrays, structs, and * borrows have one owner, everything else is copied
+    fn is_move_type(&self, ty: &Ty) -> bool {
+        match strip_binding(ty) {
+            Ty::String | Ty::Array(_) | Ty::MutBorrow(_) => true,
+            Ty::Custom(x) => struct_info(x, self.scopes, self.ttbls).is_some(),
+            _ => false,
+        }
+    }
+    // a binding given by value to something else is moved, and can't be used after
+    fn check_move(&mut self, expr: &Expr, ty: &Ty) -> Result<(), usize> {
+        let sym = match expr {
+            Expr::Symbol(x) => x,
+            _ => return Ok(()),
+        };
+        if !self.is_move_type(ty) {
+            return Ok(());
+        }
+        let binding = match self.binding_of(&sym.val.slice) {
+            Some(x) => x,
+            None => return Ok(()),
+        };
+        let name = &sym.val.slice;
+        if self.owners.scope_of(binding) == Some(0) && !self.rets.is_empty() {
+            return Err(self.set_error(
+                "E0301",
+                format!("cannot move out of global {}", name),
+                format!(
+                    "{} lives for the whole program, borrow it with & or use copy {}",
+                    name, name
+                ),
+                sym.val.clone(),
+            ));
+        }
+        if let Some(b) = self.owners.borrowed(binding) {
+            let line = self.line_of(&b.at);
+            return Err(self.set_error(
+                "E0302",
+                format!("cannot move {} while it is borrowed", name),
+                format!("{} is borrowed on line {}", name, line),
+                sym.val.clone(),
+            ));
+        }
+        self.owners.move_out(binding, sym.val.clone());
+        Ok(())
+    }
+    // any number of & borrows, or a single * borrow, can be held at once. nothing behind a &
+    // borrow can be borrowed with *
+    fn check_borrow(
+        &mut self,
+        idx: TypeTreeIndex,
+        mutable: bool,
+        expr: &Expr,
+    ) -> Result<(), usize> {
+        let (root, name) = match self.root_of(idx) {
+            Some(x) => x,
+            None => return Ok(()),
+        };
+        let lexeme = lexeme_of(expr).unwrap_or(Lexeme {
+            token: Token::Symbol,
+            span: 0..0,
+            slice: name.clone(),
+        });
+        if mutable && self.behind_read(idx) {
+            return Err(self.set_error(
+                "E0303",
+                format!("cannot borrow {} as mutable", name),
+                format!("{} is behind a & borrow, take it with * instead", name),
+                lexeme,
+            ));
+        }
+        if let Some(b) = self.owners.conflict(root, mutable) {
+            let line = self.line_of(&b.at);
+            let (held, kind) = match b.mutable {
+                true => ("*", "mutable"),
+                false => ("&", "read only"),
+            };
+            return Err(self.set_error(
+                "E0303",
+                format!(
+                    "cannot borrow {} as {}",
+                    name,
+                    if mutable { "mutable" } else { "read only" }
+                ),
+                format!(
+                    "{} is already borrowed as {} with {} on line {}",
+                    name, kind, held, line
+                ),
+                lexeme,
+            ));
+        }
+        Ok(())
+    }
+    // a binding declared with a borrow holds it until its scope ends
+    fn hold_borrow(&mut self, holder: TypeTreeIndex, expr: &Expr, val: TypeTreeIndex) -> () {
+        let borrow = match (expr, self.ttbls.get(val as usize)) {
+            (Expr::UnOp(un), Some(TypeTree::ReadBorrow(x) | TypeTree::MutBorrow(x))) => {
+                match self.root_of(x.val) {
+                    Some((root, _)) => Borrow {
+                        root,
+                        mutable: un.op.token == Token::Asterisk,
+                        at: un.op.clone(),
+                    },
+                    None => return,
+                }
+            }
+            // a borrow given to another binding goes with it
+            (Expr::Symbol(x), _) => match self
+                .binding_of(&x.val.slice)
+                .and_then(|b| self.owners.borrow_of(b))
+            {
+                Some(b) => b.clone(),
+                None => return,
+            },
+            _ => return,
+        };
+        self.owners.borrow(holder, borrow);
+    }
+    // a place can't be changed through a & borrow, or while it is borrowed
+    fn check_place(
+        &mut self,
+        place: TypeTreeIndex,
+        val: TypeTreeIndex,
+        op: Lexeme,
+    ) -> Result<(), usize> {
+        let (root, name) = match self.root_of(place) {
+            Some(x) => x,
+            None => return Ok(()),
+        };
+        let through = match self.ttbls.get(place as usize) {
+            Some(TypeTree::PropAccess(x)) => self.behind_read(x.prev),
+            Some(TypeTree::ArrayAccess(_)) => self.behind_read(place),
+            _ => false,
+        };
+        if through {
+            return Err(self.set_error(
+                "E0304",
+                "cannot assign through a & borrow".to_string(),
+                format!(
+                    "{} is borrowed read only, take it with * to change it",
+                    name
+                ),
+                op,
+            ));
+        }
+        if let Some(b) = self.owners.borrowed(root) {
+            let line = self.line_of(&b.at);
+            return Err(self.set_error(
+                "E0305",
+                format!("cannot assign to {} while it is borrowed", name),
+                format!("{} is borrowed on line {}", name, line),
+                op,
+            ));
+        }
+        // a binding can't hold on to a borrow of something that is dropped before it
+        if let Some(scope) = self.owners.scope_of(root) {
+            self.check_escape(val, scope, op)?;
+        }
+        Ok(())
+    }
+    // a binding is only changed with = or a compound operator like += when declared with let.
+    // elements and properties can be, unless they are const or behind a & borrow
+    fn check_mutable(&mut self, place: TypeTreeIndex, op: Lexeme) -> Result<(), usize> {
+        let verb = match op.token {
+            Token::As => "assign to".to_string(),
+            _ => format!("use {} on", op.slice),
+        };
+        let tree = self.ttbls.get(place as usize).unwrap();
+        let binding = match tree {
+            TypeTree::SymbolAccess(x) => self.binding_of(&x.ident),
+            TypeTree::SelfAccess(_) => self.binding_of("self"),
+            TypeTree::ArrayAccess(_) if matches!(tree.get_curried(), Ty::Const(_)) => {
+                let name = self.root_of(place).map(|x| x.1).unwrap_or_default();
+                return Err(self.set_error(
+                    "E0307",
+                    format!("cannot {} a const element of {}", verb, name),
+                    format!("the elements of {} are const, declare them with let", name),
+                    op,
+                ));
+            }
+            _ => None,
+        };
+        let (binding, name) = match (binding, self.root_of(place)) {
+            (Some(b), Some((_, name))) => (b, name),
+            _ => return Ok(()),
+        };
+        let ty = self.ttbls.get(binding as usize).unwrap().get_curried();
+        if let Err(x) = ty.ensure_mut() {
+            let how = match x {
+                Ty::Const(_) => "declared with const",
+                _ => "not declared with let",
+            };
+            return Err(self.set_error(
+                "E0307",
+                format!("cannot {} {}, it is immutable", verb, name),
+                format!("{} is {}, declare it with let to change it", name, how),
+                op,
+            ));
+        }
+        Ok(())
+    }
+    // a borrow kept by something in scope can't be of a value dropped before that scope ends.
+    // scopes nest by index, returning keeps it in scope 0
+    fn check_escape(&mut self, val: TypeTreeIndex, scope: u32, at: Lexeme) -> Result<(), usize> {
+        let root = match self.ttbls.get(val as usize) {
+            Some(TypeTree::ReadBorrow(x) | TypeTree::MutBorrow(x)) => self.root_of(x.val),
+            Some(TypeTree::SymbolAccess(x)) => self
+                .binding_of(&x.ident)
+                .and_then(|b| self.owners.borrow_of(b))
+                .map(|b| b.root)
+                .and_then(|b| Some((b, self.name_of(b)?))),
+            _ => None,
+        };
+        let (root, name) = match root {
+            Some(x) => x,
+            None => return Ok(()),
+        };
+        let owned = !matches!(
+            strip_binding(&self.ttbls.get(root as usize).unwrap().get_curried()),
+            Ty::ReadBorrow(_) | Ty::MutBorrow(_)
+        );
+        if owned && self.owners.scope_of(root).is_some_and(|x| x > scope) {
+            return Err(self.set_error(
+                "E0306",
+                format!("borrow of {} escapes its scope", name),
+                format!(
+                    "{} is dropped when its scope ends, give it by value instead",
+                    name
+                ),
+                at,
+            ));
+        }
+        Ok(())
+    }
+    fn name_of(&self, binding: TypeTreeIndex) -> Option<String> {
+        match self.ttbls.get(binding as usize)? {
+            TypeTree::ConstInit(x) | TypeTree::MutInit(x) => Some(
+                self.ttbls
+                    .get(x.left as usize)?
+                    .into_symbol_init()
+                    .ident
+                    .clone(),
+            ),
+            TypeTree::TopConstInit(x) | TypeTree::TopMutInit(x) => Some(
+                self.ttbls
+                    .get(x.left as usize)?
+                    .into_symbol_init()
+                    .ident
+                    .clone(),
+            ),
+            TypeTree::ArgInit(x) | TypeTree::SymbolInit(x) => Some(x.ident.clone()),
+            TypeTree::SelfInit(_) => Some("self".to_string()),
+            _ => None,
+        }
+    }
+
+    // both sides of a binary operation have to agree, the result is the type they agree on
+    fn check_operands(
+        &mut self,
+        bin: &BinOp,
+        left: &(TypeTreeIndex, Ty),
+        right: &(TypeTreeIndex, Ty),
+    ) -> Result<Ty, usize> {
+        self.check_defined(&left.1, bin.op.clone())?;
+        self.check_defined(&right.1, bin.op.clone())?;
+        let ty = match meet(&left.1, &right.1) {
+            Some(x) => x,
+            None => return Err(self.mismatch(&left.1, &right.1, bin.op.clone())),
+        };
+        if ty.is_decimal() || ty == Ty::F128 {
+            return Err(self.set_error(
+                "E0100",
+                format!("{} arithmetic is not yet supported", ty),
+                format!("{} values can only be stored and passed around", ty),
+                bin.op.clone(),
+            ));
+        }
+        self.check_literal(left.0, &ty, bin.op.clone())?;
+        self.check_literal(right.0, &ty, bin.op.clone())?;
+        Ok(ty)
+    }
+    // a value given to something of the expected type, the type the value ends up as
+    fn check_into(
+        &mut self,
+        expected: &Ty,
+        found: &(TypeTreeIndex, Ty),
+        lexeme: Lexeme,
+    ) -> Result<Ty, usize> {
+        let ty = match unify(expected, &found.1) {
+            Some(x) => x,
+            None if strip(&found.1) == &Ty::Undefined => {
+                return Err(self.set_error(
+                    "E0113",
+                    format!("{} cannot be undefined", strip(expected)),
+                    format!(
+                        "only an optional, like ?{}, can be undefined",
+                        strip(expected)
+                    ),
+                    lexeme,
+                ))
+            }
+            None if found
+                .1
+                .optional()
+                .is_some_and(|x| unify(expected, &x).is_some()) =>
+            {
+                return Err(self.maybe_undefined(&found.1, lexeme))
+            }
+            None => return Err(self.mismatch(expected, &found.1, lexeme)),
+        };
+        self.check_literal(found.0, &ty, lexeme)?;
+        Ok(ty)
+    }
+    // `~=` has no meaning yet, floats have no remainder or bits to operate on, and decimals have
+    // no arithmetic
+    fn check_compound(&mut self, ty: &Ty, op: Lexeme) -> Result<(), usize> {
+        let ty = strip(ty);
+        let bits = matches!(
+            op.token,
+            Token::ModAs | Token::OrAs | Token::XorAs | Token::LShiftAs | Token::RShiftAs
+        );
+        let suggestion = match op.token {
+            Token::NotAs => format!(
+                "{} can be parsed, but not yet checked or compiled",
+                op.slice
+            ),
+            _ if ty.is_decimal() || ty == &Ty::F128 || (ty.is_float() && bits) => {
+                format!("{} can not yet be used on {} values", op.slice, ty)
+            }
+            _ => return Ok(()),
+        };
+        Err(self.set_error(
+            "E0100",
+            format!("not yet supported: the {} operator", op.slice),
+            suggestion,
+            op,
+        ))
+    }
+    // an optional has to be checked before what it holds is read
+    fn check_defined(&mut self, ty: &Ty, lexeme: Lexeme) -> Result<(), usize> {
+        match ty.optional() {
+            Some(_) => Err(self.maybe_undefined(ty, lexeme)),
+            None => Ok(()),
+        }
+    }
+    fn maybe_undefined(&mut self, ty: &Ty, lexeme: Lexeme) -> usize {
+        self.set_error(
+            "E0114",
+            "value may be undefined".to_string(),
+            format!(
+                "{} may be undefined, check it with `if` first or pass it on with ?",
+                strip(ty)
+            ),
+            lexeme,
+        )
+    }
+    // literals have to fit in the type they are given to
+    fn check_literal(&mut self, idx: TypeTreeIndex, to: &Ty, lexeme: Lexeme) -> Result<(), usize> {
+        let to = strip(to);
+        let val = match literal_of(idx, self.ttbls) {
+            Some(x) => x,
+            None => return Ok(()),
+        };
+        if to.is_decimal() || to == &Ty::F128 {
+            return Err(self.set_error(
+                "E0100",
+                format!("{} literals are not yet supported", to),
+                format!("{} values can only be stored and passed around", to),
+                lexeme,
+            ));
+        }
+        let fits = match (val, to.int_range()) {
+            (Literal::Int(x), Some((lo, hi))) => x >= lo && x <= hi,
+            (Literal::Float(x), _) if to == &Ty::F32 => x.abs() <= f32::MAX as f64,
+            _ => true,
+        };
+        if !fits {
+            return Err(self.set_error(
+                "E0203",
+                format!("literal out of range for {}", to),
+                match to.int_range() {
+                    Some((lo, hi)) => format!("{} holds {} to {}", to, lo, hi),
+                    None => format!("{} is too large for {}", val, to),
+                },
+                lexeme,
+            ));
+        }
+        Ok(())
+    }
+    fn check_ret_type(
+        &mut self,
+        found: Option<&(TypeTreeIndex, Ty)>,
+        ret: &RetOp,
+    ) -> Result<(), usize> {
+        let expected = match self.rets.last() {
+            Some(x) => x.clone(),
+            None => return Ok(()),
+        };
+        match found {
+            Some(x) => self.check_into(&expected, x, ret.span.clone()).map(|_| ()),
+            None if unify(&expected, &Ty::Void).is_none() => {
+                Err(self.mismatch(&expected, &Ty::Void, ret.span.clone()))
+            }
+            None => Ok(()),
+        }
+    }
+    // a declaration with a type has to be given a value of that type, the binding takes on the
+    // declared type. without one it is the type of its value
+    fn check_declared(
+        &mut self,
+        ident: &Expr,
+        typ: &Option<Box<Expr>>,
+        found: &(TypeTreeIndex, Ty),
+    ) -> Result<Ty, usize> {
+        let typ = match typ {
+            Some(x) => self.lint_recurse(x)?,
+            None if strip(&found.1) == &Ty::Undefined => {
+                return Err(self.set_error(
+                    "E0113",
+                    "undefined needs an optional type".to_string(),
+                    "declare what it is undefined of, like `const x: ?u64 = undefined`".to_string(),
+                    ident.into_symbol().val,
+                ))
+            }
+            None => return Ok(found.1.clone()),
+        };
+        // the binding is still declared so later uses of it can be checked
+        match self.check_into(&typ.1, found, ident.into_symbol().val) {
+            Ok(x) if typ.1 == Ty::Unknown => Ok(x),
+            _ => Ok(typ.1),
+        }
+    }
+    fn check_prop_type(&mut self, obj: &Ty, prop: &PropAssignment, found: &(TypeTreeIndex, Ty)) {
+        let ident = prop.ident.into_symbol().val;
+        match prop_of(obj, &ident.slice, self.scopes, self.ttbls) {
+            Some((_, expected)) => {
+                let _ = self.check_into(&expected, found, ident);
+            }
+            None => {
+                if let Ty::Custom(x) = obj {
+                    let suggestion = format!("{} has no property {}", x, ident.slice);
+                    self.set_error(
+                        "E0103",
+                        format!("unknown property {}", ident.slice),
+                        suggestion,
+                        ident,
+                    );
+                }
+            }
+        }
+    }
+    // the arguments of a call to a known function are checked against its parameters
+    fn check_args(
+        &mut self,
+        sym: &Symbol,
+        inv: &ast::Invoke,
+        args: &Vec<TypeTreeIndex>,
+        found: &Vec<Ty>,
+    ) -> Result<(), usize> {
+        let params = match self.params_of(&sym.val.slice) {
+            Some(x) => x,
+            None => return Ok(()),
+        };
+        let variadic = params.last() == Some(&Ty::Rest);
+        let fixed = params.len() - variadic as usize;
+        if found.len() < fixed || (!variadic && found.len() > fixed) {
+            return Err(self.set_error(
+                "E0104",
+                format!("wrong number of arguments to {}", sym.val.slice),
+                format!("expected {}, found {}", fixed, found.len()),
+                sym.val.clone(),
+            ));
+        }
+        for (i, expected) in params.iter().take(fixed).enumerate() {
+            let lexeme = inv
+                .args
+                .as_ref()
+                .and_then(|x| lexeme_of(&x[i]))
+                .unwrap_or(sym.val.clone());
+            self.check_into(expected, &(args[i], found[i].clone()), lexeme)?;
+        }
+        Ok(())
+    }
+    fn params_of(&self, ident: &str) -> Option<Vec<Ty>> {
+        let ss = self.scopes.get(self.curr_scope as usize)?;
+        let tt = ss.get_tt_idx_same_up(ident, self.scopes)?;
+        match self.ttbls.get(tt as usize)? {
+            TypeTree::FuncInit(x) => Some(x.args_curried.clone()),
+            TypeTree::ExternInit(x) => Some(x.args_curried.clone()),
+            TypeTree::ImportInit(x) => x.args_curried.clone(),
+            _ => None,
+        }
+    }
+    // a tag variant is given its payload when it has one, and nothing otherwise
+    fn check_payload_count(
+        &mut self,
+        owner: &(String, Ty),
+        prop: &ast::PropAccess,
+        found: usize,
+    ) -> Result<(), usize> {
+        let ident = prop.identifier.into_symbol().val;
+        let expected = match payload_of(&owner.1, &ident.slice, self.scopes, self.ttbls) {
+            Some((_, Ty::Void)) | None => 0,
+            Some(_) => 1,
+        };
+        if found == expected {
+            return Ok(());
+        }
+        Err(self.set_error(
+            "E0104",
+            format!("wrong number of payloads to {}.{}", owner.0, ident.slice),
+            match expected {
+                0 => format!("{} has no payload, remove the parentheses", ident.slice),
+                _ => format!(
+                    "{} carries one payload, give it in parentheses",
+                    ident.slice
+                ),
+            },
+            ident,
+        ))
+    }
+    // `Day.monday` names a variant when the left is the enum, tag, or error type itself
+    fn variant_owner(&self, prev: &Expr) -> Option<(String, Ty)> {
+        let sym = match prev {
+            Expr::Symbol(x) => x,
+            _ => return None,
+        };
+        let scope = self.scopes.get(self.curr_scope as usize)?;
+        let idx = scope.get_tt_idx_same_up(&sym.val.slice, self.scopes)?;
+        match self.ttbls.get(idx as usize)? {
+            x @ (TypeTree::EnumInfo(_) | TypeTree::TagInfo(_) | TypeTree::ErrorInfo(_)) => {
+                Some((sym.val.slice.clone(), x.get_curried()))
+            }
+            _ => None,
+        }
+    }
+    // an arm has to match the type of what is matched on
+    fn check_pattern(&mut self, ty: &Ty, arm: TypeTreeIndex, expr: &Expr) -> Result<(), usize> {
+        let left = self.ttbls.get(arm as usize).unwrap().into_binary_op().left;
+        let tree = self.ttbls.get(left as usize).unwrap();
+        if matches!(tree, TypeTree::RestAccess(_)) {
+            return Ok(());
+        }
+        let found = (left, tree.get_curried());
+        match lexeme_of(expr) {
+            Some(lexeme) => self.check_into(ty, &found, lexeme).map(|_| ()),
+            None => Ok(()),
+        }
+    }
+    // every variant needs an arm, or a _ arm for the rest. arms after everything is covered can
+    // never be reached
+    fn check_coverage(
+        &mut self,
+        ty: &Ty,
+        arms: &Vec<TypeTreeIndex>,
+        _match: &Match,
+    ) -> Result<(), usize> {
+        let owner = match strip(ty) {
+            Ty::Custom(x) | Ty::CustomError(x) => format!("{}.", x),
+            _ => "".to_string(),
+        };
+        let all: Vec<String> = match variants_of(ty, self.scopes, self.ttbls) {
+            Some(x) if !x.is_empty() => x,
+            _ if strip(ty) == &Ty::Bool => vec!["true".to_string(), "false".to_string()],
+            _ => vec![],
+        };
+        let mut covered: Vec<String> = vec![];
+        let mut rest = false;
+        for (arm, expr) in arms.iter().zip(_match.arms.iter()) {
+            let left = match self.ttbls.get(*arm as usize) {
+                Some(TypeTree::Arm(x)) => x.left,
+                _ => continue,
+            };
+            let name = match self.ttbls.get(left as usize) {
+                Some(TypeTree::RestAccess(_)) => None,
+                Some(TypeTree::VariantAccess(x)) => Some(x.ident.clone()),
+                Some(TypeTree::VariantInit(x)) => Some(x.ident.clone()),
+                Some(TypeTree::BoolValue(x)) => Some(x.to_string()),
+                _ => Some("".to_string()),
+            };
+            let done = rest || (!all.is_empty() && all.iter().all(|x| covered.contains(x)));
+            let seen = name.as_ref().is_some_and(|x| covered.contains(x));
+            if let (true, Some(lexeme)) = (done || seen, lexeme_of(expr)) {
+                self.set_warning(
+                    "W0002",
+                    "unreachable match arm".to_string(),
+                    match (done, &name) {
+                        (false, Some(x)) => format!("{}{} is already matched above", owner, x),
+                        _ => "everything is already matched by the arms above".to_string(),
+                    },
+                    lexeme,
+                );
+            }
+            match name {
+                Some(x) if !x.is_empty() => covered.push(x),
+                Some(_) => (),
+                None => rest = true,
+            }
+        }
+        if rest || matches!(strip(ty), Ty::Unknown | Ty::Any) {
+            return Ok(());
+        }
+        let lexeme = match lexeme_of(&_match.expr) {
+            Some(x) => x,
+            None => return Ok(()),
+        };
+        if all.is_empty() {
+            return Err(self.set_error(
+                "E0106",
+                "_ not covered".to_string(),
+                format!(
+                    "{} has more values than the arms list, add a _ arm",
+                    strip(ty)
+                ),
+                lexeme,
+            ));
+        }
+        let missing: Vec<String> = all
+            .iter()
+            .filter(|x| !covered.contains(x))
+            .map(|x| format!("{}{}", owner, x))
+            .collect();
+        if missing.is_empty() {
+            return Ok(());
+        }
+        Err(self.set_error(
+            "E0106",
+            format!("{} not covered", missing.join(", ")),
+            "add an arm for each, or a _ arm for the rest".to_string(),
+            lexeme,
+        ))
+    }
+    // something that parses, but the linter can't check or compile yet
+    fn not_supported(&mut self, what: &str, expr: &Expr) -> usize {
+        let lexeme = lexeme_of(expr).unwrap_or(Lexeme {
+            token: Token::Symbol,
+            span: 0..0,
+            slice: String::new(),
+        });
+        self.set_error(
+            "E0100",
+            format!("not yet supported: {}", what),
+            format!("{} can be parsed, but not yet checked or compiled", what),
+            lexeme,
+        )
+    }
+    // only an error union can be tried or caught, what it holds without the error is given back
+    fn check_error_union(&mut self, ty: &Ty, lexeme: Lexeme) -> Result<Ty, usize> {
+        match ty.error_union() {
+            Some(x) => Ok(x),
+            None => Err(self.set_error(
+                "E0111",
+                format!("{} is never an error", strip(ty)),
+                "only an error union, like E!T, can be tried or caught".to_string(),
+                lexeme,
+            )),
+        }
+    }
+    fn mismatch(&mut self, expected: &Ty, found: &Ty, lexeme: Lexeme) -> usize {
+        self.set_error(
+            "E0200",
+            "mismatched types".to_string(),
+            format!("expected {}, found {}", strip(expected), strip(found)),
+            lexeme,
+        )
+    }
 
+    fn set_error(
+        &mut self,
+        id: &'static str,
+        title: String,
+        suggestion: String,
+        lexeme: Lexeme,
+    ) -> usize {
+        let le = self.make_error(id, title, suggestion, lexeme);
         self.issues.push(le);
         return self.issues.len() - 1;
     }
+    fn line_of(&self, lexeme: &Lexeme) -> usize {
+        self.source.position(lexeme.span.start).0
+    }
+    // warnings are reported, but don't stop the file from being compiled
+    fn set_warning(&mut self, id: &'static str, title: String, suggestion: String, lexeme: Lexeme) {
+        let le = self.make_error(id, title, suggestion, lexeme);
+        self.warnings.push(le.with_severity(Severity::Warning));
+    }
+    // points somewhere else that explains the issue
+    fn add_note(&mut self, issue: usize, note: String, lexeme: &Lexeme) {
+        let lep = self.make_point(lexeme);
+        self.issues[issue].add_note(lep, note);
+    }
+    fn make_error(
+        &self,
+        id: &'static str,
+        title: String,
+        suggestion: String,
+        lexeme: Lexeme,
+    ) -> LinterError {
+        let mut le = LinterError::new(title).with_id(id);
+        le.add_point(self.make_point(&lexeme), suggestion);
+        le
+    }
+    fn make_point(&self, lexeme: &Lexeme) -> LinterErrorPoint {
+        let span = self.source.span(&lexeme.span);
+        LinterErrorPoint::new(
+            self.source.name.clone(),
+            self.source.code(&lexeme.span).to_string(),
+            self.source.line(span.line).to_string(),
+            span.line,
+            span.col,
+            span.end_line,
+            span.end_col,
+        )
+    }
 }
 
 pub fn make_error(title: String) -> LinterError {
@@ -1207,108 +3006,416 @@ pub fn make_error(title: String) -> LinterError {
 }
 
 trait DoConvert {
-    fn into_type(self) -> Ty;
+    fn into_type(self) -> Option<Ty>;
 }
 
-impl DoConvert for &Expr {
-    fn into_type(self) -> Ty {
-        match self {
-            Expr::Number(num) => match num.val.token {
-                Token::Decimal => Ty::F64,
-                // todo:: check if it fits in u64
-                Token::Number => Ty::I64,
-                _ => panic!("type-lang linter issue, number not implemented"),
-            },
-            Expr::ValueType(val) => match val.val.token {
-                Token::I32 => Ty::I32,
-                Token::U32 => Ty::U32,
-                Token::I64 => Ty::I64,
-                Token::U64 => Ty::U64,
-                Token::I16 => Ty::I32,
-                Token::U16 => Ty::U32,
-                Token::U8 => Ty::U32,
-                Token::I8 => Ty::U32,
-                Token::Bit => Ty::U32,
-                Token::F64 => Ty::F64,
-                Token::D64 => Ty::U32,
-                Token::F32 => Ty::U32,
-                Token::D32 => Ty::U32,
-                Token::D128 => Ty::U32,
-                Token::F128 => Ty::U32,
-                Token::ISize => Ty::U32,
-                Token::USize => Ty::U32,
-                Token::Char => Ty::Char,
-                Token::Utf8 => Ty::Char,
-                Token::Utf16 => Ty::Char,
-                Token::Utf32 => Ty::Char,
-                Token::Utf64 => Ty::Char,
-                Token::Bool => Ty::Char,
-                Token::Any => Ty::Any,
-                Token::Sized => Ty::Sized,
-                Token::Scalar => Ty::Scalar,
-                Token::Void => Ty::Void,
-                Token::TSelf => Ty::TSelf,
-                _ => panic!("type-lang linter issue, not a value type"),
-            },
-            _ => panic!("type-lang linter issue, unhandled expr"),
+impl DoConvert for &ValueType {
+    fn into_type(self) -> Option<Ty> {
+        let ty = match self.val.token {
+            Token::I32 => Ty::I32,
+            Token::U32 => Ty::U32,
+            Token::I64 => Ty::I64,
+            Token::U64 => Ty::U64,
+            Token::I16 => Ty::I16,
+            Token::U16 => Ty::U16,
+            Token::U8 => Ty::U8,
+            Token::I8 => Ty::I8,
+            Token::Bit => Ty::Bit,
+            Token::F64 => Ty::F64,
+            Token::D64 => Ty::D64,
+            Token::F32 => Ty::F32,
+            Token::D32 => Ty::D32,
+            Token::D128 => Ty::D128,
+            Token::F128 => Ty::F128,
+            Token::ISize => Ty::ISize,
+            Token::USize => Ty::USize,
+            Token::Char => Ty::Char,
+            Token::Utf8 => Ty::Utf8,
+            Token::Utf16 => Ty::Utf16,
+            Token::Utf32 => Ty::Utf32,
+            Token::Utf64 => Ty::Utf64,
+            Token::Bool => Ty::Bool,
+            Token::Any => Ty::Any,
+            Token::Sized => Ty::Sized,
+            Token::Scalar => Ty::Scalar,
+            Token::Void => Ty::Void,
+            Token::TSelf => Ty::TSelf,
+            _ => return None,
+        };
+        Some(ty)
+    }
+}
+
+impl DoConvert for &Number {
+    fn into_type(self) -> Option<Ty> {
+        match self.val.token {
+            Token::Decimal => Some(Ty::FloatLiteral),
+            Token::Number => Some(Ty::IntLiteral),
+            _ => None,
         }
     }
 }
 
-#[cfg(test)]
-mod tests {
+// the name of a top level item that is visible to other modules
+fn pub_name(expr: &Expr) -> Option<String> {
+    let (vis, ident) = match expr {
+        Expr::TopDecl(x) => (&x.visibility, &x.identifier),
+        Expr::FuncDecl(x) => (&x.visibility, &x.identifier),
+        Expr::StructDecl(x) => (&x.visibility, &x.identifier),
+        Expr::EnumDecl(x) => (&x.visibility, &x.identifier),
+        Expr::TagDecl(x) => (&x.visibility, &x.identifier),
+        Expr::ErrorDecl(x) => (&x.visibility, &x.identifier),
+        Expr::TraitDecl(x) => (&x.visibility, &x.identifier),
+        _ => return None,
+    };
+    match vis {
+        Some(v) if v.token != Token::Extern => Some(ident.into_symbol().val.slice),
+        _ => None,
+    }
+}
 
-    use super::*;
-    use parser::*;
-    #[test]
-    fn it_should_check_double_negation() {
-        let lexer = TLexer::new("8 + --5");
-        let mut parser = Parser::new(lexer);
-        let result = parser.low_bin();
-        let mut tts = vec![];
-        let mut scps = vec![];
-        let mut linter = LintSource::new("8 + --5", &mut scps, &mut tts);
-        let test = linter.lint_recurse(&result.unwrap());
+// the type without its const or let
+fn strip_binding(ty: &Ty) -> &Ty {
+    match ty {
+        Ty::Const(x) | Ty::Mut(x) => strip_binding(x),
+        _ => ty,
+    }
+}
 
-        assert!(test.is_err_and(|x| { x == 0 }));
-        assert_eq!(
-            linter.issues.get(0).unwrap().points.get(0).unwrap(),
-            &LinterErrorPoint {
-                code: "8 + -".to_string(),
-                line: 1,
-                col: 5
-            }
-        );
+fn strip(ty: &Ty) -> &Ty {
+    match ty {
+        Ty::Const(x) | Ty::Mut(x) | Ty::ReadBorrow(x) | Ty::MutBorrow(x) => strip(x),
+        _ => ty,
     }
-    #[test]
-    fn it_should_handle_func_args() {
-        const TEST_STR: &'static str = "const add = fn(x: u64, y: u64) u64 { return x + y }
-        ";
-        let lexer = TLexer::new(TEST_STR);
-        let mut parser = Parser::new(lexer);
-        let result = parser.all();
-        let mut tts = vec![];
-        let mut scps = vec![];
-        let mut linter = LintSource::new(TEST_STR, &mut scps, &mut tts);
-        let _ = linter.lint_check(&result.unwrap());
+}
 
-        assert!(linter.issues.len() == 0);
+// only numbers, chars, and bools can be cast between each other
+fn castable(ty: &Ty) -> bool {
+    let ty = strip(ty);
+    ty.bits().is_some()
+        || matches!(
+            ty,
+            Ty::Bool | Ty::IntLiteral | Ty::FloatLiteral | Ty::Unknown | Ty::Any
+        )
+}
+
+// a value of from can be held by to without losing anything
+fn widens(from: &Ty, to: &Ty) -> bool {
+    let (f, t) = match (from.bits(), to.bits()) {
+        (Some(f), Some(t)) => (f, t),
+        _ => return false,
+    };
+    if from.is_int() && to.is_int() {
+        return f < t && !(from.is_signed() && !to.is_signed());
     }
-    #[test]
-    fn it_should_handle_sigs() {
-        const TEST_STR: &'static str = "
-        const val: zerror!?usize = 2
-        const val2: !?usize = 3
-        const val3: ?usize = 4
+    if from.is_utf() && to.is_utf() {
+        return f <= t;
+    }
+    // f128 has no conversions in code generation yet
+    let floats = from.is_float() && to.is_float() && to != &Ty::F128;
+    (floats || (from.is_decimal() && to.is_decimal())) && f < t
+}
+
+// the type a value of found takes on when it is given to something of type expected, none if it
+// doesn't fit. literals take on the type they are given to, smaller numbers widen to larger ones
+fn unify(expected: &Ty, found: &Ty) -> Option<Ty> {
+    let (expected, found) = (strip(expected), strip(found));
+    match (expected, found) {
+        (Ty::Unknown | Ty::Any, x) | (x, Ty::Unknown | Ty::Any | Ty::Never) => Some(x.clone()),
+        (Ty::IntLiteral, x) if x.is_int() || x == &Ty::IntLiteral => Some(x.clone()),
+        (x, Ty::IntLiteral) if x.is_int() || x.is_float() || x.is_utf() || x.is_decimal() => {
+            Some(x.clone())
+        }
+        (Ty::FloatLiteral, x) if x.is_float() => Some(x.clone()),
+        (Ty::FloatLiteral, Ty::IntLiteral | Ty::FloatLiteral) => Some(Ty::FloatLiteral),
+        (x, Ty::FloatLiteral) if x.is_float() || x.is_decimal() => Some(x.clone()),
+        (x, y) if widens(y, x) => Some(x.clone()),
+        (Ty::Array(x), Ty::Array(y)) => Some(Ty::Array(Box::new(unify(x, y)?))),
+        (Ty::String, Ty::Array(x)) | (Ty::Array(x), Ty::String) => {
+            unify(&Ty::Char, x).map(|_| Ty::String)
+        }
+        // a tag holds any tag made of its types
+        (Ty::Tag(x), Ty::Tag(y)) => y
+            .iter()
+            .all(|t| x.iter().any(|e| unify(e, t).is_some()))
+            .then(|| expected.clone()),
+        (Ty::Tag(x), y) => x.iter().find_map(|t| unify(t, y)).map(|_| expected.clone()),
+        // any error is an error
+        (Ty::Error, y) if y.is_error() => Some(y.clone()),
+        (x, y) if x == y => Some(x.clone()),
+        _ => None,
+    }
+}
+
+#[derive(Clone, Copy)]
+enum Literal {
+    Int(i128),
+    Float(f64),
+}
+
+impl std::fmt::Display for Literal {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Literal::Int(x) => write!(f, "{}", x),
+            Literal::Float(x) => write!(f, "{}", x),
+        }
+    }
+}
+
+// the value of an expression made only of literals
+fn literal_of(idx: TypeTreeIndex, types: &Vec<TypeTree>) -> Option<Literal> {
+    let bin =
+        |op: &BinaryOp, int: fn(i128, i128) -> Option<i128>, float: fn(f64, f64) -> f64| match (
+            literal_of(op.left, types)?,
+            literal_of(op.right, types)?,
+        ) {
+            (Literal::Int(l), Literal::Int(r)) => int(l, r).map(Literal::Int),
+            (Literal::Int(l), Literal::Float(r)) => Some(Literal::Float(float(l as f64, r))),
+            (Literal::Float(l), Literal::Int(r)) => Some(Literal::Float(float(l, r as f64))),
+            (Literal::Float(l), Literal::Float(r)) => Some(Literal::Float(float(l, r))),
+        };
+    match types.get(idx as usize)? {
+        TypeTree::U64(x) => Some(Literal::Int(*x as i128)),
+        TypeTree::F64(x) => Some(Literal::Float(*x)),
+        TypeTree::Negate(x) => match literal_of(x.val, types)? {
+            Literal::Int(x) => Some(Literal::Int(-x)),
+            Literal::Float(x) => Some(Literal::Float(-x)),
+        },
+        TypeTree::Plus(x) => bin(x, i128::checked_add, |l, r| l + r),
+        TypeTree::Minus(x) => bin(x, i128::checked_sub, |l, r| l - r),
+        TypeTree::Multiply(x) => bin(x, i128::checked_mul, |l, r| l * r),
+        _ => None,
+    }
+}
+
+// globals are written into the object as they are, so their values are folded when compiling.
+// this is what oir can fold, and why a value cannot be. structs, arrays, and strings are only
+// folded where they are written out whole, not through another name or an operator
+fn unfolded(
+    idx: TypeTreeIndex,
+    whole: bool,
+    scopes: &Vec<ScopeTable>,
+    types: &Vec<TypeTree>,
+) -> Option<String> {
+    match types.get(idx as usize)? {
+        TypeTree::I64(_)
+        | TypeTree::U64(_)
+        | TypeTree::I32(_)
+        | TypeTree::U32(_)
+        | TypeTree::F64(_)
+        | TypeTree::BoolValue(_) => None,
+        TypeTree::StringInit(_) if whole => None,
+        TypeTree::Char(x) => match u8::try_from(*x) {
+            Ok(_) => None,
+            Err(_) => Some(format!("'{}' does not fit in a byte", x)),
+        },
+        TypeTree::ArrayInit(x) if whole => x
+            .vals
+            .iter()
+            .find_map(|x| unfolded(*x, true, scopes, types)),
+        TypeTree::StructInit(x) if whole => {
+            x.idents.iter().find_map(|x| match types.get(*x as usize) {
+                Some(TypeTree::PropInit(prop)) => unfolded(prop.right, true, scopes, types),
+                _ => None,
+            })
+        }
+        TypeTree::Negate(x) => unfolded(x.val, false, scopes, types),
+        TypeTree::Plus(x) | TypeTree::Minus(x) | TypeTree::Multiply(x) => {
+            unfolded(x.left, false, scopes, types).or(unfolded(x.right, false, scopes, types))
+        }
+        TypeTree::SymbolAccess(x) => {
+            let top = scopes
+                .first()
+                .and_then(|s| s.this_tree.get(&x.ident))
+                .and_then(|t| types.get(*t as usize));
+            match top {
+                Some(TypeTree::TopConstInit(init)) => unfolded(init.right, false, scopes, types)
+                    .map(|_| {
+                        format!(
+                            "{} cannot be copied into another global, write the value out",
+                            x.ident
+                        )
+                    }),
+                _ => Some(format!(
+                    "{} is not a constant, only const globals can be used",
+                    x.ident
+                )),
+            }
+        }
+        _ => Some("use literals, other constants, and + - * on them".to_string()),
+    }
+}
+
+// the named errors a value can be
+fn errors_of(ty: &Ty) -> Vec<Ty> {
+    match strip(ty) {
+        Ty::Tag(x) => x
+            .iter()
+            .filter(|x| matches!(x, Ty::CustomError(_)))
+            .cloned()
+            .collect(),
+        x @ Ty::CustomError(_) => vec![x.clone()],
+        _ => vec![],
+    }
+}
+
+// a function returning E!T only returns E, one returning !T returns any error
+fn returns_error(ret: &Ty, err: &Ty) -> bool {
+    let errs = errors_of(ret);
+    errs.is_empty() || errs.contains(err)
+}
+
+// the type of an optional once it is known to be defined, still const or let
+fn narrow(ty: &Ty) -> Ty {
+    match ty {
+        Ty::Const(x) => Ty::Const(Box::new(narrow(x))),
+        Ty::Mut(x) => Ty::Mut(Box::new(narrow(x))),
+        x => x.optional().unwrap_or(x.clone()),
+    }
+}
+
+// the name of a binding, or property of one, like `self.current`
+fn path_of(expr: &Expr) -> Option<String> {
+    match expr {
+        Expr::Symbol(x) => Some(x.val.slice.clone()),
+        Expr::SelfValue(_) => Some("self".to_string()),
+        Expr::PropAccess(x) => Some(format!(
+            "{}.{}",
+            path_of(&x.prev)?,
+            x.identifier.into_symbol().val.slice
+        )),
+        _ => None,
+    }
+}
+
+// the type both sides of an operation agree on
+fn meet(left: &Ty, right: &Ty) -> Option<Ty> {
+    unify(left, right).or_else(|| unify(right, left))
+}
+
+// somewhere in the source to point at for an expression
+fn lexeme_of(expr: &Expr) -> Option<Lexeme> {
+    match expr {
+        Expr::Symbol(x) => Some(x.val.clone()),
+        Expr::Number(x) => Some(x.val.clone()),
+        Expr::CharsValue(x) => Some(x.val.clone()),
+        Expr::BoolValue(x) => Some(x.val.clone()),
+        Expr::BinOp(x) => Some(x.op.clone()),
+        Expr::UnOp(x) => Some(x.op.clone()),
+        Expr::Invoke(x) => lexeme_of(&x.prev),
+        Expr::PropAccess(x) => lexeme_of(&x.prev),
+        Expr::ArrayAccess(x) => lexeme_of(&x.prev),
+        Expr::PropAssignments(x) => lexeme_of(&x.prev),
+        Expr::ErrBubble(x) => lexeme_of(&x.prev),
+        Expr::UndefBubble(x) => lexeme_of(&x.prev).or(Some(x.span.clone())),
+        Expr::TraitDecl(x) => lexeme_of(&x.identifier),
+        Expr::TopDecl(x) => Some(x.mutability.clone()),
+        Expr::InnerDecl(x) => Some(x.mutability.clone()),
+        Expr::SymbolDecl(x) => Some(x.val.clone()),
+        Expr::SelfDecl(x) | Expr::SelfValue(x) => Some(x.val.clone()),
+        Expr::Never(x) => Some(x.val.clone()),
+        Expr::RetOp(x) => Some(x.span.clone()),
+        Expr::Destructure(x) => x.elements.first().and_then(|x| lexeme_of(x)),
+        Expr::FuncType(x) => lexeme_of(&x.ret_typ),
+        Expr::CatchDecl(x) => lexeme_of(&x.prev).or(Some(x.span.clone())),
+        Expr::ArgDef(x) => lexeme_of(&x.ident),
+        Expr::Arm(x) => lexeme_of(&x.left),
+        Expr::Rest(x) => Some(x.val.clone()),
+        _ => None,
+    }
+}
+
+// the type of a single element, when indexing into an array or string
+fn elem_of(ty: &Ty) -> Ty {
+    match ty {
+        Ty::Const(x) | Ty::Mut(x) | Ty::ReadBorrow(x) | Ty::MutBorrow(x) => elem_of(x),
+        Ty::Array(x) => *x.clone(),
+        Ty::String => Ty::Char,
+        _ => Ty::Unknown,
+    }
+}
+
+fn unescape(raw: &str) -> Option<String> {
+    let mut out = String::new();
+    let mut chars = raw.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            out.push(c);
+            continue;
+        }
+        match chars.next()? {
+            'n' => out.push('\n'),
+            't' => out.push('\t'),
+            'r' => out.push('\r'),
+            '0' => out.push('\0'),
+            '\n' => (),
+            x @ ('\\' | '\'' | '"' | '`') => out.push(x),
+            _ => return None,
+        }
+    }
+    Some(out)
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use parser::*;
+    #[test]
+    fn it_should_check_double_negation() {
+        let lexer = TLexer::new("8 + --5");
+        let mut parser = Parser::new(lexer);
+        let result = parser.low_bin();
+        let mut tts = vec![];
+        let mut scps = vec![];
+        let mut linter = LintSource::new("8 + --5", &mut scps, &mut tts);
+        let test = linter.lint_recurse(&result.unwrap());
+
+        assert!(test.is_err_and(|x| { x == 0 }));
+        assert_eq!(
+            linter.issues.get(0).unwrap().points.get(0).unwrap(),
+            &LinterErrorPoint {
+                file: "".to_string(),
+                code: "8 + -".to_string(),
+                source: "8 + --5".to_string(),
+                line: 1,
+                col: 5,
+                end_line: 1,
+                end_col: 6,
+            }
+        );
+    }
+    #[test]
+    fn it_should_handle_func_args() {
+        const TEST_STR: &'static str = "const add = fn(x: u64, y: u64) u64 { return x + y }
+        ";
+        let lexer = TLexer::new(TEST_STR);
+        let mut parser = Parser::new(lexer);
+        let (result, errors) = parser.all();
+        assert_eq!(errors, vec![]);
+        let mut tts = vec![];
+        let mut scps = vec![];
+        let mut linter = LintSource::new(TEST_STR, &mut scps, &mut tts);
+        let _ = linter.lint_check(&result);
+
+        assert!(linter.issues.len() == 0);
+    }
+    #[test]
+    fn it_should_handle_sigs() {
+        const TEST_STR: &'static str = "
+        const val: zerror!?usize = 2
+        const val2: !?usize = 3
+        const val3: ?usize = 4
         const val4: usize = 5
         ";
         let lexer = TLexer::new(TEST_STR);
         let mut parser = Parser::new(lexer);
-        let result = parser.all();
+        let (result, errors) = parser.all();
+        assert_eq!(errors, vec![]);
         let mut tts = vec![];
         let mut scps = vec![];
         let mut linter = LintSource::new(TEST_STR, &mut scps, &mut tts);
-        let _ = linter.lint_check(&result.unwrap());
+        let _ = linter.lint_check(&result);
 
         assert!(linter.issues.len() == 0);
     }
@@ -1319,12 +3426,922 @@ mod tests {
         ";
         let lexer = TLexer::new(TEST_STR);
         let mut parser = Parser::new(lexer);
-        let result = parser.all();
+        let (result, errors) = parser.all();
+        assert_eq!(errors, vec![]);
+        let mut tts = vec![];
+        let mut scps = vec![];
+        let mut linter = LintSource::new(TEST_STR, &mut scps, &mut tts);
+        let _ = linter.lint_check(&result);
+
+        assert!(linter.issues.len() == 1);
+        let issue = linter.issues.get(0).unwrap();
+        assert_eq!(issue.title, "mismatched types");
+        assert_eq!(issue.suggestions[0], "expected void, found usize");
+        assert_eq!(issue.points[0].line, 2);
+    }
+    #[test]
+    fn it_should_unify_literals_with_their_context() {
+        const TEST_STR: &'static str = "const small: u8 = 2
+            const wide = fn(x: u32, y: f64) f64 { return y * 2 }
+            const main = fn() u8 { return small + 1 }
+            const bad = fn() u8 { return wide(1, 2.5) }
+            const ratio: f64 = 3
+            const count: u32 = 1.5
+        ";
+        let lexer = TLexer::new(TEST_STR);
+        let mut parser = Parser::new(lexer);
+        let (result, errors) = parser.all();
+        assert_eq!(errors, vec![]);
+        let mut tts = vec![];
+        let mut scps = vec![];
+        let mut linter = LintSource::new(TEST_STR, &mut scps, &mut tts);
+        let _ = linter.lint_check(&result);
+
+        let found: Vec<&String> = linter.issues.iter().map(|x| &x.suggestions[0]).collect();
+        assert_eq!(
+            found,
+            vec!["expected u8, found f64", "expected u32, found {float}"]
+        );
+    }
+    #[test]
+    fn it_should_check_literal_ranges_and_widening() {
+        const TEST_STR: &'static str = "const x: u8 = 300
+            const y: i8 = -128
+            const z: i8 = -129
+            const w: u16 = 255
+            const v: u32 = w + y
+            const u: u8 = w
+            const t: i64 = w + 1
+        ";
+        let lexer = TLexer::new(TEST_STR);
+        let mut parser = Parser::new(lexer);
+        let (result, errors) = parser.all();
+        assert_eq!(errors, vec![]);
+        let mut tts = vec![];
+        let mut scps = vec![];
+        let mut linter = LintSource::new(TEST_STR, &mut scps, &mut tts);
+        let _ = linter.lint_check(&result);
+
+        let found: Vec<(&String, usize)> = linter
+            .issues
+            .iter()
+            .map(|x| (&x.title, x.points[0].line))
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                (&"literal out of range for u8".to_string(), 1),
+                (&"literal out of range for i8".to_string(), 3),
+                (&"mismatched types".to_string(), 5),
+                (&"mismatched types".to_string(), 6),
+            ]
+        );
+    }
+    #[test]
+    fn it_should_catch_use_after_move() {
+        const TEST_STR: &'static str = "const Point = struct { x: u64, y: u64 }
+            const take = fn(p: Point) u64 { return p.x }
+            const peek = fn(p: &Point) u64 { return p.y }
+            const main = fn() u64 {
+                const a = Point { x: 1, y: 2 }
+                const b = a
+                const c = a.x
+                const d = Point { x: 3, y: 4 }
+                const e = peek(d) + take(d)
+                const f = copy b
+                return d.y + e + b.x + f.x
+            }
+        ";
+        let lexer = TLexer::new(TEST_STR);
+        let mut parser = Parser::new(lexer);
+        let (result, errors) = parser.all();
+        assert_eq!(errors, vec![]);
+        let mut tts = vec![];
+        let mut scps = vec![];
+        let mut linter = LintSource::new(TEST_STR, &mut scps, &mut tts);
+        let _ = linter.lint_check(&result);
+
+        let found: Vec<(&String, usize)> = linter
+            .issues
+            .iter()
+            .map(|x| (&x.title, x.points[0].line))
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                (&"use of moved value a".to_string(), 7),
+                (&"use of moved value d".to_string(), 11),
+            ]
+        );
+        assert_eq!(
+            linter.issues[0].suggestions[0],
+            "a was moved on line 6, use copy a or borrow it with & instead"
+        );
+    }
+    #[test]
+    fn it_should_check_borrows() {
+        const TEST_STR: &'static str = "const Point = struct { x: u64, y: u64 }
+            const main = fn() u64 {
+                let a = Point { x: 1, y: 2 }
+                const r = *a
+                const s = *a
+                const t = &a
+                const b = Point { x: 3, y: 4 }
+                const u = &b
+                u.x = 5
+                a.y = 3
+                r.y = 4
+                return r.x + u.y
+            }
+            const both = fn(p: *Point, q: *Point) u64 {
+                return p.x + q.x
+            }
+            const twice = fn() u64 {
+                let k = Point { x: 1, y: 2 }
+                let j = Point { x: 3, y: 4 }
+                const once = both(*k, *j)
+                return once + both(*k, *k)
+            }
+        ";
+        let lexer = TLexer::new(TEST_STR);
+        let mut parser = Parser::new(lexer);
+        let (result, errors) = parser.all();
+        assert_eq!(errors, vec![]);
+        let mut tts = vec![];
+        let mut scps = vec![];
+        let mut linter = LintSource::new(TEST_STR, &mut scps, &mut tts);
+        let _ = linter.lint_check(&result);
+
+        let found: Vec<(&String, usize)> = linter
+            .issues
+            .iter()
+            .map(|x| (&x.title, x.points[0].line))
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                (&"cannot borrow a as mutable".to_string(), 5),
+                (&"cannot borrow a as read only".to_string(), 6),
+                (&"cannot assign through a & borrow".to_string(), 9),
+                (&"cannot assign to a while it is borrowed".to_string(), 10),
+                (&"cannot borrow k as mutable".to_string(), 21),
+            ]
+        );
+        assert_eq!(
+            linter.issues[0].suggestions[0],
+            "a is already borrowed as mutable with * on line 4"
+        );
+    }
+    #[test]
+    fn it_should_not_let_borrows_escape() {
+        const TEST_STR: &'static str = "const Point = struct { x: u64, y: u64 }
+            const leak = fn() &Point {
+                const a = Point { x: 1, y: 2 }
+                return &a
+            }
+            const pass = fn(p: &Point) &Point {
+                return p
+            }
+            const nested = fn() u64 {
+                const outer = Point { x: 1, y: 2 }
+                const other = Point { x: 5, y: 6 }
+                let r = &outer
+                if (outer.x > 0) {
+                    const inner = Point { x: 3, y: 4 }
+                    r = &inner
+                    r = &other
+                }
+                return r.x
+            }
+        ";
+        let lexer = TLexer::new(TEST_STR);
+        let mut parser = Parser::new(lexer);
+        let (result, errors) = parser.all();
+        assert_eq!(errors, vec![]);
+        let mut tts = vec![];
+        let mut scps = vec![];
+        let mut linter = LintSource::new(TEST_STR, &mut scps, &mut tts);
+        let _ = linter.lint_check(&result);
+
+        let found: Vec<(&String, usize)> = linter
+            .issues
+            .iter()
+            .map(|x| (&x.title, x.points[0].line))
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                (&"borrow of a escapes its scope".to_string(), 4),
+                (&"borrow of inner escapes its scope".to_string(), 15),
+            ]
+        );
+    }
+    #[test]
+    fn it_should_only_change_what_is_mutable() {
+        const TEST_STR: &'static str = "const Point = struct { x: u64, y: u64 }
+            const e: [const char] = \"Hello\"
+            const d: [char] = \"Hello\"
+            const bump = fn(p: &Point, n: u64, m: let u64) u64 {
+                n = 2
+                m += 1
+                return m + p.x
+            }
+            const main = fn() u64 {
+                const a = 1
+                let b = 2
+                a = 3
+                a += 1
+                b <<= 1
+                e[0] = 'h'
+                d[0] = 'h'
+                return b
+            }
+        ";
+        let lexer = TLexer::new(TEST_STR);
+        let mut parser = Parser::new(lexer);
+        let (result, errors) = parser.all();
+        assert_eq!(errors, vec![]);
+        let mut tts = vec![];
+        let mut scps = vec![];
+        let mut linter = LintSource::new(TEST_STR, &mut scps, &mut tts);
+        let _ = linter.lint_check(&result);
+
+        let found: Vec<(&String, usize)> = linter
+            .issues
+            .iter()
+            .map(|x| (&x.title, x.points[0].line))
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                (&"cannot assign to n, it is immutable".to_string(), 5),
+                (&"cannot assign to a, it is immutable".to_string(), 12),
+                (&"cannot use += on a, it is immutable".to_string(), 13),
+                (&"cannot assign to a const element of e".to_string(), 15),
+            ]
+        );
+        assert_eq!(
+            linter.issues[1].suggestions[0],
+            "a is declared with const, declare it with let to change it"
+        );
+    }
+    #[test]
+    fn it_should_walk_nested_scopes() {
+        const TEST_STR: &'static str = "const main = fn() u64 {
+                const a = 1
+                let total = a
+                if (total > 0) {
+                    const a = 2
+                    if (a > 1) {
+                        total = total + a
+                    }
+                }
+                const b = 5
+                const b = 6
+                return total + missing
+            }
+        ";
+        let lexer = TLexer::new(TEST_STR);
+        let mut parser = Parser::new(lexer);
+        let (result, errors) = parser.all();
+        assert_eq!(errors, vec![]);
+        let mut tts = vec![];
+        let mut scps = vec![];
+        let mut linter = LintSource::new(TEST_STR, &mut scps, &mut tts);
+        let _ = linter.lint_check(&result);
+
+        let found: Vec<(&String, usize)> = linter
+            .issues
+            .iter()
+            .map(|x| (&x.title, x.points[0].line))
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                (&"b is already declared in this scope".to_string(), 11),
+                (&"cannot find missing in this scope".to_string(), 12),
+            ]
+        );
+        let redeclared = &linter.issues[0];
+        assert_eq!(redeclared.id, "E0102");
+        let notes: Vec<(&str, usize)> = redeclared
+            .notes
+            .iter()
+            .map(|(x, note)| (note.as_str(), x.line))
+            .collect();
+        assert_eq!(notes, vec![("previously declared here", 10)]);
+        let warned: Vec<(&String, usize)> = linter
+            .warnings
+            .iter()
+            .map(|x| (&x.title, x.points[0].line))
+            .collect();
+        assert_eq!(warned, vec![(&"unused variable b".to_string(), 10)]);
+        assert_eq!(linter.warnings[0].severity, Severity::Warning);
+    }
+    #[test]
+    fn it_should_check_match_coverage() {
+        const TEST_STR: &'static str = "const Day = enum
+          | monday
+          | tuesday
+          | sunday
+        const Oops = error
+          | lost
+          | late
+        const a = fn(d: Day) usize {
+            return match (d) {
+                Day.monday => 1
+                Day.tuesday => 2
+            }
+        }
+        const b = fn(d: Day) usize {
+            return match (d) {
+                Day.monday => 1
+                Day.monday => 2
+                _ => 3
+                Day.sunday => 4
+            }
+        }
+        const c = fn(x: usize) usize {
+            return match (x) {
+                1 => 1
+                2 => 2
+            }
+        }
+        const e = fn(o: Oops) usize {
+            return match (o) {
+                Oops.lost => 1
+                Day.friday => 2
+            }
+        }
+        ";
+        let lexer = TLexer::new(TEST_STR);
+        let mut parser = Parser::new(lexer);
+        let (result, errors) = parser.all();
+        assert_eq!(errors, vec![]);
+        let mut tts = vec![];
+        let mut scps = vec![];
+        let mut linter = LintSource::new(TEST_STR, &mut scps, &mut tts);
+        let _ = linter.lint_check(&result);
+
+        let found: Vec<(&str, &str, usize)> = linter
+            .issues
+            .iter()
+            .map(|x| (x.id, x.title.as_str(), x.points[0].line))
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                ("E0106", "Day.sunday not covered", 9),
+                ("E0106", "_ not covered", 23),
+                ("E0103", "unknown variant friday", 31),
+                ("E0106", "Oops.late not covered", 29),
+            ]
+        );
+        let warned: Vec<(&str, usize)> = linter
+            .warnings
+            .iter()
+            .map(|x| (x.suggestions[0].as_str(), x.points[0].line))
+            .collect();
+        assert_eq!(
+            warned,
+            vec![
+                ("Day.monday is already matched above", 17),
+                ("everything is already matched by the arms above", 19),
+            ]
+        );
+    }
+    #[test]
+    fn it_should_number_enum_variants() {
+        const TEST_STR: &'static str = "const Day = enum(u8)
+          | monday = 1
+          | tuesday
+          | sunday = 7
+        const Big = enum(u8)
+          | low = 254
+          | high
+          | over
+        const Twice = enum
+          | a = 2
+          | b = 1
+          | c
+        const Odd = enum(f64)
+          | a = 1.5
+        const Listed = enum
+          | a = [1, 2]
+        ";
+        let lexer = TLexer::new(TEST_STR);
+        let mut parser = Parser::new(lexer);
+        let (result, errors) = parser.all();
+        assert_eq!(errors, vec![]);
+        let mut tts = vec![];
+        let mut scps = vec![];
+        let mut linter = LintSource::new(TEST_STR, &mut scps, &mut tts);
+        let _ = linter.lint_check(&result);
+
+        let found: Vec<(&str, &str, usize)> = linter
+            .issues
+            .iter()
+            .map(|x| (x.id, x.title.as_str(), x.points[0].line))
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                ("E0203", "over is out of range for u8", 8),
+                ("E0107", "a and c are both 2", 12),
+                ("E0108", "f64 cannot back an enum", 13),
+                ("E0109", "enum values must be integer literals", 14),
+                ("E0109", "enum values must be integer literals", 16),
+            ]
+        );
+        assert_eq!(linter.issues[1].notes.len(), 1);
+        let values: Vec<Vec<i128>> = linter
+            .ttbls
+            .iter()
+            .filter_map(|x| match x {
+                TypeTree::EnumInfo(x) => Some(x.values.clone()),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(
+            values,
+            vec![
+                vec![1, 2, 7],
+                vec![254, 255, 256],
+                vec![2, 1, 2],
+                vec![0],
+                vec![0]
+            ]
+        );
+    }
+    #[test]
+    fn it_should_check_tag_payloads() {
+        const TEST_STR: &'static str = "const Shape = tag
+          | circle: u64
+          | none
+        const a = fn() Shape {
+            return Shape.circle
+        }
+        const b = fn() Shape {
+            return Shape.none(1)
+        }
+        const c = fn() Shape {
+            return Shape.circle(true)
+        }
+        const d = fn(s: Shape) u64 {
+            return match (s) {
+                Shape.circle(r) => r
+                Shape.none => r
+            }
+        }
+        const e = fn(s: Shape) u64 {
+            return match (s) {
+                Shape.circle(1) => 1
+                _ => 0
+            }
+        }
+        const f = fn(s: Shape) u64 {
+            return match (s) {
+                Shape.circle(r) => r
+                Shape.circle(_) => 0
+            }
+        }
+        const g = fn() Shape {
+            return Shape.circle([1, 2])
+        }
+        const h = fn(s: Shape) u64 {
+            return match (s) {
+                Shape.circle([1]) => 1
+                _ => 0
+            }
+        }
+        ";
+        let lexer = TLexer::new(TEST_STR);
+        let mut parser = Parser::new(lexer);
+        let (result, errors) = parser.all();
+        assert_eq!(errors, vec![]);
+        let mut tts = vec![];
+        let mut scps = vec![];
+        let mut linter = LintSource::new(TEST_STR, &mut scps, &mut tts);
+        let _ = linter.lint_check(&result);
+
+        let found: Vec<(&str, &str, usize)> = linter
+            .issues
+            .iter()
+            .map(|x| (x.id, x.title.as_str(), x.points[0].line))
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                ("E0104", "wrong number of payloads to Shape.circle", 5),
+                ("E0104", "wrong number of payloads to Shape.none", 8),
+                ("E0200", "mismatched types", 11),
+                ("E0101", "cannot find r in this scope", 16),
+                ("E0106", "Shape.none not covered", 14),
+                ("E0110", "expected a name for the payload", 21),
+                ("E0106", "Shape.none not covered", 26),
+                ("E0200", "mismatched types", 32),
+                ("E0110", "expected a name for the payload", 36),
+            ]
+        );
+        let sizes: Vec<Vec<Ty>> = linter
+            .ttbls
+            .iter()
+            .filter_map(|x| match x {
+                TypeTree::TagInfo(x) => Some(x.types.clone()),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(sizes, vec![vec![Ty::U64, Ty::Void]]);
+    }
+    #[test]
+    fn it_should_check_try_and_catch() {
+        const TEST_STR: &'static str = "type NotFound = error
+        type Closed = error
+        const find = fn() NotFound!u64 {
+            return NotFound
+        }
+        const a = fn(x: u64) NotFound!u64 {
+            return try x
+        }
+        const b = fn() u64 {
+            return try find()
+        }
+        const c = fn() Closed!u64 {
+            return try find()
+        }
+        const d = fn() u64 {
+            return find() catch (e) {
+                return true
+            }
+        }
+        const e = fn() NotFound!u64 {
+            const x = try find()
+            return find() catch (e) {
+                return x
+            }
+        }
+        const f = fn() NotFound!u64 {
+            return try [1, 2]
+        }
+        const g = fn() u64 {
+            return find() catch () bool {
+                return true
+            }
+        }
+        ";
+        let lexer = TLexer::new(TEST_STR);
+        let mut parser = Parser::new(lexer);
+        let (result, errors) = parser.all();
+        assert_eq!(errors, vec![]);
+        let mut tts = vec![];
+        let mut scps = vec![];
+        let mut linter = LintSource::new(TEST_STR, &mut scps, &mut tts);
+        let _ = linter.lint_check(&result);
+
+        let found: Vec<(&str, &str, usize)> = linter
+            .issues
+            .iter()
+            .map(|x| (x.id, x.title.as_str(), x.points[0].line))
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                ("E0111", "u64 is never an error", 7),
+                ("E0112", "try outside of a function returning an error", 10),
+                ("E0200", "mismatched types", 13),
+                ("E0200", "mismatched types", 17),
+                ("E0111", "[{integer}] is never an error", 27),
+                ("E0200", "mismatched types", 30),
+            ]
+        );
+    }
+    #[test]
+    fn it_should_check_optionals() {
+        const TEST_STR: &'static str = "type Lexer = struct {
+          current: ?u64,
+          idx: u64,
+        }
+        const a = fn() u64 {
+            const x: u64 = undefined
+            const y = undefined
+            return 0
+        }
+        const b = fn(x: ?u64) u64 {
+            return x + 1
+        }
+        const c = fn(x: ?u64) u64 {
+            if (x) {
+                return x + 1
+            }
+            return 0
+        }
+        const d = fn(x: ?u64) ?u64 {
+            const y = x?
+            return y + 1
+        }
+        const e = fn(x: u64) ?u64 {
+            return x?
+        }
+        const f = fn(x: ?u64) u64 {
+            return x?
+        }
+        const i = fn() ?u64 {
+            const y = [1, 2]?
+            return undefined
+        }
+        const g = fn(self: Lexer) u64 {
+            if (self.current) {
+                return self.current
+            }
+            return self.current
+        }
+        const h = fn() ?u64 {
+            let x: ?u64 = 1
+            if (x) {
+                x = undefined
+                return x + 1
+            }
+            return undefined
+        }
+        ";
+        let lexer = TLexer::new(TEST_STR);
+        let mut parser = Parser::new(lexer);
+        let (result, errors) = parser.all();
+        assert_eq!(errors, vec![]);
+        let mut tts = vec![];
+        let mut scps = vec![];
+        let mut linter = LintSource::new(TEST_STR, &mut scps, &mut tts);
+        let _ = linter.lint_check(&result);
+
+        let found: Vec<(&str, &str, usize)> = linter
+            .issues
+            .iter()
+            .map(|x| (x.id, x.title.as_str(), x.points[0].line))
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                ("E0113", "u64 cannot be undefined", 6),
+                ("E0113", "undefined needs an optional type", 7),
+                ("E0114", "value may be undefined", 11),
+                ("E0115", "u64 is never undefined", 24),
+                ("E0116", "? outside of a function returning an optional", 27),
+                ("E0115", "[{integer}] is never undefined", 30),
+                ("E0114", "value may be undefined", 37),
+                ("E0114", "value may be undefined", 43),
+            ]
+        );
+    }
+    #[test]
+    fn it_should_check_global_values() {
+        const TEST_STR: &'static str = "const a: u64 = 2
+        const b: u64 = a * 3 + 1
+        const both = [a, b + 1]
+        let y: u64 = 3
+        const x: u64 = y
+        const e = '€'
+        const f = fn() u64 {
+            return 1
+        }
+        const z = f()
+        ";
+        let lexer = TLexer::new(TEST_STR);
+        let mut parser = Parser::new(lexer);
+        let (result, errors) = parser.all();
+        assert_eq!(errors, vec![]);
+        let mut tts = vec![];
+        let mut scps = vec![];
+        let mut linter = LintSource::new(TEST_STR, &mut scps, &mut tts);
+        let _ = linter.lint_check(&result);
+
+        let found: Vec<(&str, &str, usize)> = linter
+            .issues
+            .iter()
+            .map(|x| (x.id, x.suggestions[0].as_str(), x.points[0].line))
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                (
+                    "E0117",
+                    "y is not a constant, only const globals can be used",
+                    5
+                ),
+                ("E0117", "'€' does not fit in a byte", 6),
+                (
+                    "E0117",
+                    "use literals, other constants, and + - * on them",
+                    10
+                ),
+            ]
+        );
+    }
+    #[test]
+    fn it_should_need_an_else_for_if_values() {
+        const TEST_STR: &'static str = "const f = fn(x: u64) u64 {
+            let y = 0
+            if (x > 1) {
+                y = 1
+            } else if (x > 2) {
+                y = 2
+            }
+            const z = if (x > 2) {
+                break 10
+            }
+            const w = if (x > 3) {
+                break 10
+            } else if (x > 4) {
+                break 20
+            }
+            return y
+        }
+        ";
+        let lexer = TLexer::new(TEST_STR);
+        let mut parser = Parser::new(lexer);
+        let (result, errors) = parser.all();
+        assert_eq!(errors, vec![]);
+        let mut tts = vec![];
+        let mut scps = vec![];
+        let mut linter = LintSource::new(TEST_STR, &mut scps, &mut tts);
+        let _ = linter.lint_check(&result);
+
+        let found: Vec<(&str, &str, usize)> = linter
+            .issues
+            .iter()
+            .map(|x| (x.id, x.title.as_str(), x.points[0].line))
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                ("E0118", "if used as a value without an else", 8),
+                ("E0118", "if used as a value without an else", 13),
+            ]
+        );
+    }
+    #[test]
+    fn it_should_declare_bindings_with_bad_values() {
+        const TEST_STR: &'static str = "let y: u64 = 3
+        const g: u64 = y
+        const f = fn(x: u64) u64 {
+            const z = if (x > 2) {
+                break 10
+            }
+            const w = nope + 1
+            return z + w + g
+        }
+        ";
+        let lexer = TLexer::new(TEST_STR);
+        let mut parser = Parser::new(lexer);
+        let (result, errors) = parser.all();
+        assert_eq!(errors, vec![]);
+        let mut tts = vec![];
+        let mut scps = vec![];
+        let mut linter = LintSource::new(TEST_STR, &mut scps, &mut tts);
+        let _ = linter.lint_check(&result);
+
+        let found: Vec<(&str, &str, usize)> = linter
+            .issues
+            .iter()
+            .map(|x| (x.id, x.title.as_str(), x.points[0].line))
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                ("E0117", "global values must be known at compile time", 2),
+                ("E0118", "if used as a value without an else", 4),
+                ("E0101", "cannot find nope in this scope", 7),
+            ]
+        );
+    }
+    #[test]
+    fn it_should_only_loop_over_arrays_and_ranges() {
+        const TEST_STR: &'static str = "const f = fn(x: u64, xs: [u64]) u64 {
+            let total = 0
+            for (xs) fn(v) void {
+                total += v
+            }
+            for (0..x) fn(i) void {
+                total += i
+            }
+            for (\"ab\") fn(c) void {
+                total += 1
+            }
+            for (x) fn(i) void {
+                total += 1
+            }
+            return total
+        }
+        ";
+        let lexer = TLexer::new(TEST_STR);
+        let mut parser = Parser::new(lexer);
+        let (result, errors) = parser.all();
+        assert_eq!(errors, vec![]);
+        let mut tts = vec![];
+        let mut scps = vec![];
+        let mut linter = LintSource::new(TEST_STR, &mut scps, &mut tts);
+        let _ = linter.lint_check(&result);
+
+        let found: Vec<(&str, &str, usize)> = linter
+            .issues
+            .iter()
+            .map(|x| (x.id, x.suggestions[0].as_str(), x.points[0].line))
+            .collect();
+        assert_eq!(
+            found,
+            vec![(
+                "E0205",
+                "u64 cannot be looped over, use a range like 0..x",
+                12
+            )]
+        );
+    }
+    #[test]
+    fn it_should_report_what_is_not_supported() {
+        const TEST_STR: &'static str = "const Speak = trait
+            const half = fn(x: u64) u64 {
+                return x / 2
+            }
+            const small = fn(x: u64) bool {
+                return x < 2
+            }
+            const main = fn() u64 {
+                return half(4)
+            }
+            const bits = fn() f64 {
+                let f: f64 = 1.5
+                f %= 2.0
+                let x: u64 = 1
+                x ~= 2
+                return f
+            }
+        ";
+        let lexer = TLexer::new(TEST_STR);
+        let mut parser = Parser::new(lexer);
+        let (result, errors) = parser.all();
+        assert_eq!(errors, vec![]);
+        let mut tts = vec![];
+        let mut scps = vec![];
+        let mut linter = LintSource::new(TEST_STR, &mut scps, &mut tts);
+        let _ = linter.lint_check(&result);
+
+        let found: Vec<(&String, usize, usize)> = linter
+            .issues
+            .iter()
+            .map(|x| (&x.title, x.points[0].line, x.points[0].col))
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                (&"not yet supported: trait declarations".to_string(), 1, 7),
+                (&"not yet supported: the / operator".to_string(), 3, 26),
+                (&"not yet supported: the < operator".to_string(), 6, 26),
+                (&"not yet supported: the %= operator".to_string(), 13, 19),
+                (&"not yet supported: the ~= operator".to_string(), 15, 19),
+            ]
+        );
+    }
+    #[test]
+    fn it_should_type_arrays_and_chars() {
+        const TEST_STR: &'static str = "const d: [char] = \"Hello\\tThere\"
+            const first = fn(x: [char]) bool { return x[0] == 'H' }
+        ";
+        let lexer = TLexer::new(TEST_STR);
+        let mut parser = Parser::new(lexer);
+        let (result, errors) = parser.all();
+        assert_eq!(errors, vec![]);
+        let mut tts = vec![];
+        let mut scps = vec![];
+        let mut linter = LintSource::new(TEST_STR, &mut scps, &mut tts);
+        let _ = linter.lint_check(&result);
+
+        assert!(linter.issues.len() == 0);
+        let access = tts.iter().find_map(|x| match x {
+            TypeTree::ArrayAccess(x) => Some(x.curried.clone()),
+            _ => None,
+        });
+        assert_eq!(access, Some(Ty::Char));
+        let string = tts.iter().find_map(|x| match x {
+            TypeTree::StringInit(x) => Some(x.vals.len()),
+            _ => None,
+        });
+        assert_eq!(string, Some(11));
+        assert!(tts.iter().any(|x| matches!(x, TypeTree::Char('H'))));
+    }
+    #[test]
+    fn it_should_handle_later_functions() {
+        const TEST_STR: &'static str = "const main = fn() usize { return later(2) }
+            const later = fn(x: usize) usize { return x }
+        ";
+        let lexer = TLexer::new(TEST_STR);
+        let mut parser = Parser::new(lexer);
+        let (result, errors) = parser.all();
+        assert_eq!(errors, vec![]);
         let mut tts = vec![];
         let mut scps = vec![];
         let mut linter = LintSource::new(TEST_STR, &mut scps, &mut tts);
-        let _ = linter.lint_check(&result.unwrap());
+        let tops = linter.lint_check(&result);
 
         assert!(linter.issues.len() == 0);
+        assert!(tops.len() == 2);
     }
 }
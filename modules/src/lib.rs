@@ -0,0 +1,183 @@
+use ast::Expr;
+use codelocation::SourceMap;
+use lexer::TLexer;
+use linter::{LintSource, LintedModule, ModuleTable};
+use parser::Parser;
+use perror::*;
+use std::fs::read_to_string;
+use std::path::{Path, PathBuf};
+
+// Loads modules by import path, relative to a root directory.
+// `import "a.b.symbol"` is the pub symbol `symbol` in a/b.ty.
+// every module is linted once, after the modules it imports.
+pub struct ModuleLoader {
+    root: PathBuf,
+    pub modules: ModuleTable,
+    // modules currently being loaded, to catch import cycles
+    loading: Vec<String>,
+    // every file read, so diagnostics can name where they came from
+    pub sources: SourceMap,
+}
+
+impl ModuleLoader {
+    pub fn new(root: &Path) -> Self {
+        ModuleLoader {
+            root: root.to_path_buf(),
+            modules: ModuleTable::new(),
+            loading: vec![],
+            sources: SourceMap::new(),
+        }
+    }
+    pub fn path_of(&self, module: &str) -> PathBuf {
+        let mut path = self.root.clone();
+        module.split('.').for_each(|x| path.push(x));
+        path.set_extension("ty");
+        path
+    }
+    pub fn load(&mut self, module: &str) -> ResultModule<&LintedModule> {
+        self.check_cycle(module)?;
+        if !self.modules.contains_key(module) {
+            let path = self.path_of(module);
+            let contents = match read_to_string(&path) {
+                Ok(x) => x,
+                Err(_) => {
+                    return Err(ModuleError::new(format!(
+                        "unable to read module {} at {}",
+                        module,
+                        path.display()
+                    )))
+                }
+            };
+            self.load_buffer(module, &contents)?;
+        }
+        Ok(self.modules.get(module).unwrap())
+    }
+    pub fn load_buffer(&mut self, module: &str, contents: &str) -> ResultModule<&LintedModule> {
+        self.check_cycle(module)?;
+        let id = self
+            .sources
+            .add(&self.path_of(module).display().to_string(), contents);
+        let file = self.sources.get(id).unwrap().clone();
+        let lex = TLexer::new(contents);
+        let mut parse = Parser::new(lex).with_file(&file);
+        // what did parse is still linted, so every error in the file is found at once
+        let (ast_parsed, parse_errors) = parse.all();
+        self.loading.push(module.to_string());
+        for x in imports_of(&ast_parsed) {
+            if let Err(x) = self.load(&x) {
+                self.loading.pop();
+                return Err(x);
+            }
+        }
+        self.loading.pop();
+
+        let mut types = vec![];
+        let mut scopes = vec![];
+        let mut linter = LintSource::new(contents, &mut scopes, &mut types)
+            .with_file(&file)
+            .with_modules(&self.modules);
+        let top = linter.lint_check(&ast_parsed);
+        let issues = linter.issues;
+        let warnings = linter.warnings;
+        let pub_scope = linter.pub_scope;
+        self.modules.insert(
+            module.to_string(),
+            LintedModule {
+                scopes,
+                types,
+                top,
+                pub_scope,
+                parse_errors,
+                issues,
+                warnings,
+            },
+        );
+        Ok(self.modules.get(module).unwrap())
+    }
+    fn check_cycle(&self, module: &str) -> ResultModule<()> {
+        if self.loading.iter().any(|x| x == module) {
+            return Err(ModuleError::new(format!(
+                "import cycle: {} -> {}",
+                self.loading.join(" -> "),
+                module
+            )));
+        }
+        Ok(())
+    }
+}
+
+// the modules a file imports from, `a.b.symbol` is from module `a.b`
+fn imports_of(file: &Expr) -> Vec<String> {
+    let all = file.into_file_all();
+    all.top_decls
+        .iter()
+        .filter_map(|x| match x.as_ref() {
+            Expr::Import(import) => {
+                let slice = import.expr.into_chars_value().val.slice;
+                let trimmed = &slice[1..slice.len() - 1];
+                trimmed.rsplit_once('.').map(|x| x.0.to_string())
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    fn it_should_catch_import_cycles() {
+        let mut loader = ModuleLoader::new(Path::new("."));
+        let result = loader.load_buffer("a", "const x = import \"a.y\"");
+        assert_eq!(
+            result.unwrap_err(),
+            ModuleError::new("import cycle: a -> a".to_string())
+        );
+    }
+    #[test]
+    fn it_should_expose_only_pub_items() {
+        let mut loader = ModuleLoader::new(Path::new("."));
+        let _ = loader
+            .load_buffer(
+                "lib",
+                "pub const add = fn(x: u64) u64 { return x }
+                const sub = fn(x: u64) u64 { return x }",
+            )
+            .unwrap();
+        let lib = loader.modules.get("lib").unwrap();
+        assert!(lib.pub_scope.this_tree.contains_key("add"));
+        assert!(!lib.pub_scope.this_tree.contains_key("sub"));
+        let main = loader
+            .load_buffer("main", "const sub = import \"lib.sub\"")
+            .unwrap();
+        assert_eq!(main.issues.len(), 1);
+        assert_eq!(main.issues[0].title, "sub is not a pub item of lib");
+    }
+    #[test]
+    fn it_should_keep_imported_structs_apart() {
+        let mut loader = ModuleLoader::new(Path::new("."));
+        let _ = loader
+            .load_buffer(
+                "shapes",
+                "pub const Point = struct { x: u64 }
+                pub const sum = fn(p: Point) u64 { return p.x }",
+            )
+            .unwrap();
+        let main = loader
+            .load_buffer(
+                "main",
+                "const Shape = import \"shapes.Point\"
+                const sum = import \"shapes.sum\"
+                const Point = struct { x: u64 }
+                const f = fn() u64 {
+                    const mine = Point { x: 1 }
+                    const theirs = Shape { x: 2 }
+                    return sum(theirs) + sum(mine)
+                }",
+            )
+            .unwrap();
+        let titles: Vec<&str> = main.issues.iter().map(|x| x.title.as_str()).collect();
+        assert_eq!(titles, vec!["mismatched types"]);
+        assert!(main.scopes[0].this_tree.contains_key("shapes.Point"));
+    }
+}
@@ -1,45 +1,148 @@
-use lexer::TLexer;
-use linter::LintSource;
-use parser::Parser;
+use modules::ModuleLoader;
+use perror::{Diagnostic, FirError, ModuleError, Severity};
 use scir::Scir;
 use std::fs::create_dir;
+use std::fs::read_to_string;
 use std::fs::write;
-use std::fs::File;
-use std::io::Read;
+use std::io::{stdout, IsTerminal};
 use std::path::Path;
 use std::path::PathBuf;
+use std::process::exit;
 
 pub fn from_buffer(contents: &str, path: &Path) -> () {
-    let lex = TLexer::new(&contents);
-    let mut parse = Parser::new(lex);
-    let ast_parsed = parse.all().unwrap();
-    let mut type_tables = vec![];
-    let mut scopes = vec![];
-    let mut linter = LintSource::new(path.to_str().unwrap(), &mut scopes, &mut type_tables);
-    let lint_res = linter.lint_check(&ast_parsed);
-    if linter.issues.len() > 0 {
-        for x in linter.issues {
-            println!("{}", x);
+    let root = root_of(&[path]);
+    let mut loader = ModuleLoader::new(loader_root(root));
+    let name = module_of(root, path);
+    if let Err(x) = loader.load_buffer(&name, contents) {
+        module_failed(&x);
+    }
+    check_issues(&loader);
+    make_object(&mut loader, &name);
+}
+
+pub fn from_file(input_path: &PathBuf) -> () {
+    let contents = read_to_string(input_path).unwrap();
+    from_buffer(&contents, input_path);
+}
+
+// files given together share a loader, so a module they import is only linted once
+pub fn from_files(input_paths: Vec<&PathBuf>) -> () {
+    let paths: Vec<&Path> = input_paths.iter().map(|x| x.as_path()).collect();
+    let root = root_of(&paths);
+    let mut loader = ModuleLoader::new(loader_root(root));
+    let names: Vec<String> = paths.iter().map(|x| module_of(root, x)).collect();
+    for x in names.iter() {
+        if let Err(x) = loader.load(x) {
+            module_failed(&x);
         }
-        panic!("linter issues exist");
     }
+    check_issues(&loader);
+    for x in names.iter() {
+        make_object(&mut loader, x);
+    }
+}
+
+// the deepest directory all of the files are in, modules are named by their path from it
+fn root_of<'a>(paths: &[&'a Path]) -> &'a Path {
+    let mut root = paths
+        .first()
+        .and_then(|x| x.parent())
+        .unwrap_or(Path::new(""));
+    while !paths.iter().all(|x| x.starts_with(root)) {
+        root = root.parent().unwrap_or(Path::new(""));
+    }
+    root
+}
+
+fn loader_root(root: &Path) -> &Path {
+    match root == Path::new("") {
+        true => Path::new("."),
+        false => root,
+    }
+}
+
+// a/b.ty is the module a.b, the one `import "a.b.symbol"` reads
+fn module_of(root: &Path, path: &Path) -> String {
+    let relative = path.strip_prefix(root).unwrap_or(path).with_extension("");
+    relative
+        .iter()
+        .map(|x| x.to_str().unwrap())
+        .collect::<Vec<_>>()
+        .join(".")
+}
+
+// colors only go to a terminal, and can be turned off with NO_COLOR
+fn color() -> bool {
+    stdout().is_terminal() && std::env::var_os("NO_COLOR").is_none()
+}
+
+// a missing module or an import cycle stops everything, there is nothing left to compile
+fn module_failed(err: &ModuleError) -> ! {
+    println!("{}", Diagnostic::from(err).render(color()));
+    exit(1);
+}
+
+// a function that cannot be lowered leaves the object without it, so nothing is written
+fn function_failed(err: &FirError) -> ! {
+    println!("{}", Diagnostic::from(err).render(color()));
+    exit(1);
+}
+
+// every issue is printed before stopping, so they can all be fixed at once
+fn check_issues(loader: &ModuleLoader) -> () {
+    let mut errors = 0;
+    let mut warnings = 0;
+    let color = color();
+    for x in loader.modules.values() {
+        for warning in x.warnings.iter() {
+            println!("{}", Diagnostic::from(warning).render(color));
+            warnings += 1;
+        }
+        for error in x.parse_errors.iter() {
+            println!("{}", Diagnostic::from(error).render(color));
+            errors += 1;
+        }
+        for issue in x.issues.iter() {
+            println!("{}", Diagnostic::from(issue).render(color));
+            errors += 1;
+        }
+    }
+    if errors > 0 {
+        let summary = Diagnostic {
+            severity: Severity::Error,
+            id: "",
+            title: format!(
+                "could not compile, {} and {}",
+                plural(errors, "error"),
+                plural(warnings, "warning")
+            ),
+            labels: vec![],
+            helps: vec![],
+        };
+        println!("{}", summary.render(color));
+        exit(1);
+    }
+}
+
+fn plural(count: usize, what: &str) -> String {
+    match count {
+        1 => format!("1 {}", what),
+        x => format!("{} {}s", x, what),
+    }
+}
+
+fn make_object(loader: &mut ModuleLoader, name: &str) -> () {
     if !Path::new(".ty").is_dir() {
         create_dir(".ty").unwrap();
     }
-    let wo_extension = path.with_extension("");
-    let filename = wo_extension.file_name().unwrap().to_str().unwrap();
+    // a.b keeps its dots, setting the extension would replace .b
     let mut output = PathBuf::new();
     output.push(".ty");
-    output.push(filename);
-    output.set_extension("o");
-    let mut scir = Scir::new(filename, scopes, type_tables);
-    scir.loopf(lint_res);
+    output.push(format!("{}.o", name));
+    let module = loader.modules.remove(name).unwrap();
+    let mut scir = Scir::new(name, module.scopes, module.types);
+    if let Err(x) = scir.loopf(module.top) {
+        function_failed(&x);
+    }
     write(output, scir.flush_self()).unwrap();
 }
-
-pub fn from_file(input_path: &PathBuf) -> () {
-    let mut file = File::open(input_path.clone()).unwrap();
-    let mut contents = String::new();
-    file.read_to_string(&mut contents).unwrap();
-    from_buffer(&contents, input_path);
-}
@@ -1,17 +1,22 @@
 use cranelift_codegen::ir::Function;
+use cranelift_codegen::ir::Signature;
 use cranelift_codegen::settings::*;
 use cranelift_codegen::Context;
-use cranelift_module::{DataDescription, Linkage, Module};
+use cranelift_module::{DataDescription, DataId, FuncId, Linkage, Module};
 use cranelift_object::{ObjectBuilder, ObjectModule};
 use datatable::DataTable;
-use types::TopInitialization;
-use types::TypeTree;
-use types::TypeTreeIndex;
+use layout::{elem_of, layout_of, prop_of, struct_info};
+use scopetable::ScopeTable;
+use std::collections::BTreeMap;
+use types::*;
 
 // Object intermediate representation
 pub struct Oir {
     pub obj_mod: ObjectModule,
-    pub data: DataDescription,
+    // every function is declared before any are defined, so calls can be resolved in any order
+    pub ftable: BTreeMap<String, FuncId>,
+    // the module being compiled, pub symbols are named by it
+    pub module: String,
 }
 
 impl Oir {
@@ -26,42 +31,234 @@ impl Oir {
             ObjectBuilder::new(isa, obj_name, cranelift_module::default_libcall_names()).unwrap();
         Oir {
             obj_mod: ObjectModule::new(obj_builder),
-            data: DataDescription::new(),
+            ftable: BTreeMap::new(),
+            module: obj_name.to_string(),
         }
     }
-    pub fn recurse(&mut self, idx: TypeTreeIndex, types: &Vec<TypeTree>) -> () {
+    // the bytes of a global, written at offset `at` within `out`. arrays and strings that are held
+    // within a struct become their own data object, with the struct holding the address and length
+    fn emit(
+        &mut self,
+        idx: TypeTreeIndex,
+        ty: &Ty,
+        at: usize,
+        out: &mut GlobalBytes,
+        scopes: &Vec<ScopeTable>,
+        types: &Vec<TypeTree>,
+    ) -> Result<(), String> {
         let expr = types.get(idx as usize).unwrap();
-        match expr {
-            TypeTree::I64(x) => self.data.define(Box::from(x.clone().to_ne_bytes())),
-            TypeTree::U64(x) => self.data.define(Box::from(x.clone().to_ne_bytes())),
-            _ => panic!("unexpected type tree in oir"),
+        match (peel(ty), expr) {
+            (Ty::Custom(_), TypeTree::StructInit(init)) => {
+                for x in init.idents.iter() {
+                    let prop = match types.get(*x as usize).unwrap() {
+                        TypeTree::PropInit(prop) => prop,
+                        _ => panic!("expected property in struct"),
+                    };
+                    let ident = &types
+                        .get(prop.left as usize)
+                        .unwrap()
+                        .into_symbol_init()
+                        .ident;
+                    let (offset, prop_ty) = prop_of(ty, ident, scopes, types).unwrap();
+                    self.emit(
+                        prop.right,
+                        &prop_ty,
+                        at + offset as usize,
+                        out,
+                        scopes,
+                        types,
+                    )?;
+                }
+            }
+            (Ty::Array(_) | Ty::String, TypeTree::StringInit(_) | TypeTree::ArrayInit(_)) => {
+                let writable = writable_within(ty, scopes, types);
+                let (id, len) = self.array_data(idx, ty, writable, scopes, types)?;
+                out.relocs.push((at as u32, id));
+                out.bytes[at + 8..at + 16].copy_from_slice(&(len as u64).to_ne_bytes());
+            }
+            (Ty::F64 | Ty::FloatLiteral, _) => {
+                let val = match fold(idx, scopes, types)? {
+                    Folded::Int(x) => x as f64,
+                    Folded::Float(x) => x,
+                };
+                out.bytes[at..at + 8].copy_from_slice(&val.to_ne_bytes());
+            }
+            (Ty::F32, _) => {
+                let val = match fold(idx, scopes, types)? {
+                    Folded::Int(x) => x as f32,
+                    Folded::Float(x) => x as f32,
+                };
+                out.bytes[at..at + 4].copy_from_slice(&val.to_ne_bytes());
+            }
+            (_, _) => {
+                let size = layout_of(ty, scopes, types).size as usize;
+                let val = match fold(idx, scopes, types)? {
+                    Folded::Int(x) => x as i128,
+                    Folded::Float(x) => x as i128,
+                };
+                out.bytes[at..at + size].copy_from_slice(&val.to_ne_bytes()[..size]);
+            }
+        }
+        Ok(())
+    }
+    // the elements of an array or string laid out back to back, with the number of elements.
+    // strings are zero terminated so they can be handed to c as is, the terminator is not counted
+    fn array_bytes(
+        &mut self,
+        idx: TypeTreeIndex,
+        ty: &Ty,
+        scopes: &Vec<ScopeTable>,
+        types: &Vec<TypeTree>,
+    ) -> Result<(GlobalBytes, u32, usize), String> {
+        match types.get(idx as usize).unwrap() {
+            TypeTree::StringInit(init) => {
+                let text: String = init
+                    .vals
+                    .iter()
+                    .filter_map(|x| match types.get(*x as usize) {
+                        Some(TypeTree::Char(c)) => Some(*c),
+                        _ => None,
+                    })
+                    .collect();
+                let mut bytes = text.as_bytes().to_vec();
+                bytes.push(0);
+                Ok((GlobalBytes::new(bytes), 1, text.len()))
+            }
+            TypeTree::ArrayInit(init) => {
+                let elem = elem_of(ty)
+                    .or(init.vals_curried.first().cloned())
+                    .unwrap_or(Ty::Unknown);
+                let layout = layout_of(&elem, scopes, types);
+                let size = layout.size as usize;
+                let mut out = GlobalBytes::new(vec![0; size * init.vals.len()]);
+                for (i, x) in init.vals.iter().enumerate() {
+                    self.emit(*x, &elem, i * size, &mut out, scopes, types)?;
+                }
+                Ok((out, layout.align, init.vals.len()))
+            }
+            _ => Err("arrays and strings in globals must be written out".to_string()),
+        }
+    }
+    fn array_data(
+        &mut self,
+        idx: TypeTreeIndex,
+        ty: &Ty,
+        writable: bool,
+        scopes: &Vec<ScopeTable>,
+        types: &Vec<TypeTree>,
+    ) -> Result<(DataId, usize), String> {
+        let (bytes, align, len) = self.array_bytes(idx, ty, scopes, types)?;
+        let id = self
+            .obj_mod
+            .declare_anonymous_data(writable, false)
+            .unwrap();
+        self.define(id, bytes, align);
+        Ok((id, len))
+    }
+    fn define(&mut self, id: DataId, bytes: GlobalBytes, align: u32) -> () {
+        let mut data = DataDescription::new();
+        data.define(bytes.bytes.into_boxed_slice());
+        data.set_align(u64::from(align));
+        for (offset, target) in bytes.relocs {
+            let gv = self.obj_mod.declare_data_in_data(target, &mut data);
+            data.write_data_addr(offset, gv, 0);
         }
+        self.obj_mod.define_data(id, &data).unwrap();
+    }
+    // string literals within functions are read only
+    pub fn declare_string(&mut self, text: &str) -> DataId {
+        let mut bytes = text.as_bytes().to_vec();
+        bytes.push(0);
+        let id = self.obj_mod.declare_anonymous_data(false, false).unwrap();
+        self.define(id, GlobalBytes::new(bytes), 1);
+        id
     }
 
+    // each global is its own data object. a top level array holds its elements directly.
+    // `let` globals are writable, as are const globals holding elements or properties that can be
+    // written. only pub globals are visible outside of the object
     pub fn const_init(
         &mut self,
         init: &TopInitialization,
+        writable: bool,
         dt: &mut DataTable,
+        scopes: &Vec<ScopeTable>,
         types: &Vec<TypeTree>,
-    ) -> () {
+    ) -> Result<(), String> {
         let slice = &types
             .get(init.left as usize)
             .unwrap()
             .into_symbol_init()
             .ident;
-        self.recurse(init.right, types);
+        let (bytes, align) = match peel(&init.curried) {
+            Ty::Array(_) | Ty::String => {
+                let (bytes, align, _) =
+                    self.array_bytes(init.right, &init.curried, scopes, types)?;
+                (bytes, align)
+            }
+            _ => {
+                let layout = layout_of(&init.curried, scopes, types);
+                let mut bytes = GlobalBytes::new(vec![0; layout.size as usize]);
+                self.emit(init.right, &init.curried, 0, &mut bytes, scopes, types)?;
+                (bytes, layout.align)
+            }
+        };
+        let (linkage, symbol) = match init.vis {
+            true => (Linkage::Export, mangle(&self.module, slice)),
+            false => (Linkage::Local, slice.to_string()),
+        };
+        let writable = writable || writable_within(&init.curried, scopes, types);
         let id = self
             .obj_mod
-            .declare_data(slice, Linkage::Export, false, false)
+            .declare_data(&symbol, linkage, writable, false)
             .unwrap();
-        self.obj_mod.define_data(id, &self.data).unwrap();
+        self.define(id, bytes, align);
         dt.table.insert(slice.to_string(), id);
+        Ok(())
     }
-    pub fn add_fn(&mut self, name: &str, func: Function) -> () {
+    // only pub functions are visible outside of the object, by their name within the module
+    // unless they are exported
+    pub fn declare_fn(&mut self, name: &str, vis: bool, export: bool, sig: &Signature) -> FuncId {
+        let (linkage, symbol) = match (vis, export) {
+            (true, true) => (Linkage::Export, name.to_string()),
+            (true, false) => (Linkage::Export, mangle(&self.module, name)),
+            (false, _) => (Linkage::Local, name.to_string()),
+        };
+        let func_id = self
+            .obj_mod
+            .declare_function(&symbol, linkage, sig)
+            .unwrap();
+        self.ftable.insert(name.to_string(), func_id);
+        func_id
+    }
+    // functions defined outside of ty, like libc
+    pub fn declare_import(&mut self, name: &str, sig: &Signature) -> FuncId {
         let func_id = self
             .obj_mod
-            .declare_function(name, Linkage::Export, &func.signature)
+            .declare_function(name, Linkage::Import, sig)
+            .unwrap();
+        self.ftable.insert(name.to_string(), func_id);
+        func_id
+    }
+    // a function from another module, known by name locally
+    pub fn import_fn(&mut self, name: &str, symbol: &str, sig: &Signature) -> FuncId {
+        let func_id = self.declare_import(symbol, sig);
+        self.ftable.insert(name.to_string(), func_id);
+        func_id
+    }
+    pub fn import_data(&mut self, imp: &ImportInitialize, dt: &mut DataTable) -> DataId {
+        let id = self
+            .obj_mod
+            .declare_data(&imp.symbol, Linkage::Import, imp.writable, false)
             .unwrap();
+        dt.table.insert(imp.name.clone(), id);
+        id
+    }
+    pub fn add_fn(&mut self, name: &str, func: Function) -> () {
+        let func_id = match self.ftable.get(name) {
+            Some(x) => *x,
+            None => self.declare_fn(name, false, false, &func.signature),
+        };
 
         let mut ctx = Context::for_function(func);
         self.obj_mod.define_function(func_id, &mut ctx).unwrap();
@@ -72,3 +269,101 @@ impl Oir {
         return bytes;
     }
 }
+
+// the contents of a global, and where within it the addresses of other data objects are written
+struct GlobalBytes {
+    bytes: Vec<u8>,
+    relocs: Vec<(u32, DataId)>,
+}
+
+impl GlobalBytes {
+    fn new(bytes: Vec<u8>) -> Self {
+        GlobalBytes {
+            bytes,
+            relocs: vec![],
+        }
+    }
+}
+
+enum Folded {
+    Int(i64),
+    Float(f64),
+}
+
+// globals are known at compile time, expressions of literals and other constants are folded.
+// the linter rejects anything else, so an error here is a value it let through
+fn fold(
+    idx: TypeTreeIndex,
+    scopes: &Vec<ScopeTable>,
+    types: &Vec<TypeTree>,
+) -> Result<Folded, String> {
+    match types.get(idx as usize).unwrap() {
+        TypeTree::I64(x) => Ok(Folded::Int(*x)),
+        TypeTree::U64(x) => Ok(Folded::Int(*x as i64)),
+        TypeTree::I32(x) => Ok(Folded::Int(i64::from(*x))),
+        TypeTree::U32(x) => Ok(Folded::Int(i64::from(*x))),
+        TypeTree::F64(x) => Ok(Folded::Float(*x)),
+        TypeTree::BoolValue(x) => Ok(Folded::Int(i64::from(*x))),
+        TypeTree::Char(x) => match u8::try_from(*x) {
+            Ok(x) => Ok(Folded::Int(i64::from(x))),
+            Err(_) => Err(format!("char {} does not fit in a byte", x)),
+        },
+        TypeTree::Negate(x) => match fold(x.val, scopes, types)? {
+            Folded::Int(x) => Ok(Folded::Int(x.wrapping_neg())),
+            Folded::Float(x) => Ok(Folded::Float(-x)),
+        },
+        TypeTree::Plus(x) => fold_bin(x, i64::wrapping_add, |l, r| l + r, scopes, types),
+        TypeTree::Minus(x) => fold_bin(x, i64::wrapping_sub, |l, r| l - r, scopes, types),
+        TypeTree::Multiply(x) => fold_bin(x, i64::wrapping_mul, |l, r| l * r, scopes, types),
+        TypeTree::SymbolAccess(x) => {
+            let top = scopes
+                .get(0)
+                .and_then(|s| s.this_tree.get(&x.ident))
+                .and_then(|t| types.get(*t as usize));
+            match top {
+                Some(TypeTree::TopConstInit(init)) => fold(init.right, scopes, types),
+                _ => Err(format!(
+                    "{} is not a constant known at compile time",
+                    x.ident
+                )),
+            }
+        }
+        _ => Err("global data must be known at compile time".to_string()),
+    }
+}
+
+fn fold_bin(
+    op: &BinaryOp,
+    int: fn(i64, i64) -> i64,
+    float: fn(f64, f64) -> f64,
+    scopes: &Vec<ScopeTable>,
+    types: &Vec<TypeTree>,
+) -> Result<Folded, String> {
+    match (
+        fold(op.left, scopes, types)?,
+        fold(op.right, scopes, types)?,
+    ) {
+        (Folded::Int(l), Folded::Int(r)) => Ok(Folded::Int(int(l, r))),
+        (Folded::Int(l), Folded::Float(r)) => Ok(Folded::Float(float(l as f64, r))),
+        (Folded::Float(l), Folded::Int(r)) => Ok(Folded::Float(float(l, r as f64))),
+        (Folded::Float(l), Folded::Float(r)) => Ok(Folded::Float(float(l, r))),
+    }
+}
+
+// elements and properties can be written through a const binding, unless they are const
+fn writable_within(ty: &Ty, scopes: &Vec<ScopeTable>, types: &Vec<TypeTree>) -> bool {
+    match peel(ty) {
+        Ty::Array(x) => !matches!(x.as_ref(), Ty::Const(_)),
+        Ty::String => true,
+        Ty::Custom(name) => struct_info(name, scopes, types)
+            .is_some_and(|x| x.types.iter().any(|x| !matches!(x, Ty::Const(_)))),
+        _ => false,
+    }
+}
+
+fn peel(ty: &Ty) -> &Ty {
+    match ty {
+        Ty::Const(x) | Ty::Mut(x) => peel(x),
+        _ => ty,
+    }
+}
@@ -0,0 +1,141 @@
+use lexer::Lexeme;
+use std::collections::BTreeMap;
+use types::TypeTreeIndex;
+
+// a binding holding a & or * borrow of another binding, the root
+#[derive(Debug, Clone)]
+pub struct Borrow {
+    pub root: TypeTreeIndex,
+    pub mutable: bool,
+    pub at: Lexeme,
+}
+
+// who owns what while a file is linted. bindings are known by the index of their declaration.
+// borrows held by a binding last until the scope it was declared in ends
+#[derive(Debug, Default)]
+pub struct Ownership {
+    scopes: BTreeMap<TypeTreeIndex, u32>,
+    moved: BTreeMap<TypeTreeIndex, Lexeme>,
+    borrows: BTreeMap<TypeTreeIndex, Borrow>,
+}
+
+impl Ownership {
+    pub fn new() -> Self {
+        Ownership::default()
+    }
+    pub fn clear(&mut self) -> () {
+        self.scopes.clear();
+        self.moved.clear();
+        self.borrows.clear();
+    }
+    pub fn declare(&mut self, binding: TypeTreeIndex, scope: u32) -> () {
+        self.scopes.insert(binding, scope);
+    }
+    pub fn scope_of(&self, binding: TypeTreeIndex) -> Option<u32> {
+        self.scopes.get(&binding).copied()
+    }
+    pub fn moved_at(&self, binding: TypeTreeIndex) -> Option<&Lexeme> {
+        self.moved.get(&binding)
+    }
+    pub fn move_out(&mut self, binding: TypeTreeIndex, at: Lexeme) -> () {
+        self.moved.insert(binding, at);
+    }
+    // a moved binding that is assigned a new value is usable again
+    pub fn revive(&mut self, binding: TypeTreeIndex) -> () {
+        self.moved.remove(&binding);
+    }
+    pub fn borrow(&mut self, holder: TypeTreeIndex, borrow: Borrow) -> () {
+        self.borrows.insert(holder, borrow);
+    }
+    pub fn end_borrow(&mut self, holder: TypeTreeIndex) -> () {
+        self.borrows.remove(&holder);
+    }
+    pub fn borrow_of(&self, holder: TypeTreeIndex) -> Option<&Borrow> {
+        self.borrows.get(&holder)
+    }
+    // a live borrow of root that a new borrow would conflict with. a * borrow conflicts with every
+    // other borrow, any number of & borrows can live together
+    pub fn conflict(&self, root: TypeTreeIndex, mutable: bool) -> Option<&Borrow> {
+        self.borrows
+            .values()
+            .find(|b| b.root == root && (mutable || b.mutable))
+    }
+    pub fn borrowed(&self, root: TypeTreeIndex) -> Option<&Borrow> {
+        self.conflict(root, true)
+    }
+    // the scope has ended, the borrows its bindings held are given back
+    pub fn release(&mut self, scope: u32) -> () {
+        let scopes = &self.scopes;
+        self.borrows
+            .retain(|holder, _| scopes.get(holder).is_some_and(|s| *s != scope));
+    }
+    // branches are checked from the same starting point, a value moved in any branch is moved after
+    pub fn snapshot(&self) -> BTreeMap<TypeTreeIndex, Lexeme> {
+        self.moved.clone()
+    }
+    pub fn restore(&mut self, moved: BTreeMap<TypeTreeIndex, Lexeme>) -> () {
+        self.moved = moved;
+    }
+    pub fn merge(&mut self, moved: BTreeMap<TypeTreeIndex, Lexeme>) -> () {
+        for (k, v) in moved {
+            self.moved.entry(k).or_insert(v);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use token::Token;
+
+    fn at() -> Lexeme {
+        Lexeme {
+            token: Token::Symbol,
+            span: 0..1,
+            slice: "x".to_string(),
+        }
+    }
+    #[test]
+    fn it_should_release_borrows_with_their_scope() {
+        let mut own = Ownership::new();
+        own.declare(1, 0);
+        own.declare(2, 3);
+        own.declare(4, 3);
+        own.borrow(
+            2,
+            Borrow {
+                root: 1,
+                mutable: false,
+                at: at(),
+            },
+        );
+        assert!(own.conflict(1, false).is_none());
+        assert!(own.conflict(1, true).is_some());
+        own.borrow(
+            4,
+            Borrow {
+                root: 1,
+                mutable: true,
+                at: at(),
+            },
+        );
+        assert!(own.conflict(1, false).is_some());
+        own.release(3);
+        assert!(own.borrowed(1).is_none());
+    }
+    #[test]
+    fn it_should_merge_moves_from_branches() {
+        let mut own = Ownership::new();
+        let before = own.snapshot();
+        own.move_out(1, at());
+        let body = own.snapshot();
+        own.restore(before);
+        assert!(own.moved_at(1).is_none());
+        own.move_out(2, at());
+        own.merge(body);
+        assert!(own.moved_at(1).is_some());
+        assert!(own.moved_at(2).is_some());
+        own.revive(1);
+        assert!(own.moved_at(1).is_none());
+    }
+}
@@ -11,21 +11,77 @@ pub type OptExpr = Option<Box<Expr>>;
 
 pub struct Parser<'s> {
     lexer: TLexer<'s>,
+    // errors recovered from so far, parsing carries on after each
+    errors: Vec<ParserError>,
+    source: SourceFile,
 }
 
 impl<'s> Parser<'s> {
     // todo:: optimization: use an allocator for all expressions into a single vec, use either
     // references or id's
     pub fn new(lexer: TLexer<'s>) -> Self {
-        Parser { lexer }
+        let source = SourceFile::new(0, "", lexer.lexer.source());
+        Parser {
+            lexer,
+            errors: vec![],
+            source,
+        }
+    }
+    // the file errors point into
+    pub fn with_file(mut self, file: &SourceFile) -> Self {
+        self.source = file.clone();
+        self
     }
 
-    pub fn all(&mut self) -> ResultExpr {
+    // every declaration that parsed, and the errors for the ones that didn't
+    pub fn all(&mut self) -> (Box<Expr>, Vec<ParserError>) {
         let mut tops: Vec<Box<Expr>> = vec![];
         while self.lexer.peek().is_some() {
-            tops.push(self.top_decl()?);
+            let start = self.position();
+            match self.top_decl() {
+                Ok(x) => tops.push(x),
+                Err(x) => {
+                    self.errors.push(x);
+                    self.synchronize(start, false);
+                }
+            }
+        }
+        (expr!(FileAll, tops), std::mem::take(&mut self.errors))
+    }
+
+    fn position(&mut self) -> Option<usize> {
+        self.lexer.peek().map(|x| x.span.start)
+    }
+    // panic mode recovery, skips ahead to where parsing can start again. in a block that is
+    // the next statement or the closing brace, at the top level it is the next declaration.
+    // at least one token is skipped when the failure didn't get past start
+    fn synchronize(&mut self, start: Option<usize>, in_block: bool) -> () {
+        if self.position() == start {
+            match self.lexer.peek() {
+                Some(x) if in_block && x.token == Token::CBrace => return,
+                Some(_) => {
+                    self.lexer.collect();
+                }
+                None => return,
+            }
+        }
+        let mut depth = 0;
+        while let Some(x) = self.lexer.peek() {
+            match x.token {
+                Token::OBrace => depth += 1,
+                Token::CBrace if depth == 0 && in_block => return,
+                Token::CBrace if depth > 0 => depth -= 1,
+                Token::Const | Token::Let if depth == 0 => return,
+                Token::Return | Token::Break if depth == 0 && in_block => return,
+                Token::Pub | Token::Export | Token::Extern | Token::Type | Token::Impl
+                    if depth == 0 && !in_block =>
+                {
+                    return
+                }
+                _ => (),
+            }
+            self.lexer.collect();
         }
-        return result_expr!(FileAll, tops);
     }
 
     pub fn _return(&mut self) -> ResultExpr {
@@ -36,8 +92,11 @@ impl<'s> Parser<'s> {
                 self,
                 "expected 'break' or 'return' depending on block context".to_string(),
             )?;
+        if self.lexer.peek().is_some_and(|l| l.token == Token::CBrace) {
+            return result_expr!(RetOp, span, None);
+        }
         self.expr()
-            .xresult_or(|expr| result_expr!(RetOp, span, expr))
+            .xresult_or(|expr| result_expr!(RetOp, span, Some(expr)))
     }
 
     pub fn _import(&mut self, mutability: Lexeme, identifier: Box<Expr>) -> ResultExpr {
@@ -58,8 +117,12 @@ impl<'s> Parser<'s> {
     ) -> ResultExpr {
         let mut variants: Vec<Box<Expr>> = vec![];
         while let Some(_) = self.lexer.collect_if(Token::Bar) {
-            let x = self.ident().xconvert_to_decl()?;
-            variants.push(x);
+            let x = self.ident().xconvert_to_decl(&self)?;
+            // a variant can carry a payload, `| circle: f64`
+            match self.opt_signature()? {
+                Some(typ) => variants.push(expr!(Declarator, x, typ)),
+                None => variants.push(x),
+            }
         }
         result_expr!(TagDecl, visibility, mutability, identifier, variants, sig)
     }
@@ -73,7 +136,7 @@ impl<'s> Parser<'s> {
     ) -> ResultExpr {
         let mut variants: Vec<Box<Expr>> = vec![];
         while let Some(_) = self.lexer.collect_if(Token::Bar) {
-            let x = self.ident().xconvert_to_decl()?;
+            let x = self.ident().xconvert_to_decl(&self)?;
             variants.push(x);
         }
         result_expr!(ErrorDecl, visibility, mutability, identifier, variants, sig)
@@ -96,8 +159,15 @@ impl<'s> Parser<'s> {
                 .xexpect_token(&self, "expected ')'".to_string())?;
         }
         while let Some(_) = self.lexer.collect_if(Token::Bar) {
-            let x = self.ident().xconvert_to_decl()?;
-            variants.push(x);
+            let x = self.ident().xconvert_to_decl(&self)?;
+            // a variant can be given its value, `| a = 1`
+            match self.lexer.collect_if(Token::As) {
+                Some(_) => {
+                    let val = self.or()?;
+                    variants.push(expr!(PropAssignment, x, val));
+                }
+                None => variants.push(x),
+            }
         }
         result_expr!(EnumDecl, visibility, mutability, identifier, variants, sig, enum_type)
     }
@@ -122,7 +192,9 @@ impl<'s> Parser<'s> {
     }
 
     pub fn top_decl(&mut self) -> ResultExpr {
-        let has_pub = self.lexer.collect_if(Token::Pub);
+        let has_pub = self
+            .lexer
+            .collect_of_if(&[Token::Pub, Token::Export, Token::Extern]);
         let mutability = self
             .lexer
             .collect_of_if(&[Token::Let, Token::Const, Token::Type, Token::Impl])
@@ -130,7 +202,7 @@ impl<'s> Parser<'s> {
         let identifier = self
             .destructure()
             .xexpect_expr(&self, "expected identifier, or destructure".to_string())
-            .xconvert_to_decl()?;
+            .xconvert_to_decl(&self)?;
         let sig = self.opt_signature()?;
         let _ = self
             .lexer
@@ -153,7 +225,7 @@ impl<'s> Parser<'s> {
                 Token::Trait => return self._trait(has_pub, mutability, identifier, sig),
                 Token::Error => return self._error(has_pub, mutability, identifier, sig),
                 Token::Enum => return self._enum(has_pub, mutability, identifier, sig),
-                _ => panic!("type-lang error unreachable code hit"),
+                _ => return Err(self.make_error("expected a declaration".to_string())),
             }
         }
         let asgn = self.expr()?;
@@ -232,7 +304,10 @@ impl<'s> Parser<'s> {
         let ret_type = self
             .sig_union()
             .xexpect_expr(&self, "expected function return type".to_string())?;
-        let block = self.block()?;
+        let block = match &visibility {
+            Some(v) if v.token == Token::Extern => None,
+            _ => Some(self.block()?),
+        };
         result_expr!(FuncDecl, visibility, mutability, identifier, args, ret_type, block, sig)
     }
     pub fn chars(&mut self) -> OptExpr {
@@ -263,8 +338,7 @@ impl<'s> Parser<'s> {
         let sig = self
             .opt_signature()
             .xexpect_expr(&self, "expected signature".to_string())?;
-        return result_expr!(Declarator, id.xconvert_to_decl().unwrap(), sig)
-            .xconvert_to_result_opt();
+        return result_expr!(Declarator, id.xconvert_to_decl(&self)?, sig).xconvert_to_result_opt();
     }
     pub fn args(&mut self) -> Result<Option<Vec<Box<Expr>>>> {
         let mut arg_list: Vec<Box<Expr>> = vec![];
@@ -280,6 +354,20 @@ impl<'s> Parser<'s> {
         }
         Ok(None)
     }
+    pub fn inferred_args(&mut self) -> Result<Option<Vec<Box<Expr>>>> {
+        let mut arg_list: Vec<Box<Expr>> = vec![];
+        if let Some(arg_local) = self.inferred_arg()? {
+            arg_list.push(arg_local);
+            while let Some(_comma) = self.lexer.collect_if(Token::Comma) {
+                arg_list.push(
+                    self.inferred_arg()?
+                        .xexpect_expr(&self, "expected argument".to_string())?,
+                );
+            }
+            return Ok(Some(arg_list));
+        }
+        Ok(None)
+    }
     pub fn array_decl(&mut self) -> ResultOptExpr {
         if let Some(_) = self.lexer.collect_if(Token::OBracket) {
             if let Some(_) = self.lexer.collect_if(Token::CBracket) {
@@ -307,7 +395,7 @@ impl<'s> Parser<'s> {
             );
             let sym = Box::new(Expr::SymbolDecl(id.into_symbol()));
             if let Ok(sig) = result {
-                return result_expr!(ArgDef, sym, sig).xconvert_to_result_opt();
+                return result_expr!(ArgDef, sym, Some(sig)).xconvert_to_result_opt();
             }
             return result.xconvert_to_result_opt();
         }
@@ -318,18 +406,35 @@ impl<'s> Parser<'s> {
             );
             let sym = Box::new(Expr::SelfDecl(id.into_self()));
             if let Ok(sig) = result {
-                return result_expr!(ArgDef, sym, sig).xconvert_to_result_opt();
+                return result_expr!(ArgDef, sym, Some(sig)).xconvert_to_result_opt();
             }
             return result.xconvert_to_result_opt();
         }
         Ok(None)
     }
+    pub fn inferred_arg(&mut self) -> ResultOptExpr {
+        if let Some(id) = self.ident() {
+            let sig = self.opt_signature()?;
+            let sym = Box::new(Expr::SymbolDecl(id.into_symbol()));
+            return bubble_expr!(ArgDef, sym, sig);
+        }
+        if let Some(id) = self._self() {
+            let sig = self.opt_signature()?;
+            let sym = Box::new(Expr::SelfDecl(id.into_self()));
+            return bubble_expr!(ArgDef, sym, sig);
+        }
+        Ok(None)
+    }
 
     pub fn sig_union(&mut self) -> ResultOptExpr {
         let left = self.signature_no_colon();
         let err = self.lexer.collect_if(Token::Exclam);
         let undef = self.lexer.collect_if(Token::Question);
-        let right = self.signature_no_colon();
+        // a right most type only follows an error or undefined marker
+        let right = match err.is_some() || undef.is_some() {
+            true => self.signature_no_colon(),
+            false => Ok(None),
+        };
         if left.is_err() {
             return left;
         }
@@ -364,14 +469,19 @@ impl<'s> Parser<'s> {
             Token::Ampersand,
             Token::Asterisk,
         ]);
+        // the type keeps its borrow or mutability, like &[const char]
+        let borrowed = |x: Box<Expr>| match &muta {
+            Some(m) => Box::new(Expr::UnOp(UnOp::new(m.clone(), x))),
+            None => x,
+        };
         if let Some(x) = self.val_type() {
-            return Ok(Some(x));
+            return Ok(Some(borrowed(x)));
         }
         if let Some(arr) = self.arr_type()? {
-            return Ok(Some(arr));
+            return Ok(Some(borrowed(arr)));
         }
         if let Some(id) = self.ident() {
-            return Ok(Some(id));
+            return Ok(Some(borrowed(id)));
         }
         if let Some(fn_typ) = self._fn_type()? {
             if muta.is_some() {
@@ -414,7 +524,8 @@ impl<'s> Parser<'s> {
                 let x = self.expr()?;
                 return bubble_expr!(Reassignment, a, x, op);
             }
-            return Ok(None);
+            // an access on its own is a statement, like invoking a void function
+            return Ok(Some(a));
         }
         Ok(acc)
     }
@@ -424,7 +535,7 @@ impl<'s> Parser<'s> {
             let identifier = self
                 .destructure()
                 .xexpect_expr(&self, "expected identifier, or destructure".to_string())
-                .xconvert_to_decl()?;
+                .xconvert_to_decl(&self)?;
             let sig = self.opt_signature()?;
             let _ = self
                 .lexer
@@ -452,10 +563,24 @@ impl<'s> Parser<'s> {
             .collect_if(Token::CParen)
             .xexpect_token(&self, "expected ')'".to_string())?;
         if let Some(_fn) = self.anon_fn()? {
-            return bubble_expr!(If, x, _fn);
+            let els = self._else()?;
+            return bubble_expr!(If, x, _fn, els);
         }
         let blk = self.block()?;
-        return bubble_expr!(If, x, blk);
+        let els = self._else()?;
+        return bubble_expr!(If, x, blk, els);
+    }
+    pub fn _else(&mut self) -> ResultOptExpr {
+        if let None = self.lexer.collect_if(Token::Else) {
+            return Ok(None);
+        }
+        if let Some(x) = self._if()? {
+            return Ok(Some(x));
+        }
+        if let Some(_fn) = self.anon_fn()? {
+            return Ok(Some(_fn));
+        }
+        self.block().xconvert_to_result_opt()
     }
     pub fn _while(&mut self) -> ResultOptExpr {
         let f = self.lexer.collect_if(Token::While);
@@ -523,42 +648,63 @@ impl<'s> Parser<'s> {
             .collect_if(Token::OBrace)
             .xexpect_token(&self, "expected '{'".to_string())?;
         let mut exprs: Vec<Box<Expr>> = vec![];
-        'l: loop {
-            match self.inner_decl()? {
-                Some(x) => exprs.push(x),
-                None => match self.reassign()? {
-                    Some(x) => exprs.push(x),
-                    None => match self._for()? {
-                        Some(x) => exprs.push(x),
-                        None => match self._if()? {
-                            Some(x) => exprs.push(x),
-                            None => match self._while()? {
-                                Some(x) => exprs.push(x),
-                                None => match self._match()? {
-                                    Some(x) => exprs.push(x),
-                                    None => break 'l,
-                                },
-                            },
-                        },
-                    },
-                },
+        loop {
+            let start = self.position();
+            match self.statement() {
+                Ok(Some(x)) => exprs.push(x),
+                Ok(None) => break,
+                Err(x) => {
+                    self.errors.push(x);
+                    self.synchronize(start, true);
+                }
             }
         }
-
-        if let Ok(x) = self._return() {
-            exprs.push(x);
+        if self
+            .lexer
+            .peek()
+            .is_some_and(|l| l.token.is_of_kind(&[Token::Return, Token::Break]))
+        {
+            let start = self.position();
+            match self._return() {
+                Ok(x) => exprs.push(x),
+                Err(x) => {
+                    self.errors.push(x);
+                    self.synchronize(start, true);
+                }
+            }
         }
         self.lexer
             .collect_if(Token::CBrace)
             .xexpect_token(&self, "expected '}'".to_string())?;
         result_expr!(Block, exprs)
     }
+    pub fn statement(&mut self) -> ResultOptExpr {
+        if let Some(x) = self.inner_decl()? {
+            return Ok(Some(x));
+        }
+        if let Some(x) = self.reassign()? {
+            return Ok(Some(x));
+        }
+        if let Some(x) = self._for()? {
+            return Ok(Some(x));
+        }
+        if let Some(x) = self._if()? {
+            return Ok(Some(x));
+        }
+        if let Some(x) = self._while()? {
+            return Ok(Some(x));
+        }
+        self._match()
+    }
     pub fn expr(&mut self) -> ResultExpr {
         if self.lexer.peek().is_some_and(|l| {
             return l.token == Token::OBrace;
         }) {
             return self.block();
         }
+        if let Some(x) = self._if()? {
+            return Ok(x);
+        }
         let mresult = self._match()?;
         if let None = mresult {
             return self.or().xresult_or(|mut left| {
@@ -708,6 +854,14 @@ impl<'s> Parser<'s> {
                 Token::Range,
                 Token::CastAs,
             ]) {
+                // the right of a cast is the type being cast to
+                if bin.token == Token::CastAs {
+                    left = self
+                        .signature_no_colon()?
+                        .xconvert_to_result(&self, "expected a type after as".to_string())
+                        .xresult_or(|right| result_expr!(BinOp, left, bin, right))?;
+                    continue;
+                }
                 left = self
                     .unary()
                     .xresult_or(|right| result_expr!(BinOp, left, bin, right))?
@@ -744,7 +898,7 @@ impl<'s> Parser<'s> {
             Token::Catch,
         ]) {
             match x.token {
-                Token::Question => self.resolve_access(expr!(UndefBubble, prev)),
+                Token::Question => self.resolve_access(expr!(UndefBubble, x, prev)),
                 Token::Period => {
                     let ident = self
                         .ident()
@@ -753,7 +907,7 @@ impl<'s> Parser<'s> {
                     self.resolve_access(expr!(PropAccess, prev, ident))
                 }
                 Token::Catch => {
-                    return self.catch().xconvert_to_result_opt();
+                    return self.catch(x, prev).xconvert_to_result_opt();
                 }
                 Token::OBracket => {
                     let expr = self.array_access()?;
@@ -781,7 +935,7 @@ impl<'s> Parser<'s> {
                         let ident = self
                             .ident()
                             .xexpect_expr(&self, "expected identifier".to_string())
-                            .xconvert_to_decl()?;
+                            .xconvert_to_decl(&self)?;
                         let mut props: Vec<Box<Expr>> = vec![];
                         let _ = self
                             .lexer
@@ -793,7 +947,7 @@ impl<'s> Parser<'s> {
                             let id = self
                                 .ident()
                                 .xexpect_expr(&self, "expected identifier".to_string())
-                                .xconvert_to_decl()?;
+                                .xconvert_to_decl(&self)?;
                             let _ = self
                                 .lexer
                                 .collect_if(Token::Colon)
@@ -810,31 +964,33 @@ impl<'s> Parser<'s> {
                     }
                     return self.resolve_access(expr!(PropAssignments, prev, None));
                 }
-                _ => panic!("developer error"),
+                _ => return Err(self.make_error("expected an access".to_string())),
             }
         } else {
             Ok(Some(prev))
         }
     }
-    pub fn catch(&mut self) -> ResultExpr {
+    // `x catch (e) u64 { ... }` runs the block when x is an error, the name the error is bound to
+    // and the type the block gives are both optional
+    pub fn catch(&mut self, span: Lexeme, prev: Box<Expr>) -> ResultExpr {
         let _ = self
             .lexer
             .collect_if(Token::OParen)
             .xexpect_token(&self, "expected one of '('".to_string())?;
-        let args = self.arg()?;
-        let ret_type = self
-            .sig_union()
-            .xexpect_expr(&self, "expected catch return type".to_string())?;
+        let args = self.inferred_arg()?;
         let _ = self
             .lexer
             .collect_if(Token::CParen)
             .xexpect_token(&self, "expected one of ')'".to_string())?;
-
+        let ret_type = match self.lexer.peek().is_some_and(|l| l.token == Token::OBrace) {
+            true => None,
+            false => Some(
+                self.sig_union()
+                    .xexpect_expr(&self, "expected catch return type".to_string())?,
+            ),
+        };
         let bl = self.block()?;
-        if args.is_some() {
-            return result_expr!(CatchDecl, Some(vec![args.unwrap()]), ret_type, bl);
-        }
-        return result_expr!(CatchDecl, None, ret_type, bl);
+        return result_expr!(CatchDecl, span, prev, args.map(|x| vec![x]), ret_type, bl);
     }
     pub fn access(&mut self) -> ResultOptExpr {
         let term = self.terminal()?;
@@ -915,7 +1071,7 @@ impl<'s> Parser<'s> {
                 .lexer
                 .collect_if(Token::OParen)
                 .xexpect_token(&self, "expected one of '('".to_string())?;
-            let args = self.args()?;
+            let args = self.inferred_args()?;
             let _ = self
                 .lexer
                 .collect_if(Token::CParen)
@@ -998,8 +1154,34 @@ impl<'s> Parser<'s> {
         return lexeme.xconvert_expr(|span| expr!(ValueType, span));
     }
     fn make_error(&self, title: String) -> ParserError {
-        let x = CodeLocation::new_lexer_stop_point(&self.lexer.lexer);
-        return ParserError::new(title, x.code, x.line, x.col, x.val);
+        let range = self.lexer.lexer.span();
+        let (line, col) = self.source.position(range.start);
+        // a token the lexer couldn't make sense of says more than what was expected there
+        let (id, title) = match self.lexer.current() {
+            Some(l) if l.token == Token::Invalid => {
+                ("E0002", format!("unexpected character '{}'", l.slice))
+            }
+            Some(l) if l.token == Token::Unterminated && l.slice.starts_with('\'') => (
+                "E0003",
+                "unterminated char, it needs a closing '".to_string(),
+            ),
+            Some(l) if l.token == Token::Unterminated => (
+                "E0003",
+                format!("unterminated string, it needs a closing {}", &l.slice[..1]),
+            ),
+            _ => ("E0001", title),
+        };
+        let mut err = ParserError::new(
+            title,
+            self.source.code(&range).to_string(),
+            line,
+            col,
+            self.source.slice(&range).to_string(),
+        );
+        err.id = id;
+        err.file = self.source.name.clone();
+        err.source = self.source.line(line).to_string();
+        return err;
     }
 }
 
@@ -1028,11 +1210,11 @@ trait ConvertToResult {
 }
 
 trait ConvertToDecl {
-    fn xconvert_to_decl(self) -> ResultExpr;
+    fn xconvert_to_decl(self, parser: &Parser) -> ResultExpr;
 }
 
 trait ConvertToDeclResult {
-    fn xconvert_to_decl(self) -> ResultExpr;
+    fn xconvert_to_decl(self, parser: &Parser) -> ResultExpr;
 }
 
 trait ConvertToResultOpt {
@@ -1109,7 +1291,7 @@ impl ExpectExpr for OptExpr {
 }
 
 impl ConvertToDeclResult for ResultExpr {
-    fn xconvert_to_decl(self) -> ResultExpr {
+    fn xconvert_to_decl(self, parser: &Parser) -> ResultExpr {
         match self {
             Err(x) => Err(x),
             Ok(val) => match *val {
@@ -1119,21 +1301,21 @@ impl ConvertToDeclResult for ResultExpr {
                 Expr::Destructure(_) => {
                     return Ok(val);
                 }
-                _ => panic!("type lang issue, expected into symbol"),
+                _ => Err(parser.make_error("expected identifier, or destructure".to_string())),
             },
         }
     }
 }
 
 impl ConvertToDecl for OptExpr {
-    fn xconvert_to_decl(self) -> ResultExpr {
+    fn xconvert_to_decl(self, parser: &Parser) -> ResultExpr {
         match self {
-            None => panic!("type lang issue, expected a symbol"),
+            None => Err(parser.make_error("expected identifier".to_string())),
             Some(val) => match *val {
                 Expr::Symbol(x) => {
                     return Ok(Box::new(Expr::SymbolDecl(x)));
                 }
-                _ => panic!("type lang issue, expected into symbol"),
+                _ => Err(parser.make_error("expected identifier".to_string())),
             },
         }
     }
@@ -1366,14 +1548,14 @@ mod tests {
                         token: Token::Return,
                         span: 12..18,
                     },
-                    expr!(
+                    Some(expr!(
                         Symbol,
                         Lexeme {
                             slice: String::from("x"),
                             token: Token::Symbol,
                             span: 19..20
                         }
-                    )
+                    ))
                 )
             ]
         );
@@ -1428,6 +1610,105 @@ mod tests {
         assert_eq!(result.unwrap(), expr);
     }
     #[test]
+    fn it_should_parse_else_if() {
+        let lexer = TLexer::new("if (x) { break 1 } else if (y) { break 2 } else { break 3 }");
+        let mut parser = Parser::new(lexer);
+        let result = parser._if().unwrap().unwrap();
+        let Expr::If(first) = *result else {
+            panic!("expected if");
+        };
+        let Expr::If(second) = *first.else_body.unwrap() else {
+            panic!("expected else if");
+        };
+        let Expr::Block(last) = *second.else_body.unwrap() else {
+            panic!("expected else block");
+        };
+        assert_eq!(last.exprs.len(), 1);
+    }
+    #[test]
+    fn it_should_parse_bare_break() {
+        let lexer = TLexer::new("{ break }");
+        let mut parser = Parser::new(lexer);
+        let result = parser.block();
+        let expr = expr!(
+            Block,
+            vec![expr!(
+                RetOp,
+                Lexeme {
+                    slice: String::from("break"),
+                    token: Token::Break,
+                    span: 2..7,
+                },
+                None
+            )]
+        );
+        assert_eq!(result.unwrap(), expr);
+    }
+    #[test]
+    fn it_should_recover_from_errors() {
+        let lexer = TLexer::new(
+            "const a = fn() u64 {
+                const x = = 5
+                return 1
+            }
+            const b = 5 +
+            pub const c = fn() u64 { return 2 }
+            const d = )
+            const e = 7",
+        );
+        let mut parser = Parser::new(lexer);
+        let (result, errors) = parser.all();
+        let lines: Vec<usize> = errors.iter().map(|x| x.line).collect();
+        assert_eq!(lines, vec![2, 6, 7]);
+        let Expr::FileAll(all) = *result else {
+            panic!("expected file");
+        };
+        assert_eq!(all.top_decls.len(), 3);
+        let Expr::FuncDecl(a) = all.top_decls[0].as_ref() else {
+            panic!("expected function declaration");
+        };
+        let Expr::Block(body) = a.block.as_ref().unwrap().as_ref() else {
+            panic!("expected block");
+        };
+        assert_eq!(body.exprs.len(), 1);
+    }
+    #[test]
+    fn it_should_report_invalid_tokens() {
+        let lexer = TLexer::new(
+            "const a = 5
+            const b = fn() u64 {
+                return a § 2
+            }
+            const c = \"abc
+            const d = 'x",
+        );
+        let mut parser = Parser::new(lexer);
+        let (_, errors) = parser.all();
+        let found: Vec<(&str, usize, usize)> = errors
+            .iter()
+            .map(|x| (x.title.as_str(), x.line, x.col))
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                ("unexpected character '§'", 3, 26),
+                ("unterminated string, it needs a closing \"", 5, 23),
+                ("unterminated char, it needs a closing '", 6, 23),
+            ]
+        );
+    }
+    #[test]
+    fn it_should_parse_extern_fn() {
+        let lexer = TLexer::new("extern const abs = fn(x: i32) i32");
+        let mut parser = Parser::new(lexer);
+        let result = parser.top_decl().unwrap();
+        let Expr::FuncDecl(decl) = *result else {
+            panic!("expected function declaration");
+        };
+        assert_eq!(decl.visibility.unwrap().token, Token::Extern);
+        assert!(decl.block.is_none());
+    }
+    #[test]
     fn it_should_parse_sig() {
         // 1
         let lexer1 = TLexer::new("Error");
@@ -1492,7 +1773,7 @@ mod tests {
                     token: Token::Symbol,
                     span: 19..20
                 }))),
-                expr!(
+                Some(expr!(
                     Sig,
                     Some(expr!(
                         ValueType,
@@ -1505,7 +1786,7 @@ mod tests {
                     None,
                     None,
                     None
-                )
+                ))
             )]),
             expr!(
                 Sig,
@@ -1521,7 +1802,7 @@ mod tests {
                 None,
                 None
             ),
-            expr!(
+            Some(expr!(
                 Block,
                 vec![expr!(
                     RetOp,
@@ -1530,16 +1811,16 @@ mod tests {
                         token: Token::Return,
                         span: 37..43
                     },
-                    expr!(
+                    Some(expr!(
                         Symbol,
                         Lexeme {
                             slice: "x".to_string(),
                             token: Token::Symbol,
                             span: 44..45
                         }
-                    )
+                    ))
                 )]
-            ),
+            )),
             None
         );
         assert_eq!(result.unwrap(), expr);
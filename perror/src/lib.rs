@@ -2,6 +2,7 @@ use std::fmt;
 
 pub type Result<T> = std::result::Result<T, ParserError>;
 pub type ResultFir<T> = std::result::Result<T, FirError>;
+pub type ResultModule<T> = std::result::Result<T, ModuleError>;
 
 #[derive(Debug, PartialEq, Clone)]
 pub struct FirError {
@@ -25,97 +26,147 @@ impl FirError {
     }
 }
 
+#[derive(Debug, PartialEq, Clone)]
+pub struct ModuleError {
+    pub title: String,
+}
+
+impl fmt::Display for ModuleError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", Diagnostic::from(self))
+    }
+}
+
+impl ModuleError {
+    pub fn new(title: String) -> ModuleError {
+        ModuleError { title }
+    }
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Severity {
+    Error,
+    Warning,
+    Note,
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Severity::Error => write!(f, "error"),
+            Severity::Warning => write!(f, "warning"),
+            Severity::Note => write!(f, "note"),
+        }
+    }
+}
+
 #[derive(Debug, PartialEq, Clone)]
 pub struct LinterError {
+    // stable code, see explain
+    pub id: &'static str,
+    pub severity: Severity,
     pub title: String,
     pub points: Vec<LinterErrorPoint>,
     pub suggestions: Vec<String>,
+    // other places that explain the error, like where a name was first declared
+    pub notes: Vec<(LinterErrorPoint, String)>,
 }
 
 impl LinterError {
     pub fn new(title: String) -> LinterError {
         LinterError {
+            id: "",
+            severity: Severity::Error,
             title,
             points: vec![],
             suggestions: vec![],
+            notes: vec![],
         }
     }
+    pub fn with_id(mut self, id: &'static str) -> LinterError {
+        self.id = id;
+        self
+    }
+    pub fn with_severity(mut self, severity: Severity) -> LinterError {
+        self.severity = severity;
+        self
+    }
     pub fn add_point(&mut self, lep: LinterErrorPoint, sug: String) {
         self.suggestions.push(sug);
         self.points.push(lep);
     }
+    pub fn add_note(&mut self, lep: LinterErrorPoint, note: String) {
+        self.notes.push((lep, note));
+    }
 }
 
 #[derive(Debug, PartialEq, Clone)]
 pub struct LinterErrorPoint {
+    pub file: String,
     pub code: String,
+    // the whole line the point is on
+    pub source: String,
     pub line: usize,
     pub col: usize,
+    pub end_line: usize,
+    pub end_col: usize,
 }
 
 impl LinterErrorPoint {
-    pub fn new(code: String, line: usize, col: usize) -> LinterErrorPoint {
-        LinterErrorPoint { code, line, col }
+    pub fn new(
+        file: String,
+        code: String,
+        source: String,
+        line: usize,
+        col: usize,
+        end_line: usize,
+        end_col: usize,
+    ) -> LinterErrorPoint {
+        LinterErrorPoint {
+            file,
+            code,
+            source,
+            line,
+            col,
+            end_line,
+            end_col,
+        }
     }
 }
 
 impl fmt::Display for LinterError {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        let x = self
-            .points
-            .iter()
-            .map(|x| return format!("\t{}\n", x))
-            .collect::<String>();
-        let sug = self
-            .suggestions
-            .iter()
-            .map(|x| return format!("\t{}\n", x))
-            .collect::<String>();
-        write!(
-            f,
-            "title: {}\nerrors:\n{}suggestions: {}",
-            self.title, x, sug
-        )
-    }
-}
-
-impl fmt::Display for LinterErrorPoint {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        let mut x = (0..self.col - 1).map(|_| return " ").collect::<String>();
-        x.push('^');
-        write!(
-            f,
-            "code:\n  {}\n  {}\nline: {}\ncol: {}\n",
-            self.code, x, self.line, self.col
-        )
+        write!(f, "{}", Diagnostic::from(self))
     }
 }
 
 #[derive(Debug, PartialEq, Clone)]
 pub struct ParserError {
+    // stable code, see explain
+    pub id: &'static str,
     pub title: String,
+    pub file: String,
     pub found: String,
     pub code: String,
+    // the whole line the error is on
+    pub source: String,
     pub line: usize,
     pub col: usize,
 }
 
 impl fmt::Display for ParserError {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        let mut x = (0..self.col - 1).map(|_| return " ").collect::<String>();
-        x.push('^');
-        write!(
-            f,
-            "title: {}, but found '{}'\ncode:\n  {}\n  {}\nline: {}\ncol: {}\n",
-            self.title, self.found, self.code, x, self.line, self.col
-        )
+        write!(f, "{}", Diagnostic::from(self))
     }
 }
 
 impl ParserError {
     pub fn new(title: String, code: String, line: usize, col: usize, found: String) -> ParserError {
         ParserError {
+            id: "E0001",
             title,
+            file: "".to_string(),
+            source: code.clone(),
             code,
             line,
             col,
@@ -123,3 +174,690 @@ impl ParserError {
         }
     }
 }
+
+// a labeled place in the source, the primary one is where the error is
+#[derive(Debug, PartialEq, Clone)]
+pub struct Label {
+    pub point: LinterErrorPoint,
+    pub message: String,
+    pub primary: bool,
+}
+
+// what every error and warning is rendered from
+#[derive(Debug, PartialEq, Clone)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    pub id: &'static str,
+    pub title: String,
+    pub labels: Vec<Label>,
+    pub helps: Vec<String>,
+}
+
+const RESET: &str = "\x1b[0m";
+const BOLD: &str = "\x1b[1m";
+const BLUE: &str = "\x1b[1;34m";
+
+impl Diagnostic {
+    pub fn render(&self, color: bool) -> String {
+        let paint = |code: &str, text: &str| match color {
+            true => format!("{}{}{}", code, text, RESET),
+            false => text.to_string(),
+        };
+        let sev = match self.severity {
+            Severity::Error => "\x1b[1;31m",
+            Severity::Warning => "\x1b[1;33m",
+            Severity::Note => "\x1b[1;32m",
+        };
+        let mut out = match self.id.is_empty() {
+            true => paint(sev, &self.severity.to_string()),
+            false => paint(sev, &format!("{}[{}]", self.severity, self.id)),
+        };
+        out.push_str(&paint(BOLD, &format!(": {}", self.title)));
+        out.push('\n');
+
+        let width = self
+            .labels
+            .iter()
+            .map(|x| x.point.line.to_string().len())
+            .max()
+            .unwrap_or(1);
+        let pad = " ".repeat(width);
+        let bar = paint(BLUE, &format!("{} |", pad));
+        let mut file = "";
+        if let Some(x) = self.labels.iter().find(|x| x.primary) {
+            file = &x.point.file;
+            let arrow = paint(BLUE, &format!("{}-->", pad));
+            out.push_str(&format!("{} {}\n", arrow, location(&x.point)));
+        }
+        if !self.labels.is_empty() {
+            out.push_str(&format!("{}\n", bar));
+        }
+        let mut labels: Vec<&Label> = self.labels.iter().collect();
+        labels.sort_by_key(|x| (x.point.file != file, x.point.line, x.point.col));
+        for x in labels {
+            let p = &x.point;
+            if p.file != file {
+                file = &p.file;
+                let more = paint(BLUE, &format!("{}:::", pad));
+                out.push_str(&format!("{} {}\n", more, location(p)));
+            }
+            let gutter = paint(BLUE, &format!("{:>w$} |", p.line, w = width));
+            out.push_str(&format!("{} {}\n", gutter, p.source));
+            // tabs are kept so the marks line up under the code
+            let indent: String = p
+                .source
+                .chars()
+                .take(p.col.saturating_sub(1))
+                .map(|c| if c == '\t' { '\t' } else { ' ' })
+                .collect();
+            let marks = match x.primary {
+                true => paint(sev, &"^".repeat(mark_width(p))),
+                false => paint(BLUE, &"-".repeat(mark_width(p))),
+            };
+            let message = match x.message.is_empty() {
+                true => "".to_string(),
+                false => format!(" {}", x.message),
+            };
+            out.push_str(&format!("{} {}{}{}\n", bar, indent, marks, message));
+        }
+        for x in self.helps.iter() {
+            out.push_str(&format!(
+                "{} help: {}\n",
+                paint(BLUE, &format!("{} =", pad)),
+                x
+            ));
+        }
+        if explain(self.id).is_some() {
+            out.push_str(&format!(
+                "for more information about this, try `ty explain {}`\n",
+                self.id
+            ));
+        }
+        out
+    }
+}
+
+fn location(point: &LinterErrorPoint) -> String {
+    match point.file.is_empty() {
+        true => format!("{}:{}", point.line, point.col),
+        false => format!("{}:{}:{}", point.file, point.line, point.col),
+    }
+}
+
+// the whole range is underlined when it ends on the line it starts on, otherwise up to the end of
+// the line
+fn mark_width(point: &LinterErrorPoint) -> usize {
+    let end = match point.end_line == point.line {
+        true => point.end_col,
+        false => point.source.chars().count() + 1,
+    };
+    end.saturating_sub(point.col).max(1)
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.render(false))
+    }
+}
+
+impl From<&LinterError> for Diagnostic {
+    fn from(le: &LinterError) -> Self {
+        let mut labels: Vec<Label> = le
+            .points
+            .iter()
+            .enumerate()
+            .map(|(i, x)| Label {
+                point: x.clone(),
+                message: "".to_string(),
+                primary: i == 0,
+            })
+            .collect();
+        labels.extend(le.notes.iter().map(|(x, note)| Label {
+            point: x.clone(),
+            message: note.clone(),
+            primary: false,
+        }));
+        Diagnostic {
+            severity: le.severity,
+            id: le.id,
+            title: le.title.clone(),
+            labels,
+            helps: le.suggestions.clone(),
+        }
+    }
+}
+
+impl From<&ParserError> for Diagnostic {
+    fn from(pe: &ParserError) -> Self {
+        let point = LinterErrorPoint::new(
+            pe.file.clone(),
+            pe.code.clone(),
+            pe.source.clone(),
+            pe.line,
+            pe.col,
+            pe.line,
+            pe.col + pe.found.chars().count(),
+        );
+        let message = match pe.found.is_empty() {
+            true => "found the end of the file".to_string(),
+            false => format!("found '{}'", pe.found),
+        };
+        Diagnostic {
+            severity: Severity::Error,
+            id: pe.id,
+            title: pe.title.clone(),
+            labels: vec![Label {
+                point,
+                message,
+                primary: true,
+            }],
+            helps: vec![],
+        }
+    }
+}
+
+// a module that could not be loaded has nothing in it to point at
+impl From<&ModuleError> for Diagnostic {
+    fn from(me: &ModuleError) -> Self {
+        Diagnostic {
+            severity: Severity::Error,
+            id: "",
+            title: me.title.clone(),
+            labels: vec![],
+            helps: vec![],
+        }
+    }
+}
+
+// the function could not be lowered, there is no source location left to point at
+impl From<&FirError> for Diagnostic {
+    fn from(fe: &FirError) -> Self {
+        Diagnostic {
+            severity: Severity::Error,
+            id: "",
+            title: fe.title.clone(),
+            labels: vec![],
+            helps: vec![],
+        }
+    }
+}
+
+// long form help for each error code, printed by `ty explain`
+const EXPLANATIONS: &[(&str, &str)] = &[
+    (
+        "E0001",
+        "The parser found something other than what the grammar allows at this point.
+
+The label points at the token that was found, the title lists what could have been there. Parsing
+carries on after the error, so fixing the first one often fixes the ones after it.",
+    ),
+    (
+        "E0002",
+        "A character that is not part of the language was found.
+
+    const x = 5 § 2
+
+Only ascii letters, digits, whitespace and the operators of the language can appear outside of
+strings, chars and comments.",
+    ),
+    (
+        "E0003",
+        "A string or char is missing its closing quote.
+
+    const greeting = \"hello
+
+Add the closing quote. Strings can span lines, so the error can point at where the string
+started, a long way before where the quote is missing.",
+    ),
+    (
+        "E0100",
+        "The code parses, but the linter or the code generator cannot handle it yet.
+
+This is a limit of the compiler, not a mistake in the code. The suggestion says what is missing,
+rewrite it with what is supported in the meantime.",
+    ),
+    (
+        "E0101",
+        "A name was used that is not declared in this scope, or in any scope around it.
+
+    const main = fn() void {
+        return y
+    }
+
+Declare it before using it, import it from the module that has it, or check the spelling.",
+    ),
+    (
+        "E0102",
+        "A name was declared twice in the same scope.
+
+    const x = 5
+    const x = 6
+
+Each scope can declare a name once. Give the second one another name, or declare it from within a
+block to shadow the first:
+
+    const x = 5
+    {
+        const x = 6
+    }",
+    ),
+    (
+        "E0103",
+        "A property was accessed that the struct does not declare, or a variant that the enum, tag,
+or error does not declare.
+
+    const Point = struct { x: u64 }
+    ...
+    p.y
+
+Check the declaration of the type for the properties or variants it has.",
+    ),
+    (
+        "E0104",
+        "A function was called with more or fewer arguments than it declares.
+
+    const add = fn(x: u64, y: u64) u64 { return x + y }
+    add(1)
+
+Pass exactly one value for each argument in the declaration. A tag variant with a payload is given
+it the same way, `Shape.circle(2)`, and a variant without one takes no parentheses.",
+    ),
+    (
+        "E0105",
+        "A declaration with braces has nothing in them.
+
+    const Point = struct {}
+
+Structs need at least one property, and object literals at least one declarator.",
+    ),
+    (
+        "E0106",
+        "A match does not have an arm for every value it can be given.
+
+    const Day = enum | monday | sunday
+    ...
+    match (d) {
+        Day.monday => 1
+    }
+
+The title names the variants with no arm. Add an arm for each of them, or a `_` arm to take
+everything that is left. Numbers, chars and strings have too many values to list, matching on them
+always needs a `_` arm.",
+    ),
+    (
+        "E0107",
+        "Two variants of an enum have the same value.
+
+    const Day = enum
+      | monday = 1
+      | tuesday = 0
+      | sunday
+
+A variant without a value is one more than the variant before it, so `sunday` is also 1. Give
+each variant its own value.",
+    ),
+    (
+        "E0108",
+        "An enum is backed by a type that is not an integer.
+
+    const Day = enum(f64) | monday | sunday
+
+The values of an enum are integers. Use an integer type like `u8` or `i32`, or leave the type out
+to back the enum with a `u8`.",
+    ),
+    (
+        "E0109",
+        "A variant is given a value that is not an integer literal.
+
+    const Day = enum | monday = 1.5
+
+Variant values are known when compiling. Give the variant an integer like `1` or `-1`.",
+    ),
+    (
+        "E0110",
+        "A match arm gives the payload of a tag variant something other than a name.
+
+    match (s) {
+        Shape.circle(1) => 1
+        _ => 0
+    }
+
+The payload is read into the name for the arm to use, `Shape.circle(r) => r`. Use `_` when the
+payload is not needed, and compare the value inside of the arm.",
+    ),
+    (
+        "E0111",
+        "A value that can never be an error is tried or caught.
+
+    const x = try 5
+
+Only an error union, like `E!T`, holds an error. Remove the `try` or `catch`, or have the function
+that gives the value return an error union.",
+    ),
+    (
+        "E0112",
+        "A `try` is used in a function that cannot return the error.
+
+    const f = fn() u64 {
+        return try check()
+    }
+
+`try` returns the error from the function it is in. Give the function an error union return type,
+like `E!u64`, or handle the error in place with `catch`.",
+    ),
+    (
+        "E0113",
+        "`undefined` is given to something that is not optional.
+
+    const x: u64 = undefined
+
+Only an optional, like `?u64`, can be undefined. Give the binding an optional type, or a value.
+Without a declared type there is nothing for `undefined` to be, so it needs one.",
+    ),
+    (
+        "E0114",
+        "An optional is read before it is known to be defined.
+
+    const f = fn(x: ?u64) u64 {
+        return x + 1
+    }
+
+Check it first, inside of `if (x) { ... }` x is what it holds. Or pass undefined on to the caller
+with `x?`, in a function that returns an optional.",
+    ),
+    (
+        "E0115",
+        "`?` is used on a value that can never be undefined.
+
+    const f = fn(x: u64) ?u64 {
+        return x?
+    }
+
+Only an optional, like `?T`, can be given on with `?`. Remove it.",
+    ),
+    (
+        "E0116",
+        "`?` is used in a function that cannot return undefined.
+
+    const f = fn(x: ?u64) u64 {
+        return x?
+    }
+
+`?` returns undefined from the function it is in. Give the function an optional return type,
+like `?u64`, or check the value in place with `if (x)`.",
+    ),
+    (
+        "E0117",
+        "A global is given a value that is not known at compile time.
+
+    let y: u64 = 3
+    const x: u64 = y
+
+Globals are written into the object, so their values are worked out when compiling. Use literals,
+`const` globals, and `+`, `-`, and `*` on them. A char in a global has to fit in a byte. Compute
+anything else within a function.",
+    ),
+    (
+        "E0118",
+        "An `if` gives a value, but has no `else` to give one when its condition is false.
+
+    const z = if (x > 2) {
+        break 10
+    }
+
+Add an `else` branch that breaks with a value too, or write the `if` on its own as a statement.",
+    ),
+    (
+        "E0200",
+        "A value was used where a value of another type is expected.
+
+    const x: u64 = \"five\"
+
+The suggestion shows the type that is expected and the one that was found. Convert the value
+with `as` when the conversion is valid, or change one of the types.",
+    ),
+    (
+        "E0201",
+        "A value was cast with `as` to a type it cannot be converted to.
+
+Numbers can be cast to other numbers. Other casts, like a string to a number, need a function
+that does the conversion.",
+    ),
+    (
+        "E0202",
+        "Negation was used on something that cannot be negated, or twice in a row.
+
+    const x = --5
+
+Only numbers can be negated. There is no decrement operator, write `x - 1` instead.",
+    ),
+    (
+        "E0203",
+        "A number literal does not fit in the type it has.
+
+    const x: u8 = 300
+
+Use a wider type, or a value in the range of the one declared.",
+    ),
+    (
+        "E0204",
+        "A string or char has an escape the language does not know.
+
+The valid escapes are \\n, \\t, \\r, \\0, \\\\, \\', \\\" and \\`.",
+    ),
+    (
+        "E0205",
+        "A `for` loop was given something other than an array, a string, or a range.
+
+    const x: u64 = 5
+    for (x) fn(i) void {}
+
+A `for` runs its body once for each element of an array, or each number of a range. To count up
+to a number, loop over a range like `0..x`.",
+    ),
+    (
+        "E0300",
+        "A value was used after it was moved somewhere else.
+
+    const a = Point { x: 1 }
+    const b = a
+    return a.x
+
+Once a value is moved, the binding it was moved from is no longer usable. Use `copy a` to keep
+both, or borrow it with `&a`.",
+    ),
+    (
+        "E0301",
+        "A global was moved out of.
+
+Globals live for the whole program, so they cannot be given away. Borrow it with `&`, or use
+`copy` to get a value of your own.",
+    ),
+    (
+        "E0302",
+        "A value was moved while something still borrows it.
+
+    const a = Point { x: 1 }
+    const r = &a
+    const b = a
+
+The borrow would point at nothing once the value is moved. Move it after the last use of the
+borrow.",
+    ),
+    (
+        "E0303",
+        "A value was borrowed in a way that conflicts with how it is already borrowed.
+
+A value can have many `&` borrows, or a single `*` borrow, but not both at once. A value behind a
+`&` borrow cannot be borrowed with `*`.",
+    ),
+    (
+        "E0304",
+        "A value was changed through a `&` borrow.
+
+`&` borrows are read only. Borrow it with `*` to change it.",
+    ),
+    (
+        "E0305",
+        "A value was changed while something borrows it.
+
+The borrow would see the value change under it. Change it after the last use of the borrow.",
+    ),
+    (
+        "E0306",
+        "A borrow of a local was returned or stored somewhere that outlives the local.
+
+    const get = fn() &Point {
+        const p = Point { x: 1 }
+        return &p
+    }
+
+The local is dropped when its scope ends. Give it by value instead.",
+    ),
+    (
+        "E0307",
+        "Something immutable was changed.
+
+    const x = 5
+    x = 6
+
+Only bindings declared with `let` can be reassigned, and arguments can only be changed when their
+type is marked `let`. Elements of `[const T]` arrays are never changed.",
+    ),
+    (
+        "E0400",
+        "An import is not written as a path.
+
+Imports are written as a string of the module and the symbol, `\"file.symbol\"`.",
+    ),
+    (
+        "E0401",
+        "An import names a module that cannot be found.
+
+Module `a.b` is read from `a/b.ty`, relative to the file that imports it.",
+    ),
+    (
+        "E0402",
+        "An import names an item that the module does not make public.
+
+Mark the item `pub` in the module it is declared in.",
+    ),
+    (
+        "E0403",
+        "Data was marked `extern`.
+
+Only functions can be `extern`. Use `pub` to make data visible to other modules.",
+    ),
+    (
+        "W0001",
+        "A local was declared but never read.
+
+Remove it if it is not needed. This is a warning, the file still compiles.",
+    ),
+    (
+        "W0002",
+        "A match arm can never be taken.
+
+    match (d) {
+        Day.monday => 1
+        _ => 2
+        Day.sunday => 3
+    }
+
+Arms are tried in order, so an arm after `_`, or after every variant is matched, or with a variant
+an arm above already has, is never reached. Remove it, or move it above the arm that takes its
+values.",
+    ),
+];
+
+pub fn explain(id: &str) -> Option<&'static str> {
+    EXPLANATIONS
+        .iter()
+        .find(|(x, _)| *x == id)
+        .map(|(_, text)| *text)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeSet;
+    #[test]
+    fn it_should_render_diagnostics() {
+        let mut le =
+            LinterError::new("x is already declared in this scope".to_string()).with_id("E0102");
+        let point = |line, col, source: &str| {
+            LinterErrorPoint::new(
+                "main.ty".to_string(),
+                "".to_string(),
+                source.to_string(),
+                line,
+                col,
+                line,
+                col + 1,
+            )
+        };
+        le.add_point(
+            point(12, 8, "\tconst x = 6"),
+            "give it another name".to_string(),
+        );
+        le.add_note(
+            point(9, 8, "\tconst x = 5"),
+            "previously declared here".to_string(),
+        );
+        assert_eq!(
+            Diagnostic::from(&le).render(false),
+            "error[E0102]: x is already declared in this scope
+  --> main.ty:12:8
+   |
+ 9 | \tconst x = 5
+   | \t      - previously declared here
+12 | \tconst x = 6
+   | \t      ^
+   = help: give it another name
+for more information about this, try `ty explain E0102`
+"
+        );
+        assert!(Diagnostic::from(&le).render(true).contains("\x1b[1;31m"));
+    }
+    #[test]
+    fn it_should_mark_chars_not_bytes() {
+        let mut le = LinterError::new("cannot find é in this scope".to_string());
+        le.add_point(
+            LinterErrorPoint::new(
+                "".to_string(),
+                "".to_string(),
+                "const ü = é + ü".to_string(),
+                1,
+                11,
+                3,
+                1,
+            ),
+            "declare é first".to_string(),
+        );
+        assert_eq!(
+            Diagnostic::from(&le).render(false),
+            "error: cannot find é in this scope
+ --> 1:11
+  |
+1 | const ü = é + ü
+  |           ^^^^^
+  = help: declare é first
+"
+        );
+    }
+    #[test]
+    fn it_should_render_module_errors() {
+        let me = ModuleError::new("import cycle: a -> b -> a".to_string());
+        assert_eq!(
+            Diagnostic::from(&me).render(false),
+            "error: import cycle: a -> b -> a\n"
+        );
+    }
+    #[test]
+    fn it_should_explain_every_code_once() {
+        let ids: BTreeSet<&str> = EXPLANATIONS.iter().map(|x| x.0).collect();
+        assert_eq!(ids.len(), EXPLANATIONS.len());
+        assert!(explain("E0102").unwrap().contains("shadow"));
+        assert_eq!(explain("E9999"), None);
+    }
+}
@@ -2,6 +2,7 @@ use cranelift_frontend::FunctionBuilderContext;
 use datatable::DataTable;
 use fir::Fir;
 use oir::Oir;
+use perror::ResultFir;
 use scopetable::ScopeTable;
 use symtable::SymTable;
 use types::TypeTree;
@@ -37,13 +38,38 @@ impl Scir {
         }
     }
     // top_res is the output top decls of the linter
-    pub fn loopf(&mut self, top_res: Vec<u32>) -> () {
+    pub fn loopf(&mut self, top_res: Vec<u32>) -> ResultFir<()> {
+        for item in top_res.iter() {
+            match self.types.get(*item as usize).unwrap() {
+                TypeTree::FuncInit(fi) => {
+                    let sig = self.fir.signature(fi, &self.oir);
+                    self.oir.declare_fn(&fi.name, fi.vis, fi.export, &sig);
+                }
+                TypeTree::ExternInit(ext) => {
+                    let sig = self.fir.extern_signature(ext, &self.oir);
+                    self.oir.declare_import(&ext.name, &sig);
+                }
+                TypeTree::ImportInit(imp) if imp.args_curried.is_some() => {
+                    let sig = self.fir.import_signature(imp, &self.oir);
+                    self.oir.import_fn(&imp.name, &imp.symbol, &sig);
+                }
+                TypeTree::ImportInit(imp) => {
+                    self.oir.import_data(imp, &mut self.dtable);
+                }
+                _ => (),
+            }
+        }
         for item in top_res {
             let tt = self.types.get(item as usize).unwrap();
             match tt {
-                TypeTree::TopConstInit(ci) => {
-                    self.oir.const_init(&ci, &mut self.dtable, &self.types);
-                }
+                TypeTree::TopConstInit(ci) => self
+                    .oir
+                    .const_init(&ci, false, &mut self.dtable, &self.scopes, &self.types)
+                    .unwrap_or_else(|x| panic!("developer error, unchecked global, {}", x)),
+                TypeTree::TopMutInit(ci) => self
+                    .oir
+                    .const_init(&ci, true, &mut self.dtable, &self.scopes, &self.types)
+                    .unwrap_or_else(|x| panic!("developer error, unchecked global, {}", x)),
                 TypeTree::FuncInit(fi) => {
                     self.fir.refresh();
                     let _fn = self.fir.run(
@@ -55,13 +81,19 @@ impl Scir {
                         &self.scopes,
                         &self.types,
                         &mut self.oir,
-                    );
+                    )?;
                     self.index += 1;
                     self.oir.add_fn(&fi.name, _fn);
                 }
+                TypeTree::ExternInit(_) => (),
+                TypeTree::ImportInit(_) => (),
+                TypeTree::StructInfo(_) => (),
+                // enums, tags, and errors are only types, their variants are constants
+                TypeTree::EnumInfo(_) | TypeTree::TagInfo(_) | TypeTree::ErrorInfo(_) => (),
                 _ => panic!("developer error, unhandled loopfval, {:?}", item),
             }
         }
+        Ok(())
     }
     pub fn flush_self(self) -> Vec<u8> {
         return self.oir.flush_self();
@@ -16,18 +16,19 @@ impl ScopeTable {
             this_scope,
         }
     }
+    // the closest declaration of symbol, from this scope out through every enclosing one.
+    // a declaration in an inner scope shadows any outer one of the same name
     pub fn get_tt_idx_same_up(&self, symbol: &str, scopes: &Vec<ScopeTable>) -> Option<u32> {
-        let sibling = self.this_tree.get(symbol);
-        if let Some(sib) = sibling {
-            return Some(*sib);
-        }
-        if self.parent_scope < self.this_scope {
-            let ptbl = scopes.get(self.parent_scope as usize).unwrap();
-            let parent = ptbl.this_tree.get(symbol);
-            if let Some(par) = parent {
-                return Some(*par);
+        let mut tbl = self;
+        loop {
+            if let Some(x) = tbl.this_tree.get(symbol) {
+                return Some(*x);
+            }
+            // the global scope is its own parent
+            if tbl.parent_scope >= tbl.this_scope {
+                return None;
             }
+            tbl = scopes.get(tbl.parent_scope as usize)?;
         }
-        None
     }
 }
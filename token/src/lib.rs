@@ -271,6 +271,13 @@ pub enum Token {
     #[regex(r#"[']([^'\\\n]|\\.|\\\n)*[']"#)]
     #[regex(r#"[`]([^`\\\n]|\\.|\\\n)*[`]"#)]
     Chars,
+    // a string or char missing its closing quote before the end of the line
+    #[regex(r#"["]([^"\\\n]|\\.|\\\n)*"#)]
+    #[regex(r#"[']([^'\\\n]|\\.|\\\n)*"#)]
+    #[regex(r#"[`]([^`\\\n]|\\.|\\\n)*"#)]
+    Unterminated,
+    // anything that isn't a token, only made by the lexer
+    Invalid,
 
     #[regex("[1-9][0-9]*\\.[0-9]+|0\\.[0-9]+|0|[1-9][0-9]*")]
     Number,
@@ -306,6 +313,14 @@ mod tests {
         assert_eq!(lexer.next(), Some(Ok(Token::Number)));
     }
     #[test]
+    fn it_tokenizes_unterminated() {
+        let mut lexer = Token::lexer("\"done\" \"open\n'x");
+        assert_eq!(lexer.next(), Some(Ok(Token::Chars)));
+        assert_eq!(lexer.next(), Some(Ok(Token::Unterminated)));
+        assert_eq!(lexer.slice(), "\"open");
+        assert_eq!(lexer.next(), Some(Ok(Token::Unterminated)));
+    }
+    #[test]
     fn it_tokenizes_rest() {
         let mut lexer = Token::lexer("let _ = x_b");
         assert_eq!(lexer.next(), Some(Ok(Token::Let)));
@@ -361,6 +376,6 @@ mod tests {
         let mut lexer2 = Token::lexer(r#"'\\'"#);
         assert_eq!(lexer2.next(), Some(Ok(Token::Chars)));
         let mut lexer3 = Token::lexer(r#"'unterminated string"#);
-        assert_eq!(lexer3.next(), Some(Err(())));
+        assert_eq!(lexer3.next(), Some(Ok(Token::Unterminated)));
     }
 }
@@ -12,6 +12,7 @@ use clap::Command;
 const TY: &str = "ty";
 const OBJ: &str = "obj";
 const LINK: &str = "link";
+const EXPLAIN: &str = "explain";
 
 fn main() {
     let sub_o = Command::new(OBJ).about("generates object files from .ty files").arg(
@@ -19,12 +20,16 @@ fn main() {
     );
     let link_o = Command::new(LINK).about("generates an executable from .o files").arg(arg!([name] "output name of the binary")).arg(
         arg!(-o --objects <FILES> "path from the current working directory where the .o files are located, provide a comma delimited list").action(ArgAction::Append).value_parser(value_parser!(PathBuf)).value_delimiter(','));
+    let explain_o = Command::new(EXPLAIN)
+        .about("explains an error code in detail")
+        .arg(arg!([code] "the error code, like E0102"));
     let matches = Command::new(TY)
         .bin_name(TY)
         .arg_required_else_help(true)
         .arg(arg!(-v --version "gets the current version of ty"))
         .subcommand(sub_o)
         .subcommand(link_o)
+        .subcommand(explain_o)
         .get_matches();
 
     if matches.get_flag("version") {
@@ -36,6 +41,25 @@ fn main() {
     if let Some(link) = matches.subcommand_matches(LINK) {
         link_command(link);
     }
+    if let Some(explain) = matches.subcommand_matches(EXPLAIN) {
+        explain_command(explain);
+    }
+}
+
+fn explain_command(m: &ArgMatches) {
+    let code = m.get_one::<String>("code");
+    if code.is_none() {
+        eprintln!("expected an error code.\n`ty explain [code]`.\ntry `ty explain --help`");
+        exit(1);
+    }
+    let code = code.unwrap().to_uppercase();
+    match perror::explain(&code) {
+        Some(x) => println!("{}", x),
+        None => {
+            eprintln!("no explanation for {}", code);
+            exit(1);
+        }
+    }
 }
 
 fn obj_command(m: &ArgMatches) {
@@ -45,9 +69,7 @@ fn obj_command(m: &ArgMatches) {
         exit(1);
     }
     let files: Vec<_> = pre.unwrap().collect();
-    for ele in files.into_iter() {
-        objmaker::from_file(ele);
-    }
+    objmaker::from_files(files);
 }
 
 fn link_command(m: &ArgMatches) {
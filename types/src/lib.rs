@@ -23,8 +23,10 @@ pub struct SingleType {
 
 #[derive(Debug)]
 pub struct ErrorInfo {
+    pub name: String,
     pub message: String,
     pub code: usize,
+    pub props: Vec<TypeTreeIndex>,
     pub curried: Ty,
 }
 
@@ -40,6 +42,9 @@ pub struct TagInfo {
 pub struct EnumInfo {
     pub name: String,
     pub props: Vec<TypeTreeIndex>,
+    // the integer type the values are checked against, and the value of each variant
+    pub backing: Ty,
+    pub values: Vec<i128>,
     pub curried: Ty,
     pub child_scope: u32,
 }
@@ -93,6 +98,8 @@ pub struct IfOp {
     pub in_curried: Ty,
     pub body: TypeTreeIndex,
     pub body_curried: Ty,
+    pub else_body: Option<TypeTreeIndex>,
+    pub else_curried: Option<Ty>,
 }
 
 #[derive(Debug)]
@@ -150,6 +157,32 @@ pub struct PropAccess {
     pub curried: Ty,
 }
 
+// a variant of an enum, tag, or error named through its type, `Day.monday`
+#[derive(Debug)]
+pub struct VariantAccess {
+    pub owner: String,
+    pub ident: String,
+    pub curried: Ty,
+}
+
+// a tag variant given its payload, `Shape.circle(2.0)`. in a match arm the payload is the
+// binding it is read into, `Shape.circle(r) => r`
+#[derive(Debug)]
+pub struct VariantInitialize {
+    pub owner: String,
+    pub ident: String,
+    pub payload: Option<TypeTreeIndex>,
+    pub curried: Ty,
+}
+
+// `x catch (e) { ... }` gives the value of x, or runs the handler with the error when x is one
+#[derive(Debug)]
+pub struct CatchOp {
+    pub expr: TypeTreeIndex,
+    pub handler: TypeTreeIndex,
+    pub curried: Ty,
+}
+
 #[derive(Debug)]
 pub struct SymbolInit {
     pub ident: String,
@@ -191,6 +224,36 @@ pub struct FunctionInitialize {
     pub args_curried: Vec<Ty>,
     pub block: TypeTreeIndex,
     pub block_curried: Ty,
+    pub ret_curried: Ty,
+    // exported functions use the c calling convention
+    pub export: bool,
+    // only visible functions are linked to from other objects
+    pub vis: bool,
+}
+
+#[derive(Debug)]
+pub struct ExternInitialize {
+    pub name: String,
+    pub args_curried: Vec<Ty>,
+    pub ret_curried: Ty,
+}
+
+// a function or data defined in another module, linked to by its name in that module.
+// functions have args
+#[derive(Debug)]
+pub struct ImportInitialize {
+    pub name: String,
+    pub symbol: String,
+    pub args_curried: Option<Vec<Ty>>,
+    pub curried: Ty,
+    pub export: bool,
+    pub writable: bool,
+}
+
+// the name a pub item is linked by, the same as its import path. two modules can then have pub
+// items of the same name. exported functions keep their own name, since c calls them by it
+pub fn mangle(module: &str, name: &str) -> String {
+    format!("{}.{}", module, name)
 }
 
 #[derive(Debug)]
@@ -235,6 +298,7 @@ pub enum TypeTree {
     // unops
     BubbleUndef(UnaryOp),
     BubbleError(UnaryOp),
+    Catch(CatchOp),
     ReadBorrow(UnaryOp),
     MutBorrow(UnaryOp),
     Copy(UnaryOp),
@@ -247,15 +311,19 @@ pub enum TypeTree {
     SymbolAccess(SymbolAccess),
     RestAccess(NoOp),
     SelfAccess(NoOp),
+    VariantAccess(VariantAccess),
     // data types
     ArgInit(SymbolInit),
     SelfInit(NoOp),
     SymbolInit(SymbolInit),
     StructInit(StructInitialize),
+    VariantInit(VariantInitialize),
     PropInit(Initialization),
     ArrayInit(ArrayInitialize),
     FuncInit(FunctionInitialize),
     AnonFuncInit(FunctionInitialize),
+    ExternInit(ExternInitialize),
+    ImportInit(ImportInitialize),
     ConstInit(Initialization),
     TopConstInit(TopInitialization),
     MutInit(Initialization),
@@ -320,6 +388,7 @@ impl TypeTree {
             TypeTree::CastAs(x) => x.curried.clone(),
             TypeTree::BubbleUndef(x) => x.curried.clone(),
             TypeTree::BubbleError(x) => x.curried.clone(),
+            TypeTree::Catch(x) => x.curried.clone(),
             TypeTree::ReadBorrow(x) => x.curried.clone(),
             TypeTree::MutBorrow(x) => x.curried.clone(),
             TypeTree::Copy(x) => x.curried.clone(),
@@ -330,12 +399,16 @@ impl TypeTree {
             TypeTree::ArrayAccess(x) => x.curried.clone(),
             TypeTree::SymbolAccess(x) => x.curried.clone(),
             TypeTree::RestAccess(x) => x.curried.clone(),
+            TypeTree::VariantAccess(x) => x.curried.clone(),
             TypeTree::SelfAccess(x) => x.curried.clone(),
             TypeTree::StructInit(x) => x.curried.clone(),
+            TypeTree::VariantInit(x) => x.curried.clone(),
             TypeTree::PropInit(x) => x.curried.clone(),
             TypeTree::ArrayInit(x) => x.curried.clone(),
-            TypeTree::FuncInit(x) => x.block_curried.clone(),
-            TypeTree::AnonFuncInit(x) => x.block_curried.clone(),
+            TypeTree::FuncInit(x) => x.ret_curried.clone(),
+            TypeTree::AnonFuncInit(x) => x.ret_curried.clone(),
+            TypeTree::ExternInit(x) => x.ret_curried.clone(),
+            TypeTree::ImportInit(x) => x.curried.clone(),
             TypeTree::ConstInit(x) => x.curried.clone(),
             TypeTree::MutInit(x) => x.curried.clone(),
             TypeTree::TopConstInit(x) => x.curried.clone(),
@@ -356,9 +429,9 @@ impl TypeTree {
             TypeTree::BoolValue(_) => Ty::Bool,
             TypeTree::I64(_) => Ty::I64,
             TypeTree::I32(_) => Ty::I32,
-            TypeTree::U64(_) => Ty::U64,
+            TypeTree::U64(_) => Ty::IntLiteral,
             TypeTree::U32(_) => Ty::U32,
-            TypeTree::F64(_) => Ty::F64,
+            TypeTree::F64(_) => Ty::FloatLiteral,
             TypeTree::Char(_) => Ty::Char,
             TypeTree::UnknownValue => Ty::Unknown,
             TypeTree::ArgInit(x) => x.curried.clone(),
@@ -398,6 +471,12 @@ impl TypeTree {
             _ => panic!("issue symbol not found"),
         }
     }
+    pub fn into_arg_init(&self) -> &SymbolInit {
+        match self {
+            TypeTree::ArgInit(x) => x,
+            _ => panic!("issue argument not found"),
+        }
+    }
     pub fn into_child_scope(&self) -> u32 {
         match self {
             TypeTree::StructInfo(x) => x.child_scope,
@@ -445,6 +524,7 @@ impl TypeTree {
             TypeTree::CastAs(_) => "cast",
             TypeTree::BubbleUndef(_) => "undefinded bubble",
             TypeTree::BubbleError(_) => "try error bubble",
+            TypeTree::Catch(_) => "catch",
             TypeTree::ReadBorrow(_) => "read borrow",
             TypeTree::MutBorrow(_) => "mutable borrow",
             TypeTree::Copy(_) => "unsized copy",
@@ -455,12 +535,16 @@ impl TypeTree {
             TypeTree::ArrayAccess(_) => "array index access",
             TypeTree::SymbolAccess(_) => "symbol reference",
             TypeTree::RestAccess(_) => "rest access",
+            TypeTree::VariantAccess(_) => "variant access",
             TypeTree::SelfAccess(_) => "self reference",
             TypeTree::StructInit(_) => "struct initialization",
+            TypeTree::VariantInit(_) => "variant initialization",
             TypeTree::PropInit(_) => "property assignment",
             TypeTree::ArrayInit(_) => "array initialization",
             TypeTree::FuncInit(_) => "function initialization",
             TypeTree::AnonFuncInit(_) => "anonymous function initialization",
+            TypeTree::ExternInit(_) => "extern function declaration",
+            TypeTree::ImportInit(_) => "import",
             TypeTree::ConstInit(_) => "constant initialization",
             TypeTree::MutInit(_) => "mutable initialization",
             TypeTree::TopConstInit(_) => "constant initialization",
@@ -507,12 +591,24 @@ pub enum Ty {
     Scalar,
     I64,
     I32,
+    I16,
+    I8,
     ISize,
     U64,
     USize,
     U32,
+    U16,
     U8,
+    Bit,
+    F128,
     F64,
+    F32,
+    D128,
+    D64,
+    D32,
+    // literals without a type yet, they take on the type of where they are used
+    IntLiteral,
+    FloatLiteral,
     Unknown,
     Rest,
     Undefined,
@@ -520,6 +616,10 @@ pub enum Ty {
     Never,
     Bool,
     Char,
+    Utf8,
+    Utf16,
+    Utf32,
+    Utf64,
     String,
     Const(Box<Ty>),
     Mut(Box<Ty>),
@@ -551,6 +651,8 @@ impl fmt::Display for Ty {
             Ty::USize => write!(f, "usize"),
             Ty::U32 => write!(f, "u32"),
             Ty::F64 => write!(f, "f64"),
+            Ty::IntLiteral => write!(f, "{{integer}}"),
+            Ty::FloatLiteral => write!(f, "{{float}}"),
             Ty::Unknown => write!(f, "unknown"),
             Ty::Rest => write!(f, "_"),
             Ty::Undefined => write!(f, "undefined"),
@@ -582,6 +684,10 @@ impl fmt::Display for Ty {
             }
             Ty::Error => write!(f, "error"),
             Ty::CustomError(x) => write!(f, "error {}", x),
+            Ty::Tag(x) if x.len() == 2 && x.contains(&Ty::Undefined) => {
+                let some = x.iter().find(|t| **t != Ty::Undefined).unwrap();
+                write!(f, "?{}", some)
+            }
             Ty::Tag(x) => {
                 write!(f, "tag ").unwrap();
                 for a in x {
@@ -602,32 +708,134 @@ impl fmt::Display for Ty {
             Ty::Trait(x) => write!(f, "trait {}", x),
             Ty::TSelf => write!(f, "self"),
             Ty::U8 => write!(f, "u8"),
+            Ty::I16 => write!(f, "i16"),
+            Ty::I8 => write!(f, "i8"),
+            Ty::U16 => write!(f, "u16"),
+            Ty::Bit => write!(f, "bit"),
+            Ty::F128 => write!(f, "f128"),
+            Ty::F32 => write!(f, "f32"),
+            Ty::D128 => write!(f, "d128"),
+            Ty::D64 => write!(f, "d64"),
+            Ty::D32 => write!(f, "d32"),
+            Ty::Utf8 => write!(f, "utf8"),
+            Ty::Utf16 => write!(f, "utf16"),
+            Ty::Utf32 => write!(f, "utf32"),
+            Ty::Utf64 => write!(f, "utf64"),
             Ty::Enum(x) => write!(f, "enum({})", x),
         }
     }
 }
 
 impl Ty {
+    // only what is declared with let, or reached through a * borrow, can be changed
     pub fn ensure_mut(&self) -> Result<(), Ty> {
         match self {
-            Ty::I64 => Err(Ty::I64),
-            Ty::I32 => Err(Ty::I32),
-            Ty::U64 => Err(Ty::U64),
-            Ty::Const(val) => Err(Ty::Const(val.to_owned())),
-            Ty::Mut(_) => Ok(()),
-            Ty::ReadBorrow(val) => Err(Ty::ReadBorrow(val.to_owned())),
-            Ty::MutBorrow(_) => Ok(()),
-            Ty::Void => Err(Ty::Void),
-            Ty::Error => Ok(()),
-            Ty::Unknown => Ok(()),
-            Ty::Undefined => Ok(()),
-            _ => panic!("type lang issue. type not able to be associated to const"),
+            Ty::Mut(_) | Ty::MutBorrow(_) => Ok(()),
+            // already reported
+            Ty::Error | Ty::Unknown | Ty::Undefined => Ok(()),
+            x => Err(x.clone()),
+        }
+    }
+    pub fn is_signed(&self) -> bool {
+        match self {
+            Ty::I64 | Ty::I32 | Ty::I16 | Ty::I8 | Ty::ISize => true,
+            Ty::Const(x) | Ty::Mut(x) => x.is_signed(),
+            Ty::ReadBorrow(x) | Ty::MutBorrow(x) => x.is_signed(),
+            _ => false,
+        }
+    }
+    pub fn is_int(&self) -> bool {
+        matches!(
+            self,
+            Ty::I64
+                | Ty::I32
+                | Ty::I16
+                | Ty::I8
+                | Ty::ISize
+                | Ty::U64
+                | Ty::U32
+                | Ty::U16
+                | Ty::U8
+                | Ty::USize
+                | Ty::Bit
+        )
+    }
+    pub fn is_float(&self) -> bool {
+        matches!(self, Ty::F128 | Ty::F64 | Ty::F32)
+    }
+    pub fn is_decimal(&self) -> bool {
+        matches!(self, Ty::D128 | Ty::D64 | Ty::D32)
+    }
+    // chars are utf8 code units
+    pub fn is_utf(&self) -> bool {
+        matches!(
+            self,
+            Ty::Char | Ty::Utf8 | Ty::Utf16 | Ty::Utf32 | Ty::Utf64
+        )
+    }
+    // the width of a number or char in bits
+    pub fn bits(&self) -> Option<u32> {
+        match self {
+            Ty::Bit => Some(1),
+            Ty::I8 | Ty::U8 | Ty::Char | Ty::Utf8 => Some(8),
+            Ty::I16 | Ty::U16 | Ty::Utf16 => Some(16),
+            Ty::I32 | Ty::U32 | Ty::F32 | Ty::D32 | Ty::Utf32 => Some(32),
+            Ty::I64 | Ty::U64 | Ty::ISize | Ty::USize => Some(64),
+            Ty::F64 | Ty::D64 | Ty::Utf64 => Some(64),
+            Ty::F128 | Ty::D128 => Some(128),
+            _ => None,
+        }
+    }
+    // the smallest and largest integer a value of this type can hold
+    pub fn int_range(&self) -> Option<(i128, i128)> {
+        if !self.is_int() && !self.is_utf() {
+            return None;
+        }
+        let bits = self.bits()?;
+        match self.is_signed() {
+            true => Some((-(1 << (bits - 1)), (1 << (bits - 1)) - 1)),
+            false => Some((0, (1 << bits) - 1)),
+        }
+    }
+    pub fn is_error(&self) -> bool {
+        matches!(self, Ty::Error | Ty::CustomError(_))
+    }
+    // the type held when there is no error, for an error union like E!T
+    pub fn error_union(&self) -> Option<Ty> {
+        match self {
+            Ty::Const(x) | Ty::Mut(x) | Ty::ReadBorrow(x) | Ty::MutBorrow(x) => x.error_union(),
+            Ty::Tag(x) if x.iter().any(|t| t.is_error()) => {
+                let mut ok: Vec<Ty> = x.iter().filter(|t| !t.is_error()).cloned().collect();
+                match ok.len() {
+                    0 => Some(Ty::Void),
+                    1 => ok.pop(),
+                    _ => Some(Ty::Tag(ok)),
+                }
+            }
+            _ => None,
+        }
+    }
+    // the type held when it is not undefined, for an optional like ?T. a borrow of an optional is
+    // a pointer, and always defined
+    pub fn optional(&self) -> Option<Ty> {
+        match self {
+            Ty::Const(x) | Ty::Mut(x) => x.optional(),
+            Ty::Tag(x) if x.contains(&Ty::Undefined) => {
+                let mut some: Vec<Ty> =
+                    x.iter().filter(|t| **t != Ty::Undefined).cloned().collect();
+                match some.len() {
+                    0 => Some(Ty::Void),
+                    1 => some.pop(),
+                    _ => Some(Ty::Tag(some)),
+                }
+            }
+            _ => None,
         }
     }
-    pub fn into_vec(&mut self) -> &mut Vec<Ty> {
+    pub fn into_vec(&mut self) -> Option<&mut Vec<Ty>> {
         match self {
-            Ty::Tag(x) => x,
-            _ => panic!("type lang issue. unhandled match arm"),
+            Ty::Tag(x) => Some(x),
+            _ => None,
         }
     }
 }